@@ -0,0 +1,120 @@
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyString};
+
+use django_rusty_templates::DEFAULT_MAX_DEPTH;
+use django_rusty_templates::django_rusty_templates::{Engine, Template};
+
+/// An engine with the default value for every option.
+fn engine(py: Python<'_>) -> Engine {
+    Engine::new(
+        py,
+        None,
+        false,
+        None,
+        false,
+        None,
+        "".to_string(),
+        "utf-8".to_string(),
+        None,
+        None,
+        true,
+        false,
+        false,
+        DEFAULT_MAX_DEPTH,
+        false,
+    )
+    .unwrap()
+}
+
+fn text_heavy_template() -> String {
+    let paragraph = "Lorem ipsum dolor sit amet, consectetur adipiscing elit. ";
+    (0..50)
+        .map(|i| format!("{paragraph}{{{{ name }}}} has {{{{ items|length }}}} items ({i}).\n"))
+        .collect()
+}
+
+fn bench_text_heavy(c: &mut Criterion) {
+    Python::initialize();
+
+    Python::attach(|py| {
+        let engine = engine(py);
+        let template_code = PyString::new(py, &text_heavy_template());
+        let template = engine.from_string(template_code, None).unwrap();
+        assert!(template.fast_path);
+        let slow_template = Template {
+            fast_path: false,
+            ..template.clone()
+        };
+
+        let context = PyDict::new(py);
+        context.set_item("name", "Lily").unwrap();
+        context.set_item("items", vec!["a", "b", "c"]).unwrap();
+
+        let mut group = c.benchmark_group("text_heavy");
+        for (name, template) in [("fast_path", &template), ("full", &slow_template)] {
+            group.bench_function(BenchmarkId::from_parameter(name), |b| {
+                b.iter(|| template.render(py, Some(context.clone()), None).unwrap())
+            });
+        }
+        group.finish();
+    })
+}
+
+fn empty_conditionals_template() -> String {
+    (0..200)
+        .map(|i| format!("{{% if flag %}}{{% endif %}}{{% if flag %}}{{% else %}}{i}{{% endif %}}"))
+        .collect()
+}
+
+fn bench_empty_conditionals(c: &mut Criterion) {
+    Python::initialize();
+
+    Python::attach(|py| {
+        let engine = engine(py);
+        let template_code = PyString::new(py, &empty_conditionals_template());
+        let template = engine.from_string(template_code, None).unwrap();
+
+        let mut group = c.benchmark_group("empty_conditionals");
+        for flag in [true, false] {
+            let context = PyDict::new(py);
+            context.set_item("flag", flag).unwrap();
+            group.bench_function(BenchmarkId::from_parameter(flag), |b| {
+                b.iter(|| template.render(py, Some(context.clone()), None).unwrap())
+            });
+        }
+        group.finish();
+    })
+}
+
+fn bench_change_case(c: &mut Criterion) {
+    Python::initialize();
+
+    Python::attach(|py| {
+        let engine = engine(py);
+        let template_code = PyString::new(py, "{{ text|lower }}{{ text|upper }}");
+        let template = engine.from_string(template_code, None).unwrap();
+
+        let paragraph = "lorem ipsum dolor sit amet, consectetur adipiscing elit. ".repeat(200);
+        let mut group = c.benchmark_group("change_case");
+        for (name, text) in [
+            ("lowercase", paragraph.clone()),
+            ("mixed_case", paragraph.replace('l', "L")),
+        ] {
+            let context = PyDict::new(py);
+            context.set_item("text", text).unwrap();
+            group.bench_function(BenchmarkId::from_parameter(name), |b| {
+                b.iter(|| template.render(py, Some(context.clone()), None).unwrap())
+            });
+        }
+        group.finish();
+    })
+}
+
+criterion_group!(
+    benches,
+    bench_text_heavy,
+    bench_empty_conditionals,
+    bench_change_case
+);
+criterion_main!(benches);
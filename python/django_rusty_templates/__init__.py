@@ -21,8 +21,8 @@ class RustyTemplates(BaseEngine):
         super().__init__(params)
         self.engine = Engine(self.dirs, self.app_dirs, **options)
 
-    def from_string(self, template_code):
-        return self.engine.from_string(template_code)
+    def from_string(self, template_code, autoescape=None):
+        return self.engine.from_string(template_code, autoescape)
 
     def get_template(self, template_name):
         return self.engine.get_template(template_name)
@@ -0,0 +1,492 @@
+//! Reconstruct approximate template source from a parsed tree.
+//!
+//! The output won't match the original byte for byte: whitespace inside tags
+//! is normalised, string literals are always double quoted and `{% elif %}`
+//! branches are shown as nested `{% if %}` tags. It should still be easy to
+//! recognise which part of the template each node came from.
+use std::fmt;
+
+use crate::filters::FilterType;
+use crate::lex::autoescape::AutoescapeEnabled;
+use crate::parse::{
+    BlockTranslate, Cache, Filter, For, IfCondition, Include, Tag, TagElement, TokenTree, Url,
+};
+use crate::types::{Argument, ForVariable, ForVariableName, TemplateString};
+
+pub trait TemplateDisplay {
+    fn fmt_template(&self, template: TemplateString<'_>, f: &mut fmt::Formatter<'_>)
+    -> fmt::Result;
+
+    fn display<'a>(&'a self, template: TemplateString<'a>) -> Display<'a, Self> {
+        Display {
+            node: self,
+            template,
+        }
+    }
+}
+
+pub struct Display<'a, T: ?Sized> {
+    node: &'a T,
+    template: TemplateString<'a>,
+}
+
+impl<T: TemplateDisplay + ?Sized> fmt::Display for Display<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.node.fmt_template(self.template, f)
+    }
+}
+
+impl TemplateDisplay for [TokenTree] {
+    fn fmt_template(
+        &self,
+        template: TemplateString<'_>,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        for node in self {
+            node.fmt_template(template, f)?;
+        }
+        Ok(())
+    }
+}
+
+impl TemplateDisplay for TokenTree {
+    fn fmt_template(
+        &self,
+        template: TemplateString<'_>,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        match self {
+            Self::Text(text) => write!(f, "{}", template.content(text.at)),
+            Self::TranslatedText(text) => {
+                write!(f, "{{{{ _(\"{}\") }}}}", template.content(text.at))
+            }
+            Self::Int(n) => write!(f, "{{{{ {n} }}}}"),
+            Self::Float(n) => write!(f, "{{{{ {n:?} }}}}"),
+            Self::Tag(tag) => tag.fmt_template(template, f),
+            Self::Variable(variable) => write!(f, "{{{{ {} }}}}", template.content(variable.at)),
+            Self::ForVariable(variable) => write!(f, "{{{{ {} }}}}", for_variable(variable)),
+            Self::Filter(filter) => write!(f, "{{{{ {} }}}}", filter.display(template)),
+        }
+    }
+}
+
+fn for_variable(variable: &ForVariable) -> String {
+    let mut name = "forloop".to_string();
+    for _ in 0..variable.parent_count {
+        name.push_str(".parentloop");
+    }
+    let attribute = match variable.variant {
+        ForVariableName::Counter => ".counter",
+        ForVariableName::Counter0 => ".counter0",
+        ForVariableName::RevCounter => ".revcounter",
+        ForVariableName::RevCounter0 => ".revcounter0",
+        ForVariableName::First => ".first",
+        ForVariableName::Last => ".last",
+        ForVariableName::Object => "",
+    };
+    name.push_str(attribute);
+    name
+}
+
+impl TemplateDisplay for TagElement {
+    fn fmt_template(
+        &self,
+        template: TemplateString<'_>,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        match self {
+            Self::Int(n) => write!(f, "{n}"),
+            Self::Float(n) => write!(f, "{n:?}"),
+            Self::Text(text) => write!(f, "\"{}\"", template.content(text.at)),
+            Self::TranslatedText(text) => write!(f, "_(\"{}\")", template.content(text.at)),
+            Self::Variable(variable) => write!(f, "{}", template.content(variable.at)),
+            Self::ForVariable(variable) => write!(f, "{}", for_variable(variable)),
+            Self::Filter(filter) => filter.fmt_template(template, f),
+        }
+    }
+}
+
+fn filter_argument(filter: &FilterType) -> Option<&Argument> {
+    match filter {
+        FilterType::Add(filter) => Some(&filter.argument),
+        FilterType::Center(filter) => Some(&filter.argument),
+        FilterType::Cut(filter) => Some(&filter.argument),
+        FilterType::Default(filter) => Some(&filter.argument),
+        FilterType::DefaultIfNone(filter) => Some(&filter.argument),
+        FilterType::DictSort(filter) => Some(&filter.argument),
+        FilterType::External(filter) => filter.argument.as_ref(),
+        FilterType::FloatFormat(filter) => filter.argument.as_ref(),
+        FilterType::IntComma(filter) => filter.argument.as_ref(),
+        FilterType::Join(filter) => Some(&filter.argument),
+        FilterType::Ljust(filter) => Some(&filter.argument),
+        FilterType::Pluralize(filter) => filter.argument.as_ref(),
+        FilterType::Rjust(filter) => Some(&filter.argument),
+        FilterType::Slice(filter) => Some(&filter.argument),
+        FilterType::TruncateWords(filter) => Some(&filter.argument),
+        FilterType::UrlizeTrunc(filter) => Some(&filter.argument),
+        FilterType::AddSlashes(_)
+        | FilterType::Capfirst(_)
+        | FilterType::Escape(_)
+        | FilterType::IntWord(_)
+        | FilterType::Json(_)
+        | FilterType::Length(_)
+        | FilterType::Linebreaksbr(_)
+        | FilterType::Lower(_)
+        | FilterType::MakeList(_)
+        | FilterType::NaturalTime(_)
+        | FilterType::Ordinal(_)
+        | FilterType::Safe(_)
+        | FilterType::Slugify(_)
+        | FilterType::UnicodeSlugify(_)
+        | FilterType::Upper(_)
+        | FilterType::Urlize(_) => None,
+    }
+}
+
+impl TemplateDisplay for Filter {
+    fn fmt_template(
+        &self,
+        template: TemplateString<'_>,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        self.left.fmt_template(template, f)?;
+        write!(f, "|{}", template.content(self.at))?;
+        if let Some(argument) = filter_argument(&self.filter) {
+            write!(f, ":{}", template.content(argument.at))?;
+        }
+        Ok(())
+    }
+}
+
+impl TemplateDisplay for IfCondition {
+    fn fmt_template(
+        &self,
+        template: TemplateString<'_>,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        let (operator, inner) = match self {
+            Self::Variable(element) => return element.fmt_template(template, f),
+            Self::Not(inner) => return write!(f, "not {}", inner.display(template)),
+            Self::And(inner) => ("and", inner),
+            Self::Or(inner) => ("or", inner),
+            Self::Equal(inner) => ("==", inner),
+            Self::NotEqual(inner) => ("!=", inner),
+            Self::LessThan(inner) => ("<", inner),
+            Self::GreaterThan(inner) => (">", inner),
+            Self::LessThanEqual(inner) => ("<=", inner),
+            Self::GreaterThanEqual(inner) => (">=", inner),
+            Self::In(inner) => ("in", inner),
+            Self::NotIn(inner) => ("not in", inner),
+            Self::Is(inner) => ("is", inner),
+            Self::IsNot(inner) => ("is not", inner),
+        };
+        let (left, right) = inner.as_ref();
+        write!(
+            f,
+            "{} {operator} {}",
+            left.display(template),
+            right.display(template)
+        )
+    }
+}
+
+fn fmt_arguments(
+    args: &[TagElement],
+    kwargs: &[(String, TagElement)],
+    template: TemplateString<'_>,
+    f: &mut fmt::Formatter<'_>,
+) -> fmt::Result {
+    for arg in args {
+        write!(f, " {}", arg.display(template))?;
+    }
+    for (name, arg) in kwargs {
+        write!(f, " {name}={}", arg.display(template))?;
+    }
+    Ok(())
+}
+
+impl TemplateDisplay for Url {
+    fn fmt_template(
+        &self,
+        template: TemplateString<'_>,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        write!(f, "{{% url {}", self.view_name.display(template))?;
+        fmt_arguments(&self.args, &self.kwargs, template, f)?;
+        if let Some(variable) = &self.variable {
+            write!(f, " as {variable}")?;
+        }
+        write!(f, " %}}")
+    }
+}
+
+impl TemplateDisplay for Cache {
+    fn fmt_template(
+        &self,
+        template: TemplateString<'_>,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        write!(
+            f,
+            "{{% cache {} {}",
+            self.expire_time.display(template),
+            self.fragment_name
+        )?;
+        fmt_arguments(&self.vary_on, &[], template, f)?;
+        if let Some(cache_name) = &self.cache_name {
+            write!(f, " using={}", cache_name.display(template))?;
+        }
+        write!(f, " %}}{}{{% endcache %}}", self.nodes.display(template))
+    }
+}
+
+impl TemplateDisplay for Include {
+    fn fmt_template(
+        &self,
+        template: TemplateString<'_>,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        write!(f, "{{% include {}", self.template_name.display(template))?;
+        if !self.variables.is_empty() {
+            write!(f, " with")?;
+            fmt_arguments(&[], &self.variables, template, f)?;
+        }
+        if self.only {
+            write!(f, " only")?;
+        }
+        write!(f, " %}}")
+    }
+}
+
+impl TemplateDisplay for For {
+    fn fmt_template(
+        &self,
+        template: TemplateString<'_>,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        write!(
+            f,
+            "{{% for {} in {}",
+            self.variables.names.join(", "),
+            self.iterable.iterable.display(template)
+        )?;
+        if self.reversed {
+            write!(f, " reversed")?;
+        }
+        write!(f, " %}}{}", self.body.display(template))?;
+        if let Some(empty) = &self.empty {
+            write!(f, "{{% empty %}}{}", empty.display(template))?;
+        }
+        write!(f, "{{% endfor %}}")
+    }
+}
+
+impl TemplateDisplay for BlockTranslate {
+    fn fmt_template(
+        &self,
+        _template: TemplateString<'_>,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        write!(f, "{{% blocktranslate")?;
+        if let Some(asvar) = &self.asvar {
+            write!(f, " asvar {asvar}")?;
+        }
+        write!(f, " %}}")?;
+        let mut message = self.message.as_str();
+        while let Some(index) = message.find('%') {
+            write!(f, "{}", &message[..index])?;
+            message = &message[index + 1..];
+            if let Some(rest) = message.strip_prefix('%') {
+                write!(f, "%")?;
+                message = rest;
+            } else if let Some(rest) = message.strip_prefix('(')
+                && let Some((name, rest)) = rest.split_once(")s")
+            {
+                write!(f, "{{{{ {name} }}}}")?;
+                message = rest;
+            } else {
+                write!(f, "%")?;
+            }
+        }
+        write!(f, "{message}{{% endblocktranslate %}}")
+    }
+}
+
+/// The name of a custom tag, taken from the source of its start tag.
+fn tag_name(template: TemplateString<'_>, at: (usize, usize)) -> &str {
+    template
+        .content(at)
+        .trim_start_matches("{%")
+        .split_whitespace()
+        .next()
+        .unwrap_or_default()
+}
+
+impl TemplateDisplay for Tag {
+    fn fmt_template(
+        &self,
+        template: TemplateString<'_>,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        match self {
+            Self::Autoescape { enabled, nodes } => {
+                let enabled = match enabled {
+                    AutoescapeEnabled::On => "on",
+                    AutoescapeEnabled::Off => "off",
+                };
+                write!(
+                    f,
+                    "{{% autoescape {enabled} %}}{}{{% endautoescape %}}",
+                    nodes.display(template)
+                )
+            }
+            Self::Block(block) => write!(
+                f,
+                "{{% block {} %}}{}{{% endblock %}}",
+                block.name,
+                block.nodes.display(template)
+            ),
+            Self::BlockTranslate(block_translate) => block_translate.fmt_template(template, f),
+            Self::Cache(cache) => cache.fmt_template(template, f),
+            Self::CsrfToken => write!(f, "{{% csrf_token %}}"),
+            Self::Extends(extends) => write!(
+                f,
+                "{{% extends {} %}}{}",
+                extends.parent.display(template),
+                extends.nodes.display(template)
+            ),
+            Self::GetCurrentTimezone { variable } => {
+                write!(f, "{{% get_current_timezone as {variable} %}}")
+            }
+            Self::If {
+                condition,
+                truthy,
+                falsey,
+            } => {
+                write!(
+                    f,
+                    "{{% if {} %}}{}",
+                    condition.display(template),
+                    truthy.display(template)
+                )?;
+                if let Some(falsey) = falsey {
+                    write!(f, "{{% else %}}{}", falsey.display(template))?;
+                }
+                write!(f, "{{% endif %}}")
+            }
+            Self::For(for_tag) => for_tag.fmt_template(template, f),
+            Self::Include(include) => include.fmt_template(template, f),
+            Self::Load => write!(f, "{{% load %}}"),
+            Self::Now(now) => {
+                write!(f, "{{% now \"{}\"", template.content(now.format.at))?;
+                if let Some(asvar) = &now.asvar {
+                    write!(f, " as {asvar}")?;
+                }
+                write!(f, " %}}")
+            }
+            Self::SimpleTag(simple_tag) => write!(f, "{}", template.content(simple_tag.at)),
+            Self::SimpleBlockTag(simple_tag) => write!(
+                f,
+                "{}{}{{% end{} %}}",
+                template.content(simple_tag.at),
+                simple_tag.nodes.display(template),
+                tag_name(template, simple_tag.at)
+            ),
+            Self::Spaceless { nodes } => write!(
+                f,
+                "{{% spaceless %}}{}{{% endspaceless %}}",
+                nodes.display(template)
+            ),
+            Self::Timezone { timezone, nodes } => write!(
+                f,
+                "{{% timezone {} %}}{}{{% endtimezone %}}",
+                timezone.display(template),
+                nodes.display(template)
+            ),
+            Self::Url(url) => url.fmt_template(template, f),
+            Self::WidthRatio(width_ratio) => {
+                write!(
+                    f,
+                    "{{% widthratio {} {} {}",
+                    width_ratio.value.display(template),
+                    width_ratio.max_value.display(template),
+                    width_ratio.max_width.display(template)
+                )?;
+                if let Some(asvar) = &width_ratio.asvar {
+                    write!(f, " as {asvar}")?;
+                }
+                write!(f, " %}}")
+            }
+            Self::With { variables, nodes } => {
+                write!(f, "{{% with")?;
+                fmt_arguments(&[], variables, template, f)?;
+                write!(f, " %}}{}{{% endwith %}}", nodes.display(template))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::collections::HashMap;
+
+    use pyo3::prelude::*;
+
+    use crate::parse::Parser;
+
+    fn reconstruct(source: &str) -> String {
+        Python::initialize();
+
+        Python::attach(|py| {
+            let template = TemplateString(source);
+            let libraries = HashMap::new();
+            let nodes = Parser::new(py, template, &libraries).parse().unwrap();
+            nodes.display(template).to_string()
+        })
+    }
+
+    #[test]
+    fn test_display_round_trip() {
+        let source = "{{ foo|upper }}Hello{% if x %}y{% endif %}";
+        assert_eq!(reconstruct(source), source);
+    }
+
+    #[test]
+    fn test_display_normalises_tags() {
+        let source = "{%for a,b in items reversed%}{{ forloop.counter }}{{a|default:'x'}}{%empty%}none{%endfor%}";
+        assert_eq!(
+            reconstruct(source),
+            "{% for a, b in items reversed %}{{ forloop.counter }}{{ a|default:'x' }}{% empty %}none{% endfor %}"
+        );
+    }
+
+    #[test]
+    fn test_display_if_conditions() {
+        let source =
+            "{% if not a and b|length > 2 or 'c' in d %}x{% elif e %}y{% else %}z{% endif %}";
+        assert_eq!(
+            reconstruct(source),
+            "{% if not a and b|length > 2 or \"c\" in d %}x{% else %}{% if e %}y{% else %}z{% endif %}{% endif %}"
+        );
+    }
+
+    #[test]
+    fn test_display_extends_and_blocks() {
+        let source = "{% extends base %}{% block title %}Hi{% block inner %}{{ block.super }}{% endblock inner %}{% endblock %}";
+        assert_eq!(
+            reconstruct(source),
+            "{% extends base %}{% block title %}Hi{% block inner %}{{ block.super }}{% endblock %}{% endblock %}"
+        );
+    }
+
+    #[test]
+    fn test_display_autoescape_and_url() {
+        let source = "{% autoescape off %}{% url 'home' page=2 as link %}{% endautoescape %}";
+        assert_eq!(
+            reconstruct(source),
+            "{% autoescape off %}{% url \"home\" page=2 as link %}{% endautoescape %}"
+        );
+    }
+}
@@ -10,13 +10,34 @@ pub enum FilterType {
     AddSlashes(AddSlashesFilter),
     Capfirst(CapfirstFilter),
     Center(CenterFilter),
+    Cut(CutFilter),
     Default(DefaultFilter),
+    DefaultIfNone(DefaultIfNoneFilter),
+    DictSort(DictSortFilter),
     Escape(EscapeFilter),
     External(ExternalFilter),
+    FloatFormat(FloatFormatFilter),
+    IntComma(IntCommaFilter),
+    IntWord(IntWordFilter),
+    Join(JoinFilter),
+    Json(JsonFilter),
+    Length(LengthFilter),
+    Linebreaksbr(LinebreaksbrFilter),
+    Ljust(LjustFilter),
     Lower(LowerFilter),
+    MakeList(MakeListFilter),
+    NaturalTime(NaturalTimeFilter),
+    Ordinal(OrdinalFilter),
+    Pluralize(PluralizeFilter),
+    Rjust(RjustFilter),
     Safe(SafeFilter),
+    Slice(SliceFilter),
     Slugify(SlugifyFilter),
+    TruncateWords(TruncateWordsFilter),
+    UnicodeSlugify(UnicodeSlugifyFilter),
     Upper(UpperFilter),
+    Urlize(UrlizeFilter),
+    UrlizeTrunc(UrlizeTruncFilter),
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -47,6 +68,17 @@ impl CenterFilter {
     }
 }
 
+#[derive(Clone, Debug, PartialEq)]
+pub struct CutFilter {
+    pub argument: Argument,
+}
+
+impl CutFilter {
+    pub fn new(argument: Argument) -> Self {
+        Self { argument }
+    }
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub struct DefaultFilter {
     pub argument: Argument,
@@ -58,6 +90,28 @@ impl DefaultFilter {
     }
 }
 
+#[derive(Clone, Debug, PartialEq)]
+pub struct DefaultIfNoneFilter {
+    pub argument: Argument,
+}
+
+impl DefaultIfNoneFilter {
+    pub fn new(argument: Argument) -> Self {
+        Self { argument }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct DictSortFilter {
+    pub argument: Argument,
+}
+
+impl DictSortFilter {
+    pub fn new(argument: Argument) -> Self {
+        Self { argument }
+    }
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub struct EscapeFilter;
 
@@ -86,14 +140,142 @@ impl PartialEq for ExternalFilter {
     }
 }
 
+#[derive(Clone, Debug, PartialEq)]
+pub struct FloatFormatFilter {
+    pub argument: Option<Argument>,
+}
+
+impl FloatFormatFilter {
+    pub fn new(argument: Option<Argument>) -> Self {
+        Self { argument }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct IntCommaFilter {
+    pub argument: Option<Argument>,
+}
+
+impl IntCommaFilter {
+    pub fn new(argument: Option<Argument>) -> Self {
+        Self { argument }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct IntWordFilter;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct JoinFilter {
+    pub argument: Argument,
+}
+
+impl JoinFilter {
+    pub fn new(argument: Argument) -> Self {
+        Self { argument }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct JsonFilter;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct LengthFilter;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct LinebreaksbrFilter;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct LjustFilter {
+    pub argument: Argument,
+}
+
+impl LjustFilter {
+    pub fn new(argument: Argument) -> Self {
+        Self { argument }
+    }
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub struct LowerFilter;
 
+#[derive(Clone, Debug, PartialEq)]
+pub struct MakeListFilter;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct NaturalTimeFilter;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct OrdinalFilter;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct PluralizeFilter {
+    pub argument: Option<Argument>,
+}
+
+impl PluralizeFilter {
+    pub fn new(argument: Option<Argument>) -> Self {
+        Self { argument }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct RjustFilter {
+    pub argument: Argument,
+}
+
+impl RjustFilter {
+    pub fn new(argument: Argument) -> Self {
+        Self { argument }
+    }
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub struct SafeFilter;
 
+#[derive(Clone, Debug, PartialEq)]
+pub struct SliceFilter {
+    pub argument: Argument,
+}
+
+impl SliceFilter {
+    pub fn new(argument: Argument) -> Self {
+        Self { argument }
+    }
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub struct SlugifyFilter;
 
+#[derive(Clone, Debug, PartialEq)]
+pub struct TruncateWordsFilter {
+    pub argument: Argument,
+}
+
+impl TruncateWordsFilter {
+    pub fn new(argument: Argument) -> Self {
+        Self { argument }
+    }
+}
+
+/// Not built into Django: `slugify` with `allow_unicode=True`, which keeps
+/// non-ASCII word characters.
+#[derive(Clone, Debug, PartialEq)]
+pub struct UnicodeSlugifyFilter;
+
 #[derive(Clone, Debug, PartialEq)]
 pub struct UpperFilter;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct UrlizeFilter;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct UrlizeTruncFilter {
+    pub argument: Argument,
+}
+
+impl UrlizeTruncFilter {
+    pub fn new(argument: Argument) -> Self {
+        Self { argument }
+    }
+}
@@ -118,6 +118,24 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_lex_autoescape_not_literal() {
+        for argument in ["yes", "somevar", "\"on\"", "ON", "x|lower"] {
+            let template = format!("{{% autoescape {argument} %}}");
+            let parts = TagParts {
+                at: (14, argument.len()),
+            };
+            let error = lex_autoescape_argument(template.as_str().into(), parts).unwrap_err();
+            assert_eq!(
+                error,
+                AutoescapeError::InvalidArgument {
+                    at: (14, argument.len()).into()
+                },
+                "{argument}"
+            );
+        }
+    }
+
     #[test]
     fn test_lex_autoescape_unexpected_argument() {
         let template = "{% autoescape off on %}";
@@ -50,6 +50,21 @@ pub enum LexerError {
         #[label("here")]
         at: SourceSpan,
     },
+    #[error("Unclosed comment, expected '#}}'")]
+    UnclosedComment {
+        #[label("started here")]
+        at: SourceSpan,
+    },
+    #[error("Unclosed tag, expected '%}}'")]
+    UnclosedTag {
+        #[label("started here")]
+        at: SourceSpan,
+    },
+    #[error("Unclosed variable, expected '}}}}'")]
+    UnclosedVariable {
+        #[label("started here")]
+        at: SourceSpan,
+    },
 }
 
 pub fn lex_variable(byte: usize, rest: &str) -> ((usize, usize), usize, &str) {
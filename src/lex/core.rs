@@ -1,3 +1,4 @@
+use crate::lex::common::LexerError;
 use crate::lex::{END_TAG_LEN, START_TAG_LEN};
 use crate::types::TemplateString;
 
@@ -71,18 +72,33 @@ pub struct Lexer<'t> {
     rest: &'t str,
     byte: usize,
     verbatim: Option<&'t str>,
+    unclosed_errors: bool,
 }
 
 impl<'t> Lexer<'t> {
+    /// The byte offset of the next token.
+    pub fn position(&self) -> usize {
+        self.byte
+    }
+
     pub fn new(template: TemplateString<'t>) -> Self {
         Self {
             template,
             rest: template.0,
             byte: 0,
             verbatim: None,
+            unclosed_errors: false,
         }
     }
 
+    /// Report a `{{`, `{%` or `{#` that is never closed as an error.
+    ///
+    /// By default these are treated as text, like Django does.
+    pub fn with_unclosed_errors(mut self, unclosed_errors: bool) -> Self {
+        self.unclosed_errors = unclosed_errors;
+        self
+    }
+
     fn lex_text(&mut self) -> Token {
         let next_tag = self.rest.find("{%");
         let next_variable = self.rest.find("{{");
@@ -115,18 +131,28 @@ impl<'t> Lexer<'t> {
         Token::text(at)
     }
 
-    fn lex_tag(&mut self, end_tag: EndTag) -> Token {
+    fn lex_tag(&mut self, end_tag: EndTag) -> Result<Token, LexerError> {
         let end_str = match end_tag {
             EndTag::Variable => "}}",
             EndTag::Tag => "%}",
             EndTag::Comment => "#}",
         };
         let Some(n) = self.rest.find(end_str) else {
+            if self.unclosed_errors {
+                let at = (self.byte, START_TAG_LEN).into();
+                // Stop lexing after reporting the error.
+                self.rest = "";
+                return Err(match end_tag {
+                    EndTag::Variable => LexerError::UnclosedVariable { at },
+                    EndTag::Tag => LexerError::UnclosedTag { at },
+                    EndTag::Comment => LexerError::UnclosedComment { at },
+                });
+            }
             let len = self.rest.len();
             let at = (self.byte, len);
             self.byte += len;
             self.rest = "";
-            return Token::text(at);
+            return Ok(Token::text(at));
         };
         // This can be removed if https://code.djangoproject.com/ticket/35899 lands
         match self.rest.find("\n") {
@@ -134,7 +160,7 @@ impl<'t> Lexer<'t> {
                 let at = (self.byte, newline + 1);
                 self.byte += newline + 1;
                 self.rest = &self.rest[newline + 1..];
-                return Token::text(at);
+                return Ok(Token::text(at));
             }
             _ => {}
         }
@@ -143,11 +169,11 @@ impl<'t> Lexer<'t> {
 
         let at = (self.byte, len);
         self.byte += len;
-        match end_tag {
+        Ok(match end_tag {
             EndTag::Variable => Token::variable(at),
             EndTag::Tag => Token::tag(at),
             EndTag::Comment => Token::comment(at),
-        }
+        })
     }
 
     fn lex_verbatim(&mut self, verbatim: &'t str) -> Token {
@@ -166,7 +192,7 @@ impl<'t> Lexer<'t> {
             };
             let inner = &rest[2..end_tag].trim();
             // Check we have the right endverbatim tag
-            if inner.len() < 3 || &inner[3..] != verbatim {
+            if inner.strip_prefix("end") != Some(verbatim) {
                 rest = &rest[end_tag + 2..];
                 index += start_tag + end_tag + 2;
                 continue;
@@ -191,17 +217,20 @@ impl<'t> Lexer<'t> {
 }
 
 impl Iterator for Lexer<'_> {
-    type Item = Token;
+    type Item = Result<Token, LexerError>;
 
     fn next(&mut self) -> Option<Self::Item> {
         if self.rest.is_empty() {
             return None;
         }
-        Some(match self.verbatim {
+        Some(Ok(match self.verbatim {
             None => match self.rest.get(..START_TAG_LEN) {
-                Some("{{") => self.lex_tag(EndTag::Variable),
+                Some("{{") => return Some(self.lex_tag(EndTag::Variable)),
                 Some("{%") => {
-                    let tag = self.lex_tag(EndTag::Tag);
+                    let tag = match self.lex_tag(EndTag::Tag) {
+                        Ok(tag) => tag,
+                        Err(err) => return Some(Err(err)),
+                    };
                     if let Token {
                         token_type: TokenType::Tag,
                         ..
@@ -214,11 +243,11 @@ impl Iterator for Lexer<'_> {
                     }
                     tag
                 }
-                Some("{#") => self.lex_tag(EndTag::Comment),
+                Some("{#") => return Some(self.lex_tag(EndTag::Comment)),
                 _ => self.lex_text(),
             },
             Some(verbatim) => self.lex_verbatim(verbatim),
-        })
+        }))
     }
 }
 #[cfg(test)]
@@ -234,7 +263,7 @@ mod tests {
     fn test_lex_empty() {
         let template = "";
         let lexer = Lexer::new(template.into());
-        let tokens: Vec<_> = lexer.collect();
+        let tokens: Vec<_> = lexer.collect::<Result<_, _>>().unwrap();
         assert_eq!(tokens, vec![]);
     }
 
@@ -242,7 +271,7 @@ mod tests {
     fn test_lex_text() {
         let template = "Just some text";
         let lexer = Lexer::new(template.into());
-        let tokens: Vec<_> = lexer.collect();
+        let tokens: Vec<_> = lexer.collect::<Result<_, _>>().unwrap();
         assert_eq!(tokens, vec![Token::text((0, 14))]);
         assert_eq!(contents(template, tokens), vec![template]);
     }
@@ -251,7 +280,7 @@ mod tests {
     fn test_lex_text_whitespace() {
         let template = "    ";
         let lexer = Lexer::new(template.into());
-        let tokens: Vec<_> = lexer.collect();
+        let tokens: Vec<_> = lexer.collect::<Result<_, _>>().unwrap();
         assert_eq!(tokens, vec![Token::text((0, 4))]);
         assert_eq!(contents(template, tokens), vec![template]);
     }
@@ -260,7 +289,7 @@ mod tests {
     fn test_lex_comment() {
         let template = "{# comment #}";
         let lexer = Lexer::new(template.into());
-        let tokens: Vec<_> = lexer.collect();
+        let tokens: Vec<_> = lexer.collect::<Result<_, _>>().unwrap();
         assert_eq!(tokens, vec![Token::comment((0, 13))]);
         assert_eq!(contents(template, tokens), vec![" comment "]);
     }
@@ -269,7 +298,7 @@ mod tests {
     fn test_lex_variable() {
         let template = "{{ foo.bar|title }}";
         let lexer = Lexer::new(template.into());
-        let tokens: Vec<_> = lexer.collect();
+        let tokens: Vec<_> = lexer.collect::<Result<_, _>>().unwrap();
         assert_eq!(tokens, vec![Token::variable((0, 19))]);
         assert_eq!(contents(template, tokens), vec![" foo.bar|title "]);
     }
@@ -278,7 +307,7 @@ mod tests {
     fn test_lex_tag() {
         let template = "{% for foo in bar %}";
         let lexer = Lexer::new(template.into());
-        let tokens: Vec<_> = lexer.collect();
+        let tokens: Vec<_> = lexer.collect::<Result<_, _>>().unwrap();
         assert_eq!(tokens, vec![Token::tag((0, 20))]);
         assert_eq!(contents(template, tokens), vec![" for foo in bar "]);
     }
@@ -287,7 +316,7 @@ mod tests {
     fn test_lex_incomplete_comment() {
         let template = "{# comment #";
         let lexer = Lexer::new(template.into());
-        let tokens: Vec<_> = lexer.collect();
+        let tokens: Vec<_> = lexer.collect::<Result<_, _>>().unwrap();
         assert_eq!(tokens, vec![Token::text((0, 12))]);
         assert_eq!(contents(template, tokens), vec![template]);
     }
@@ -296,7 +325,7 @@ mod tests {
     fn test_lex_incomplete_variable() {
         let template = "{{ foo.bar|title }";
         let lexer = Lexer::new(template.into());
-        let tokens: Vec<_> = lexer.collect();
+        let tokens: Vec<_> = lexer.collect::<Result<_, _>>().unwrap();
         assert_eq!(tokens, vec![Token::text((0, 18))]);
         assert_eq!(contents(template, tokens), vec![template]);
     }
@@ -305,16 +334,55 @@ mod tests {
     fn test_lex_incomplete_tag() {
         let template = "{% for foo in bar %";
         let lexer = Lexer::new(template.into());
-        let tokens: Vec<_> = lexer.collect();
+        let tokens: Vec<_> = lexer.collect::<Result<_, _>>().unwrap();
         assert_eq!(tokens, vec![Token::text((0, 19))]);
         assert_eq!(contents(template, tokens), vec![template]);
     }
 
+    #[test]
+    fn test_lex_unclosed_variable() {
+        let template = "Hello {{ foo";
+        let mut lexer = Lexer::new(template.into()).with_unclosed_errors(true);
+        assert_eq!(lexer.next(), Some(Ok(Token::text((0, 6)))));
+        let error = LexerError::UnclosedVariable { at: (6, 2).into() };
+        assert_eq!(lexer.next(), Some(Err(error)));
+        assert_eq!(lexer.next(), None);
+    }
+
+    #[test]
+    fn test_lex_unclosed_tag() {
+        let template = "{% if x";
+        let mut lexer = Lexer::new(template.into()).with_unclosed_errors(true);
+        let error = LexerError::UnclosedTag { at: (0, 2).into() };
+        assert_eq!(lexer.next(), Some(Err(error)));
+        assert_eq!(lexer.next(), None);
+    }
+
+    #[test]
+    fn test_lex_unclosed_comment() {
+        let template = "{{ foo }}{# comment";
+        let mut lexer = Lexer::new(template.into()).with_unclosed_errors(true);
+        assert_eq!(lexer.next(), Some(Ok(Token::variable((0, 9)))));
+        let error = LexerError::UnclosedComment { at: (9, 2).into() };
+        assert_eq!(lexer.next(), Some(Err(error)));
+        assert_eq!(lexer.next(), None);
+    }
+
+    #[test]
+    fn test_lex_unclosed_after_newline() {
+        let template = "{{ foo\n}}{% if x\n";
+        let mut lexer = Lexer::new(template.into()).with_unclosed_errors(true);
+        assert_eq!(lexer.next(), Some(Ok(Token::text((0, 7)))));
+        assert_eq!(lexer.next(), Some(Ok(Token::text((7, 2)))));
+        let error = LexerError::UnclosedTag { at: (9, 2).into() };
+        assert_eq!(lexer.next(), Some(Err(error)));
+    }
+
     #[test]
     fn test_django_example() {
         let template = "text\n{% if test %}{{ varvalue }}{% endif %}{#comment {{not a var}} {%not a block%} #}end text";
         let lexer = Lexer::new(template.into());
-        let tokens: Vec<_> = lexer.collect();
+        let tokens: Vec<_> = lexer.collect::<Result<_, _>>().unwrap();
         assert_eq!(
             tokens,
             vec![
@@ -343,7 +411,7 @@ mod tests {
     fn test_verbatim_with_variable() {
         let template = "{% verbatim %}{{bare   }}{% endverbatim %}";
         let lexer = Lexer::new(template.into());
-        let tokens: Vec<_> = lexer.collect();
+        let tokens: Vec<_> = lexer.collect::<Result<_, _>>().unwrap();
         assert_eq!(
             tokens,
             vec![
@@ -362,7 +430,7 @@ mod tests {
     fn test_verbatim_with_tag() {
         let template = "{% verbatim %}{% endif %}{% endverbatim %}";
         let lexer = Lexer::new(template.into());
-        let tokens: Vec<_> = lexer.collect();
+        let tokens: Vec<_> = lexer.collect::<Result<_, _>>().unwrap();
         assert_eq!(
             tokens,
             vec![
@@ -381,7 +449,7 @@ mod tests {
     fn test_verbatim_with_verbatim_tag() {
         let template = "{% verbatim %}It's the {% verbatim %} tag{% endverbatim %}";
         let lexer = Lexer::new(template.into());
-        let tokens: Vec<_> = lexer.collect();
+        let tokens: Vec<_> = lexer.collect::<Result<_, _>>().unwrap();
         assert_eq!(
             tokens,
             vec![
@@ -400,7 +468,7 @@ mod tests {
     fn test_verbatim_nested() {
         let template = "{% verbatim %}{% verbatim %}{% endverbatim %}{% endverbatim %}";
         let lexer = Lexer::new(template.into());
-        let tokens: Vec<_> = lexer.collect();
+        let tokens: Vec<_> = lexer.collect::<Result<_, _>>().unwrap();
         assert_eq!(
             tokens,
             vec![
@@ -425,7 +493,7 @@ mod tests {
     fn test_verbatim_adjacent() {
         let template = "{% verbatim %}{% endverbatim %}{% verbatim %}{% endverbatim %}";
         let lexer = Lexer::new(template.into());
-        let tokens: Vec<_> = lexer.collect();
+        let tokens: Vec<_> = lexer.collect::<Result<_, _>>().unwrap();
         assert_eq!(
             tokens,
             vec![
@@ -446,7 +514,7 @@ mod tests {
         let template =
             "{% verbatim special %}Don't {% endverbatim %} just yet{% endverbatim special %}";
         let lexer = Lexer::new(template.into());
-        let tokens: Vec<_> = lexer.collect();
+        let tokens: Vec<_> = lexer.collect::<Result<_, _>>().unwrap();
         assert_eq!(
             tokens,
             vec![
@@ -465,11 +533,30 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_verbatim_end_tag_must_match_exactly() {
+        let template = "{% verbatim %}{% badverbatim %}{% é %}{% endverbatim %}";
+        let lexer = Lexer::new(template.into());
+        let tokens: Vec<_> = lexer.collect::<Result<_, _>>().unwrap();
+        assert_eq!(
+            tokens,
+            vec![
+                Token::tag((0, 14)),
+                Token::text((14, 25)),
+                Token::tag((39, 17)),
+            ]
+        );
+        assert_eq!(
+            contents(template, tokens),
+            vec![" verbatim ", "{% badverbatim %}{% é %}", " endverbatim "]
+        );
+    }
+
     #[test]
     fn test_verbatim_open_tag() {
         let template = "{% verbatim %}Don't {% ";
         let lexer = Lexer::new(template.into());
-        let tokens: Vec<_> = lexer.collect();
+        let tokens: Vec<_> = lexer.collect::<Result<_, _>>().unwrap();
         assert_eq!(tokens, vec![Token::tag((0, 14)), Token::text((14, 9))]);
         assert_eq!(contents(template, tokens), vec![" verbatim ", "Don't {% "]);
     }
@@ -478,7 +565,7 @@ mod tests {
     fn test_verbatim_no_tag() {
         let template = "{% verbatim %}Don't end verbatim";
         let lexer = Lexer::new(template.into());
-        let tokens: Vec<_> = lexer.collect();
+        let tokens: Vec<_> = lexer.collect::<Result<_, _>>().unwrap();
         assert_eq!(tokens, vec![Token::tag((0, 14)), Token::text((14, 18))]);
         assert_eq!(
             contents(template, tokens),
@@ -33,6 +33,14 @@ impl SimpleTagToken {
             SimpleTagTokenType::TranslatedText => translated_text_content_at(self.at),
         }
     }
+
+    /// The position of the whole token, including any keyword.
+    pub fn full_at(&self) -> (usize, usize) {
+        match self.kwarg {
+            Some(name_at) => (name_at.0, self.at.0 + self.at.1 - name_at.0),
+            None => self.at,
+        }
+    }
 }
 
 #[derive(Error, Debug, Diagnostic, PartialEq, Eq)]
@@ -11,6 +11,11 @@ use crate::types::TemplateString;
 pub enum ForLexerError {
     #[error(transparent)]
     LexerError(#[from] LexerError),
+    #[error("Empty variable name in for loop:")]
+    EmptyName {
+        #[label("expected a variable name next to this comma")]
+        at: SourceSpan,
+    },
     #[error("Invalid variable name {name} in for loop:")]
     InvalidName {
         name: String,
@@ -72,6 +77,7 @@ pub struct ForLexer<'t> {
     byte: usize,
     state: State,
     previous_at: Option<(usize, usize)>,
+    previous_comma: Option<usize>,
 }
 
 impl<'t> ForLexer<'t> {
@@ -81,6 +87,7 @@ impl<'t> ForLexer<'t> {
             byte: parts.at.0,
             state: State::VariableName,
             previous_at: None,
+            previous_comma: None,
         }
     }
 
@@ -233,6 +240,32 @@ impl<'t> ForLexer<'t> {
         let at = (self.byte, index);
         self.previous_at = Some(at);
         let name = &self.rest[..index];
+        if name.is_empty() {
+            // Either two commas with nothing between them or a leading comma.
+            let comma = self.previous_comma.unwrap_or(self.byte);
+            self.rest = "";
+            self.state = State::Done;
+            return Some(Err(ForLexerError::EmptyName {
+                at: (comma, 1).into(),
+            }));
+        }
+        // After a trailing comma, the `in` keyword is where the next name
+        // should be. Like Django, `in` is still a name if another `in`
+        // follows it.
+        if name == "in"
+            && matches!(self.state, State::Done)
+            && let Some(comma) = self.previous_comma
+            && self.rest[index + next_index..].split_whitespace().next() != Some("in")
+        {
+            self.rest = "";
+            return Some(Err(ForLexerError::EmptyName {
+                at: (comma, 1).into(),
+            }));
+        }
+        self.previous_comma = match self.state {
+            State::VariableName => Some(self.byte + index),
+            State::Done => None,
+        };
         if name.contains(['"', '\'', '|']) {
             self.rest = "";
             self.state = State::Done;
@@ -512,6 +545,68 @@ mod tests {
         assert_eq!(lexer.lex_expression().unwrap_err(), incomplete.into());
     }
 
+    #[test]
+    fn test_empty_name_between_commas() {
+        let template = "{% for a,,b in c %}";
+        let parts = TagParts { at: (7, 10) };
+        let mut lexer = ForLexer::new(template.into(), parts);
+
+        let a = ForVariableNameToken { at: (7, 1) };
+        let empty = ForLexerError::EmptyName { at: (8, 1).into() };
+        assert_eq!(lexer.lex_variable_name().unwrap().unwrap(), a);
+        assert_eq!(lexer.lex_variable_name().unwrap().unwrap_err(), empty);
+        assert!(lexer.lex_variable_name().is_none());
+    }
+
+    #[test]
+    fn test_empty_name_between_spaced_commas() {
+        let template = "{% for a, , b in c %}";
+        let parts = TagParts { at: (7, 12) };
+        let mut lexer = ForLexer::new(template.into(), parts);
+
+        let a = ForVariableNameToken { at: (7, 1) };
+        let empty = ForLexerError::EmptyName { at: (8, 1).into() };
+        assert_eq!(lexer.lex_variable_name().unwrap().unwrap(), a);
+        assert_eq!(lexer.lex_variable_name().unwrap().unwrap_err(), empty);
+    }
+
+    #[test]
+    fn test_empty_name_leading_comma() {
+        let template = "{% for ,a in c %}";
+        let parts = TagParts { at: (7, 8) };
+        let mut lexer = ForLexer::new(template.into(), parts);
+
+        let empty = ForLexerError::EmptyName { at: (7, 1).into() };
+        assert_eq!(lexer.lex_variable_name().unwrap().unwrap_err(), empty);
+    }
+
+    #[test]
+    fn test_trailing_comma() {
+        let template = "{% for a, in c %}";
+        let parts = TagParts { at: (7, 8) };
+        let mut lexer = ForLexer::new(template.into(), parts);
+
+        let a = ForVariableNameToken { at: (7, 1) };
+        let empty = ForLexerError::EmptyName { at: (8, 1).into() };
+        assert_eq!(lexer.lex_variable_name().unwrap().unwrap(), a);
+        assert_eq!(lexer.lex_variable_name().unwrap().unwrap_err(), empty);
+        assert!(lexer.lex_variable_name().is_none());
+    }
+
+    #[test]
+    fn test_in_as_name_after_comma() {
+        let template = "{% for a, in in c %}";
+        let parts = TagParts { at: (7, 11) };
+        let mut lexer = ForLexer::new(template.into(), parts);
+
+        let a = ForVariableNameToken { at: (7, 1) };
+        let name = ForVariableNameToken { at: (10, 2) };
+        assert_eq!(lexer.lex_variable_name().unwrap().unwrap(), a);
+        assert_eq!(lexer.lex_variable_name().unwrap().unwrap(), name);
+        assert!(lexer.lex_variable_name().is_none());
+        lexer.lex_in().unwrap();
+    }
+
     #[test]
     fn test_invalid_name() {
         let template = "{% for '2' in 'bar' %}";
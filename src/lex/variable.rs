@@ -587,6 +587,40 @@ mod tests {
         assert_eq!(contents(template, tokens), vec![("default", Some("foo"))]);
     }
 
+    #[test]
+    fn test_lex_text_argument_with_colons() {
+        let template = "{{ t|date:\"Y-m-d H:i:s\" }}";
+        let variable = trim_variable(template);
+        let (_token, lexer) = lex_variable(variable, START_TAG_LEN).unwrap().unwrap();
+        let tokens: Vec<_> = lexer.collect();
+        assert_eq!(
+            tokens,
+            vec![Ok(FilterToken {
+                argument: Some(Argument {
+                    argument_type: ArgumentType::Text,
+                    at: (10, 13),
+                }),
+                at: (5, 4),
+            })]
+        );
+        assert_eq!(
+            contents(template, tokens),
+            vec![("date", Some("Y-m-d H:i:s"))]
+        );
+    }
+
+    #[test]
+    fn test_lex_text_argument_with_colon_then_filter() {
+        let template = "{{ t|default:'a:b'|upper }}";
+        let variable = trim_variable(template);
+        let (_token, lexer) = lex_variable(variable, START_TAG_LEN).unwrap().unwrap();
+        let tokens: Vec<_> = lexer.collect();
+        assert_eq!(
+            contents(template, tokens),
+            vec![("default", Some("a:b")), ("upper", None)]
+        );
+    }
+
     #[test]
     fn test_lex_text_argument_escaped() {
         let template = "{{ foo.bar|default:'foo\\\'' }}";
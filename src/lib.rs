@@ -16,6 +16,7 @@ macro_rules! todo {
     }};
 }
 
+mod display;
 mod error;
 mod filters;
 mod lex;
@@ -25,3 +26,7 @@ mod render;
 mod template;
 mod types;
 mod utils;
+
+pub use parse::{AstItem, AstKind, DEFAULT_MAX_DEPTH, Parser, TemplateAst};
+pub use template::django_rusty_templates;
+pub use types::TemplateString;
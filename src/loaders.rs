@@ -1,10 +1,12 @@
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
+use std::sync::Mutex;
 
 use cached::proc_macro::cached;
 use encoding_rs::Encoding;
 use pyo3::exceptions::PyUnicodeError;
 use pyo3::prelude::*;
+use pyo3::sync::MutexExt;
 use sugar_path::SugarPath;
 
 use crate::template::django_rusty_templates::{EngineData, Template};
@@ -134,7 +136,9 @@ impl AppDirsLoader {
 }
 #[derive(Debug)]
 pub struct CachedLoader {
-    cache: HashMap<String, Result<Template, LoaderError>>,
+    /// Shared by every render using the engine, so it is locked for each
+    /// lookup rather than requiring exclusive access to the loader.
+    cache: Mutex<HashMap<String, Result<Template, LoaderError>>>,
     pub loaders: Vec<Loader>,
 }
 
@@ -142,26 +146,45 @@ impl CachedLoader {
     pub fn new(loaders: Vec<Loader>) -> Self {
         Self {
             loaders,
-            cache: HashMap::new(),
+            cache: Mutex::new(HashMap::new()),
         }
     }
 
+    fn cache_insert(
+        &self,
+        py: Python<'_>,
+        template_name: &str,
+        entry: Result<Template, LoaderError>,
+    ) {
+        self.cache
+            .lock_py_attached(py)
+            .expect("Mutex should not be poisoned")
+            .insert(template_name.to_string(), entry);
+    }
+
     fn get_template(
-        &mut self,
+        &self,
         py: Python<'_>,
         template_name: &str,
         engine: &EngineData,
     ) -> Result<PyResult<Template>, LoaderError> {
-        match self.cache.get(template_name) {
-            Some(Ok(template)) => Ok(Ok((*template).clone())),
-            Some(Err(e)) => Err(e.clone()),
+        let cached = self
+            .cache
+            .lock_py_attached(py)
+            .expect("Mutex should not be poisoned")
+            .get(template_name)
+            .cloned();
+        match cached {
+            Some(Ok(template)) => Ok(Ok(template)),
+            Some(Err(e)) => Err(e),
+            // The lock isn't held while loading, so two threads may both
+            // load the same template. Either result can be cached.
             None => {
                 let mut tried = Vec::new();
-                for loader in &mut self.loaders {
+                for loader in &self.loaders {
                     match loader.get_template(py, template_name, engine) {
                         Ok(Ok(template)) => {
-                            self.cache
-                                .insert(template_name.to_string(), Ok(template.clone()));
+                            self.cache_insert(py, template_name, Ok(template.clone()));
                             return Ok(Ok(template));
                         }
                         Ok(Err(e)) => return Ok(Err(e)),
@@ -169,8 +192,7 @@ impl CachedLoader {
                     }
                 }
                 let error = LoaderError { tried };
-                self.cache
-                    .insert(template_name.to_string(), Err(error.clone()));
+                self.cache_insert(py, template_name, Err(error.clone()));
                 Err(error)
             }
         }
@@ -237,7 +259,7 @@ pub enum Loader {
 
 impl Loader {
     pub fn get_template(
-        &mut self,
+        &self,
         py: Python<'_>,
         template_name: &str,
         engine: &EngineData,
@@ -255,6 +277,7 @@ impl Loader {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use pyo3::types::PyDict;
     use pyo3::{BoundObject, IntoPyObjectExt};
 
     use quickcheck::quickcheck;
@@ -373,7 +396,7 @@ mod tests {
                 FileSystemLoader::new(vec![PathBuf::from("tests/templates")], encoding_rs::UTF_8);
 
             // Wrap the FileSystemLoader in a CachedLoader
-            let mut cached_loader = CachedLoader::new(vec![Loader::FileSystem(filesystem_loader)]);
+            let cached_loader = CachedLoader::new(vec![Loader::FileSystem(filesystem_loader)]);
 
             // Load a template via the CachedLoader
             let template = cached_loader
@@ -391,8 +414,11 @@ mod tests {
             assert_eq!(template.filename.unwrap(), expected_path);
 
             // Verify the cache state after first load
-            assert_eq!(cached_loader.cache.len(), 1);
-            verify_cache(&cached_loader.cache, "basic.txt", &expected_path);
+            {
+                let cache = cached_loader.cache.lock().unwrap();
+                assert_eq!(cache.len(), 1);
+                verify_cache(&cache, "basic.txt", &expected_path);
+            }
 
             // Load the same template again via the CachedLoader
             let template = cached_loader
@@ -404,8 +430,9 @@ mod tests {
             assert_eq!(template.filename.unwrap(), expected_path);
 
             // Verify the cache state remains consistent
-            assert_eq!(cached_loader.cache.len(), 1);
-            verify_cache(&cached_loader.cache, "basic.txt", &expected_path);
+            let cache = cached_loader.cache.lock().unwrap();
+            assert_eq!(cache.len(), 1);
+            verify_cache(&cache, "basic.txt", &expected_path);
         });
     }
 
@@ -418,7 +445,7 @@ mod tests {
             let filesystem_loader =
                 FileSystemLoader::new(vec![PathBuf::from("tests/templates")], encoding_rs::UTF_8);
 
-            let mut cached_loader = CachedLoader::new(vec![Loader::FileSystem(filesystem_loader)]);
+            let cached_loader = CachedLoader::new(vec![Loader::FileSystem(filesystem_loader)]);
             let error = cached_loader
                 .get_template(py, "missing.txt", &engine)
                 .unwrap_err();
@@ -436,11 +463,13 @@ mod tests {
             };
             assert_eq!(error, expected_err);
 
-            let cache = &cached_loader.cache;
-            assert_eq!(
-                cache.get("missing.txt").unwrap().as_ref().unwrap_err(),
-                &expected_err
-            );
+            {
+                let cache = cached_loader.cache.lock().unwrap();
+                assert_eq!(
+                    cache.get("missing.txt").unwrap().as_ref().unwrap_err(),
+                    &expected_err
+                );
+            }
 
             let error = cached_loader
                 .get_template(py, "missing.txt", &engine)
@@ -458,7 +487,7 @@ mod tests {
             let filesystem_loader =
                 FileSystemLoader::new(vec![PathBuf::from("tests/templates")], encoding_rs::UTF_8);
 
-            let mut cached_loader = CachedLoader::new(vec![Loader::FileSystem(filesystem_loader)]);
+            let cached_loader = CachedLoader::new(vec![Loader::FileSystem(filesystem_loader)]);
             let error = cached_loader
                 .get_template(py, "invalid.txt", &engine)
                 .unwrap()
@@ -491,7 +520,7 @@ mod tests {
                 .get_template(py, "index.html", &engine)
                 .unwrap()
                 .unwrap();
-            assert_eq!(template.template, "index".to_string());
+            assert_eq!(&*template.template, "index");
             assert_eq!(template.filename.unwrap(), PathBuf::from("index.html"));
         });
     }
@@ -519,6 +548,54 @@ mod tests {
         })
     }
 
+    #[test]
+    fn test_locmem_loader_case_sensitive() {
+        Python::initialize();
+
+        Python::attach(|py| {
+            let engine = EngineData::empty();
+            let mut templates: HashMap<String, String> = HashMap::new();
+            templates.insert("index.html".to_string(), "index".to_string());
+
+            let loader = LocMemLoader::new(templates);
+
+            let error = loader.get_template(py, "Index.html", &engine).unwrap_err();
+            assert_eq!(
+                error,
+                LoaderError {
+                    tried: vec![(
+                        "Index.html".to_string(),
+                        "Source does not exist".to_string(),
+                    )],
+                },
+            );
+        })
+    }
+
+    #[test]
+    fn test_locmem_loader_render() {
+        Python::initialize();
+
+        Python::attach(|py| {
+            let engine = EngineData::empty();
+            let mut templates: HashMap<String, String> = HashMap::new();
+            templates.insert("index.html".to_string(), "Hello {{ user }}!".to_string());
+
+            let loader = LocMemLoader::new(templates);
+
+            let template = loader
+                .get_template(py, "index.html", &engine)
+                .unwrap()
+                .unwrap();
+            let context = PyDict::new(py);
+            context.set_item("user", "Lily").unwrap();
+            assert_eq!(
+                template.render(py, Some(context), None).unwrap(),
+                "Hello Lily!"
+            );
+        })
+    }
+
     #[test]
     fn test_appdirs_loader() {
         Python::initialize();
@@ -1,31 +1,56 @@
 use std::borrow::Cow;
+use std::cell::RefCell;
 use std::collections::{HashMap, HashSet};
+use std::ffi::CString;
 use std::iter::Peekable;
-use std::sync::Arc;
+use std::sync::{Arc, LazyLock};
 
 use either::Either;
 use miette::{Diagnostic, SourceSpan};
 use num_bigint::BigInt;
+use pyo3::exceptions::PySyntaxWarning;
 use pyo3::intern;
 use pyo3::prelude::*;
+use regex::Regex;
 use thiserror::Error;
 
 use crate::filters::AddFilter;
 use crate::filters::AddSlashesFilter;
 use crate::filters::CapfirstFilter;
 use crate::filters::CenterFilter;
+use crate::filters::CutFilter;
 use crate::filters::DefaultFilter;
+use crate::filters::DefaultIfNoneFilter;
+use crate::filters::DictSortFilter;
 use crate::filters::EscapeFilter;
 use crate::filters::ExternalFilter;
 use crate::filters::FilterType;
+use crate::filters::FloatFormatFilter;
+use crate::filters::IntCommaFilter;
+use crate::filters::IntWordFilter;
+use crate::filters::JoinFilter;
+use crate::filters::JsonFilter;
+use crate::filters::LengthFilter;
+use crate::filters::LinebreaksbrFilter;
+use crate::filters::LjustFilter;
 use crate::filters::LowerFilter;
+use crate::filters::MakeListFilter;
+use crate::filters::NaturalTimeFilter;
+use crate::filters::OrdinalFilter;
+use crate::filters::PluralizeFilter;
+use crate::filters::RjustFilter;
 use crate::filters::SafeFilter;
+use crate::filters::SliceFilter;
 use crate::filters::SlugifyFilter;
+use crate::filters::TruncateWordsFilter;
+use crate::filters::UnicodeSlugifyFilter;
 use crate::filters::UpperFilter;
+use crate::filters::UrlizeFilter;
+use crate::filters::UrlizeTruncFilter;
 use crate::lex::START_TAG_LEN;
 use crate::lex::autoescape::{AutoescapeEnabled, AutoescapeError, lex_autoescape_argument};
 use crate::lex::common::{LexerError, text_content_at, translated_text_content_at};
-use crate::lex::core::{Lexer, TokenType};
+use crate::lex::core::{Lexer, Token, TokenType};
 use crate::lex::custom_tag::{
     SimpleTagLexer, SimpleTagLexerError, SimpleTagToken, SimpleTagTokenType,
 };
@@ -121,41 +146,135 @@ impl Filter {
                 Some(right) => FilterType::Center(CenterFilter::new(right)),
                 None => return Err(ParseError::MissingArgument { at: at.into() }),
             },
+            "cut" => match right {
+                Some(right) => FilterType::Cut(CutFilter::new(right)),
+                None => return Err(ParseError::MissingArgument { at: at.into() }),
+            },
             "default" => match right {
                 Some(right) => FilterType::Default(DefaultFilter::new(right)),
                 None => return Err(ParseError::MissingArgument { at: at.into() }),
             },
+            "default_if_none" => match right {
+                Some(right) => FilterType::DefaultIfNone(DefaultIfNoneFilter::new(right)),
+                None => return Err(ParseError::MissingArgument { at: at.into() }),
+            },
+            "dictsort" => match right {
+                Some(right) => FilterType::DictSort(DictSortFilter::new(right)),
+                None => return Err(ParseError::MissingArgument { at: at.into() }),
+            },
             "escape" => match right {
                 Some(right) => return Err(unexpected_argument("escape", right)),
                 None => FilterType::Escape(EscapeFilter),
             },
+            "floatformat" => FilterType::FloatFormat(FloatFormatFilter::new(right)),
+            "join" => match right {
+                Some(right) => FilterType::Join(JoinFilter::new(right)),
+                None => return Err(ParseError::MissingArgument { at: at.into() }),
+            },
+            // Not built into Django, so a loaded filter of the same name wins.
+            "json" if !parser.external_filters.contains_key("json") => match right {
+                Some(right) => return Err(unexpected_argument("json", right)),
+                None => FilterType::Json(JsonFilter),
+            },
+            "length" => match right {
+                Some(right) => return Err(unexpected_argument("length", right)),
+                None => FilterType::Length(LengthFilter),
+            },
+            "linebreaksbr" => match right {
+                Some(right) => return Err(unexpected_argument("linebreaksbr", right)),
+                None => FilterType::Linebreaksbr(LinebreaksbrFilter),
+            },
+            "ljust" => match right {
+                Some(right) => FilterType::Ljust(LjustFilter::new(right)),
+                None => return Err(ParseError::MissingArgument { at: at.into() }),
+            },
             "lower" => match right {
                 Some(right) => return Err(unexpected_argument("lower", right)),
                 None => FilterType::Lower(LowerFilter),
             },
+            "make_list" => match right {
+                Some(right) => return Err(unexpected_argument("make_list", right)),
+                None => FilterType::MakeList(MakeListFilter),
+            },
+            "pluralize" => FilterType::Pluralize(PluralizeFilter::new(right)),
+            "rjust" => match right {
+                Some(right) => FilterType::Rjust(RjustFilter::new(right)),
+                None => return Err(ParseError::MissingArgument { at: at.into() }),
+            },
             "safe" => match right {
                 Some(right) => return Err(unexpected_argument("safe", right)),
                 None => FilterType::Safe(SafeFilter),
             },
+            "slice" => match right {
+                Some(right) => FilterType::Slice(SliceFilter::new(right)),
+                None => return Err(ParseError::MissingArgument { at: at.into() }),
+            },
             "slugify" => match right {
                 Some(right) => return Err(unexpected_argument("slugify", right)),
                 None => FilterType::Slugify(SlugifyFilter),
             },
+            "truncatewords" => match right {
+                Some(right) => FilterType::TruncateWords(TruncateWordsFilter::new(right)),
+                None => return Err(ParseError::MissingArgument { at: at.into() }),
+            },
+            // Not built into Django, so a loaded filter of the same name wins.
+            "unicode_slugify" if !parser.external_filters.contains_key("unicode_slugify") => {
+                match right {
+                    Some(right) => return Err(unexpected_argument("unicode_slugify", right)),
+                    None => FilterType::UnicodeSlugify(UnicodeSlugifyFilter),
+                }
+            }
             "upper" => match right {
                 Some(right) => return Err(unexpected_argument("upper", right)),
                 None => FilterType::Upper(UpperFilter),
             },
+            "urlize" => match right {
+                Some(right) => return Err(unexpected_argument("urlize", right)),
+                None => FilterType::Urlize(UrlizeFilter),
+            },
+            "urlizetrunc" => match right {
+                Some(right) => FilterType::UrlizeTrunc(UrlizeTruncFilter::new(right)),
+                None => return Err(ParseError::MissingArgument { at: at.into() }),
+            },
             external => {
                 let external = match parser.external_filters.get(external) {
-                    Some(external) => external.clone().unbind(),
+                    Some(external) => external,
+                    None => match parser.find_filter_library(external) {
+                        Some(library) => {
+                            return Err(ParseError::FilterNotLoaded {
+                                at: at.into(),
+                                filter: external.to_string(),
+                                help: format!("Load it with {{% load {library} %}}"),
+                            });
+                        }
+                        None => {
+                            return Err(ParseError::InvalidFilter {
+                                at: at.into(),
+                                filter: external.to_string(),
+                            });
+                        }
+                    },
+                };
+                match native_filter(external) {
+                    Some(NativeFilter::IntComma) => {
+                        FilterType::IntComma(IntCommaFilter::new(right))
+                    }
+                    Some(NativeFilter::IntWord) => match right {
+                        Some(right) => return Err(unexpected_argument("intword", right)),
+                        None => FilterType::IntWord(IntWordFilter),
+                    },
+                    Some(NativeFilter::NaturalTime) => match right {
+                        Some(right) => return Err(unexpected_argument("naturaltime", right)),
+                        None => FilterType::NaturalTime(NaturalTimeFilter),
+                    },
+                    Some(NativeFilter::Ordinal) => match right {
+                        Some(right) => return Err(unexpected_argument("ordinal", right)),
+                        None => FilterType::Ordinal(OrdinalFilter),
+                    },
                     None => {
-                        return Err(ParseError::InvalidFilter {
-                            at: at.into(),
-                            filter: external.to_string(),
-                        });
+                        FilterType::External(ExternalFilter::new(external.clone().unbind(), right))
                     }
-                };
-                FilterType::External(ExternalFilter::new(external, right))
+                }
             }
         };
         Ok(Self { at, left, filter })
@@ -196,6 +315,52 @@ pub struct Url {
     pub variable: Option<String>,
 }
 
+#[derive(Clone, Debug, PartialEq)]
+pub struct Cache {
+    pub expire_time: TagElement,
+    pub fragment_name: String,
+    pub vary_on: Vec<TagElement>,
+    pub cache_name: Option<TagElement>,
+    pub nodes: Vec<TokenTree>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct WidthRatio {
+    pub value: TagElement,
+    pub max_value: TagElement,
+    pub max_width: TagElement,
+    pub asvar: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Now {
+    pub format: Text,
+    pub asvar: Option<String>,
+}
+
+/// A `{% block %}`, which templates extending this one can override.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Block {
+    pub name: String,
+    pub nodes: Vec<TokenTree>,
+    /// Whether the body refers to `block`, so `block.super` must be rendered.
+    pub uses_super: bool,
+}
+
+/// An `{% extends %}` tag, which takes the rest of the template with it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Extends {
+    pub parent: TagElement,
+    pub nodes: Vec<TokenTree>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Include {
+    pub template_name: TagElement,
+    pub variables: Vec<(String, TagElement)>,
+    pub only: bool,
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub enum IfCondition {
     Variable(TagElement),
@@ -254,7 +419,8 @@ fn parse_if_binding_power(
             let if_condition = parse_if_binding_power(parser, lexer, NOT_BINDING_POWER, token_at)?;
             IfCondition::Not(Box::new(if_condition))
         }
-        _ => {
+        // Listed explicitly, so a new kind of token can't silently reach here.
+        IfConditionTokenType::Operator(_) => {
             return Err(ParseError::InvalidIfPosition {
                 at: token.at.into(),
                 token: content.to_string(),
@@ -288,7 +454,25 @@ fn parse_if_binding_power(
             .next()
             .expect("already `break`ed in match peek()")
             .expect("already `return Err` in match peek()");
+        let rhs_at = match lexer.peek() {
+            Some(Ok(rhs_token)) => Some(rhs_token.at),
+            _ => None,
+        };
         let rhs = parse_if_binding_power(parser, lexer, binding_power, token.at)?;
+        if let (Some(rhs_at), IfConditionOperator::Is | IfConditionOperator::IsNot) =
+            (rhs_at, operator)
+            && is_literal(&rhs)
+        {
+            let (operator, suggestion) = match operator {
+                IfConditionOperator::Is => ("is", "=="),
+                _ => ("is not", "!="),
+            };
+            parser.warnings.push(IsLiteralWarning {
+                operator,
+                at: rhs_at.into(),
+                help: format!("Did you mean \"{suggestion}\"?"),
+            });
+        }
 
         lhs = operator.build_condition(lhs, rhs)
     }
@@ -296,6 +480,20 @@ fn parse_if_binding_power(
     Ok(lhs)
 }
 
+/// `is` compares identity, which is only meaningful for singletons like `None`,
+/// `True` and `False`. Comparing against a literal depends on Python's caching.
+fn is_literal(condition: &IfCondition) -> bool {
+    matches!(
+        condition,
+        IfCondition::Variable(
+            TagElement::Int(_)
+                | TagElement::Float(_)
+                | TagElement::Text(_)
+                | TagElement::TranslatedText(_)
+        )
+    )
+}
+
 const NOT_BINDING_POWER: u8 = 8;
 
 impl IfConditionOperator {
@@ -370,18 +568,9 @@ fn parse_for_loop(
         if parser.template.content(last.at) != "in" {
             return Err(error.into());
         }
-        let len = variable_names.len();
-        match error {
-            ForLexerInError::MissingComma { .. } if len >= 2 => {
-                let previous = &variable_names[len - 2];
-                let at = previous.at.into();
-                return Err(ForParseError::MissingVariable { at }.into());
-            }
-            _ => {
-                let at = last.at.into();
-                return Err(ForParseError::MissingVariableBeforeIn { at }.into());
-            }
-        }
+        // A trailing comma before `in` is reported by the lexer.
+        let at = last.at.into();
+        return Err(ForParseError::MissingVariableBeforeIn { at }.into());
     }
 
     let expression_token = lexer.lex_expression()?;
@@ -428,6 +617,10 @@ pub struct For {
     pub reversed: bool,
     pub body: Vec<TokenTree>,
     pub empty: Option<Vec<TokenTree>>,
+    /// Whether the body uses `forloop.revcounter`, `forloop.revcounter0` or
+    /// the whole `forloop`. Only then must the iterable be materialized to
+    /// find its length; otherwise generators are consumed lazily.
+    pub uses_length: bool,
 }
 
 #[derive(Clone, Debug)]
@@ -482,32 +675,67 @@ impl PartialEq for SimpleBlockTag {
     }
 }
 
+#[derive(Clone, Debug, PartialEq)]
+pub struct BlockTranslate {
+    /// The message id passed to `gettext`, with `%(name)s` placeholders.
+    pub message: String,
+    pub variables: Vec<String>,
+    pub asvar: Option<String>,
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub enum Tag {
     Autoescape {
         enabled: AutoescapeEnabled,
         nodes: Vec<TokenTree>,
     },
+    Block(Arc<Block>),
+    BlockTranslate(BlockTranslate),
+    Cache(Cache),
+    CsrfToken,
+    Extends(Extends),
+    GetCurrentTimezone {
+        variable: String,
+    },
     If {
         condition: IfCondition,
         truthy: Vec<TokenTree>,
         falsey: Option<Vec<TokenTree>>,
     },
     For(For),
+    Include(Include),
     Load,
+    Now(Now),
     SimpleTag(SimpleTag),
     SimpleBlockTag(SimpleBlockTag),
+    Spaceless {
+        nodes: Vec<TokenTree>,
+    },
+    Timezone {
+        timezone: TagElement,
+        nodes: Vec<TokenTree>,
+    },
     Url(Url),
+    WidthRatio(WidthRatio),
+    With {
+        variables: Vec<(String, TagElement)>,
+        nodes: Vec<TokenTree>,
+    },
 }
 
-#[derive(PartialEq, Eq)]
+#[derive(Clone, PartialEq, Eq)]
 enum EndTagType {
     Autoescape,
+    EndBlock,
     Elif,
     Else,
     EndIf,
+    EndIfEqual,
+    EndIfNotEqual,
     Empty,
     EndFor,
+    EndSpaceless,
+    EndWith,
     Verbatim,
     Custom(String),
 }
@@ -516,11 +744,16 @@ impl EndTagType {
     fn as_cow(&self) -> Cow<'static, str> {
         let end_tag = match self {
             Self::Autoescape => "endautoescape",
+            Self::EndBlock => "endblock",
             Self::Elif => "elif",
             Self::Else => "else",
             Self::EndIf => "endif",
+            Self::EndIfEqual => "endifequal",
+            Self::EndIfNotEqual => "endifnotequal",
             Self::Empty => "empty",
             Self::EndFor => "endfor",
+            Self::EndSpaceless => "endspaceless",
+            Self::EndWith => "endwith",
             Self::Verbatim => "endverbatim",
             Self::Custom(s) => return Cow::Owned(s.clone()),
         };
@@ -588,11 +821,6 @@ impl From<Either<Variable, ForVariable>> for ArgumentType {
 #[allow(clippy::enum_variant_names)]
 #[derive(Error, Debug, Diagnostic, PartialEq, Eq)]
 pub enum ForParseError {
-    #[error("Expected another variable when unpacking in for loop:")]
-    MissingVariable {
-        #[label("after this variable")]
-        at: SourceSpan,
-    },
     #[error("Expected a variable name before the 'in' keyword:")]
     MissingVariableBeforeIn {
         #[label("before this keyword")]
@@ -607,6 +835,36 @@ pub enum ForParseError {
 
 #[derive(Error, Debug, Diagnostic, PartialEq, Eq)]
 pub enum ParseError {
+    #[error("'{tag}' doesn't allow other block tags (seen '{seen}') inside it")]
+    BlockTranslateInnerTag {
+        tag: &'static str,
+        seen: String,
+        #[label("unexpected tag")]
+        at: SourceSpan,
+        #[label("start tag")]
+        start_at: SourceSpan,
+    },
+    #[error("No argument provided to the '{tag}' tag for the asvar option.")]
+    BlockTranslateMissingAsvar {
+        tag: &'static str,
+        #[label("here")]
+        at: SourceSpan,
+    },
+    #[error("The '{option}' option was specified more than once.")]
+    RepeatedOption {
+        option: String,
+        #[label("second")]
+        at: SourceSpan,
+        #[label("first")]
+        first_at: SourceSpan,
+    },
+    #[error("Unknown argument for '{tag}' tag: '{argument}'.")]
+    UnknownArgument {
+        tag: &'static str,
+        argument: String,
+        #[label("here")]
+        at: SourceSpan,
+    },
     #[error("Empty block tag")]
     EmptyTag {
         #[label("here")]
@@ -659,6 +917,20 @@ pub enum ParseError {
         #[label("here")]
         at: SourceSpan,
     },
+    #[error("Invalid filter: '{filter}'")]
+    FilterNotLoaded {
+        filter: String,
+        #[label("here")]
+        at: SourceSpan,
+        #[help]
+        help: String,
+    },
+    #[error("'{tag}' takes two arguments")]
+    IfEqualArguments {
+        tag: &'static str,
+        #[label("here")]
+        at: SourceSpan,
+    },
     #[error("Not expecting '{token}' in this position")]
     InvalidIfPosition {
         token: String,
@@ -670,6 +942,13 @@ pub enum ParseError {
         #[label("here")]
         at: SourceSpan,
     },
+    #[error("'{tag}' is a legacy tag and is not enabled")]
+    #[diagnostic(help("Set the 'legacy_tags' engine option to use it"))]
+    LegacyTagDisabled {
+        tag: &'static str,
+        #[label("here")]
+        at: SourceSpan,
+    },
     #[error("Missing boolean expression")]
     MissingBooleanExpression {
         #[label("here")]
@@ -770,6 +1049,27 @@ pub enum ParseError {
         #[label("unexpected argument")]
         at: SourceSpan,
     },
+    #[error("widthratio takes at least three arguments")]
+    WidthRatioArguments {
+        #[label("here")]
+        at: SourceSpan,
+    },
+    #[error("Invalid syntax in widthratio tag. Expecting 'as' keyword")]
+    WidthRatioExpectedAs {
+        #[label("here")]
+        at: SourceSpan,
+    },
+    #[error("'now' statement takes one argument")]
+    NowArguments {
+        #[label("here")]
+        at: SourceSpan,
+    },
+    #[error("Block tags are nested more than {max_depth} levels deep")]
+    TooDeeplyNested {
+        max_depth: usize,
+        #[label("here")]
+        at: SourceSpan,
+    },
     #[error("Unexpected end of expression")]
     UnexpectedEndExpression {
         #[label("after this")]
@@ -792,6 +1092,71 @@ pub enum ParseError {
         #[label("here")]
         at: SourceSpan,
     },
+    #[error("'cache' tag requires at least 2 arguments.")]
+    CacheArguments {
+        #[label("here")]
+        at: SourceSpan,
+    },
+    #[error("'get_current_timezone' requires 'as variable' (got [{args}])")]
+    GetCurrentTimezoneArguments {
+        args: String,
+        #[label("here")]
+        at: SourceSpan,
+    },
+    #[error("'timezone' takes one argument (timezone)")]
+    TimezoneArguments {
+        #[label("here")]
+        at: SourceSpan,
+    },
+    #[error("'include' tag takes at least one argument: the name of the template to be included.")]
+    IncludeMissingTemplate {
+        #[label("here")]
+        at: SourceSpan,
+    },
+    #[error("\"with\" in 'include' tag needs at least one keyword argument.")]
+    IncludeWithMissingKeyword {
+        #[label("here")]
+        at: SourceSpan,
+    },
+    #[error("'block' tag takes only one argument")]
+    BlockArguments {
+        #[label("here")]
+        at: SourceSpan,
+    },
+    #[error("'block' tag with name '{name}' appears more than once")]
+    DuplicateBlock {
+        name: String,
+        #[label("second")]
+        at: SourceSpan,
+        #[label("first")]
+        first_at: SourceSpan,
+    },
+    #[error("'extends' takes one argument")]
+    ExtendsArguments {
+        #[label("here")]
+        at: SourceSpan,
+    },
+    #[error("'extends' must be the first tag in the template")]
+    ExtendsNotFirst {
+        #[label("here")]
+        at: SourceSpan,
+    },
+    #[error("'extends' cannot appear more than once in the same template")]
+    RepeatedExtends {
+        #[label("here")]
+        at: SourceSpan,
+    },
+    #[error("'with' received an invalid token: '{token}'")]
+    WithInvalidToken {
+        token: String,
+        #[label("here")]
+        at: SourceSpan,
+    },
+    #[error("'with' expected at least one variable assignment")]
+    WithMissingAssignment {
+        #[label("here")]
+        at: SourceSpan,
+    },
     #[error("Unexpected tag {unexpected}, expected {expected}")]
     WrongEndTag {
         unexpected: Cow<'static, str>,
@@ -803,6 +1168,17 @@ pub enum ParseError {
     },
 }
 
+#[derive(Error, Debug, Diagnostic, PartialEq, Eq)]
+#[error("\"{operator}\" with a literal is unreliable")]
+#[diagnostic(severity(Warning))]
+pub struct IsLiteralWarning {
+    operator: &'static str,
+    #[label("literal")]
+    at: SourceSpan,
+    #[help]
+    help: String,
+}
+
 #[derive(Error, Debug)]
 pub enum PyParseError {
     #[error(transparent)]
@@ -867,6 +1243,14 @@ struct SimpleTagContext<'py> {
 
 #[derive(Clone)]
 enum TagContext<'py> {
+    /// A tag from one of Django's libraries that we implement natively.
+    BlockTranslate,
+    Cache,
+    GetCurrentTimezone,
+    Timezone,
+    /// A tag we can't handle yet. We only raise if the tag is actually used, so
+    /// libraries containing such tags can still be loaded.
+    Unsupported(&'static str),
     Simple(SimpleTagContext<'py>),
     SimpleBlock {
         end_tag_name: String,
@@ -875,6 +1259,111 @@ enum TagContext<'py> {
     EndSimpleBlock,
 }
 
+static TRIM_WHITESPACE_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\s*\n\s*").expect("Static string will never panic"));
+
+/// Find tags from Django's own libraries which we implement in Rust.
+fn native_tag<'py>(tag: &Bound<'py, PyAny>) -> Option<TagContext<'py>> {
+    let py = tag.py();
+    let module = tag.getattr(intern!(py, "__module__")).ok()?;
+    let name = tag.getattr(intern!(py, "__name__")).ok()?;
+    match (module.extract::<&str>().ok()?, name.extract::<&str>().ok()?) {
+        ("django.templatetags.i18n", "do_block_translate") => Some(TagContext::BlockTranslate),
+        ("django.templatetags.cache", "do_cache") => Some(TagContext::Cache),
+        ("django.templatetags.tz", "get_current_timezone_tag") => {
+            Some(TagContext::GetCurrentTimezone)
+        }
+        ("django.templatetags.tz", "timezone_tag") => Some(TagContext::Timezone),
+        _ => None,
+    }
+}
+
+/// Filters from Django's own libraries which we implement in Rust.
+enum NativeFilter {
+    IntComma,
+    IntWord,
+    NaturalTime,
+    Ordinal,
+}
+
+/// Find filters from Django's own libraries which we implement in Rust.
+///
+/// Like any other library filter, these are only available once the library
+/// has been loaded.
+fn native_filter(filter: &Bound<'_, PyAny>) -> Option<NativeFilter> {
+    let py = filter.py();
+    let module = filter.getattr(intern!(py, "__module__")).ok()?;
+    let name = filter.getattr(intern!(py, "__name__")).ok()?;
+    match (module.extract::<&str>().ok()?, name.extract::<&str>().ok()?) {
+        ("django.contrib.humanize.templatetags.humanize", "intcomma") => {
+            Some(NativeFilter::IntComma)
+        }
+        ("django.contrib.humanize.templatetags.humanize", "intword") => Some(NativeFilter::IntWord),
+        ("django.contrib.humanize.templatetags.humanize", "naturaltime") => {
+            Some(NativeFilter::NaturalTime)
+        }
+        ("django.contrib.humanize.templatetags.humanize", "ordinal") => Some(NativeFilter::Ordinal),
+        _ => None,
+    }
+}
+
+/// The default limit on how deeply block tags can be nested.
+pub const DEFAULT_MAX_DEPTH: usize = 128;
+
+/// The kind of an [`AstItem`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AstKind {
+    Text,
+    Comment,
+    Variable,
+    Tag,
+    Error,
+}
+
+/// A top-level span of a template, as reported by [`Parser::parse_to_ast`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AstItem {
+    pub kind: AstKind,
+    /// The byte offset and length. A block tag spans up to its end tag.
+    pub at: (usize, usize),
+    /// The variable's expression, the tag's name or the error message.
+    pub detail: String,
+}
+
+/// The result of [`Parser::parse_to_ast`].
+#[derive(Debug, Default)]
+pub struct TemplateAst {
+    /// The nodes which parsed successfully.
+    pub nodes: Vec<TokenTree>,
+    /// Every top-level span and error, in template order.
+    pub items: Vec<AstItem>,
+}
+
+impl TemplateAst {
+    pub fn diagnostics(&self) -> impl Iterator<Item = &AstItem> {
+        self.items.iter().filter(|item| item.kind == AstKind::Error)
+    }
+
+    fn push_error(&mut self, error: PyParseError, fallback_at: (usize, usize)) {
+        let (at, detail) = match error {
+            PyParseError::ParseError(error) => {
+                let at = error
+                    .labels()
+                    .and_then(|mut labels| labels.next())
+                    .map(|label| (label.offset(), label.len()))
+                    .unwrap_or(fallback_at);
+                (at, error.to_string())
+            }
+            PyParseError::PyErr(error) => (fallback_at, error.to_string()),
+        };
+        self.items.push(AstItem {
+            kind: AstKind::Error,
+            at,
+            detail,
+        });
+    }
+}
+
 pub struct Parser<'t, 'l, 'py> {
     py: Python<'py>,
     template: TemplateString<'t>,
@@ -882,7 +1371,20 @@ pub struct Parser<'t, 'l, 'py> {
     libraries: &'l HashMap<String, Py<PyAny>>,
     external_tags: HashMap<String, TagContext<'py>>,
     external_filters: HashMap<String, Bound<'py, PyAny>>,
-    forloop_depth: usize,
+    /// For each enclosing `for` loop, whether its body uses the loop length.
+    forloops: RefCell<Vec<bool>>,
+    /// For each enclosing `block` tag, whether its body uses `block.super`.
+    block_supers: RefCell<Vec<bool>>,
+    /// Where each `block` tag started, to reject repeated names.
+    block_names: HashMap<String, (usize, usize)>,
+    blocks: HashMap<String, Arc<Block>>,
+    /// Whether a tag or variable has been parsed, so `extends` is too late.
+    seen_nontext: bool,
+    seen_extends: bool,
+    warnings: Vec<IsLiteralWarning>,
+    legacy_tags: bool,
+    depth: usize,
+    max_depth: usize,
 }
 
 impl<'t, 'l, 'py> Parser<'t, 'l, 'py> {
@@ -898,10 +1400,39 @@ impl<'t, 'l, 'py> Parser<'t, 'l, 'py> {
             libraries,
             external_tags: HashMap::new(),
             external_filters: HashMap::new(),
-            forloop_depth: 0,
+            forloops: RefCell::new(Vec::new()),
+            block_supers: RefCell::new(Vec::new()),
+            block_names: HashMap::new(),
+            blocks: HashMap::new(),
+            seen_nontext: false,
+            seen_extends: false,
+            warnings: Vec::new(),
+            legacy_tags: false,
+            depth: 0,
+            max_depth: DEFAULT_MAX_DEPTH,
         }
     }
 
+    /// Report a `{{`, `{%` or `{#` that is never closed as an error instead
+    /// of treating it as text.
+    pub fn with_unclosed_errors(mut self, unclosed_errors: bool) -> Self {
+        self.lexer = Lexer::new(self.template).with_unclosed_errors(unclosed_errors);
+        self
+    }
+
+    /// Limit how deeply block tags can be nested, so pathological templates
+    /// fail to parse instead of overflowing the stack.
+    pub fn with_max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// Enable tags that Django has removed, such as `ifequal`.
+    pub fn with_legacy_tags(mut self, legacy_tags: bool) -> Self {
+        self.legacy_tags = legacy_tags;
+        self
+    }
+
     #[cfg(test)]
     fn new_with_filters(
         py: Python<'py>,
@@ -910,43 +1441,102 @@ impl<'t, 'l, 'py> Parser<'t, 'l, 'py> {
         external_filters: HashMap<String, Bound<'py, PyAny>>,
     ) -> Self {
         Self {
-            py,
-            template,
-            lexer: Lexer::new(template),
-            libraries,
-            external_tags: HashMap::new(),
             external_filters,
-            forloop_depth: 0,
+            ..Self::new(py, template, libraries)
         }
     }
 
     pub fn parse(&mut self) -> Result<Vec<TokenTree>, PyParseError> {
         let mut nodes = Vec::new();
+        while let Some(token) = self.lexer.next().transpose().map_err(ParseError::from)? {
+            if let Some(node) = self.parse_top_level(token)? {
+                nodes.push(node)
+            }
+        }
+        Ok(nodes)
+    }
+
+    /// The `block` tags parsed so far, by name, for `extends` to look up.
+    pub fn take_blocks(&mut self) -> HashMap<String, Arc<Block>> {
+        std::mem::take(&mut self.blocks)
+    }
+
+    /// Parse the whole template for tooling such as a language server.
+    ///
+    /// Unlike [`Parser::parse`], this doesn't stop at the first error. Each
+    /// top-level token that fails to parse is reported as an
+    /// [`AstKind::Error`] item and parsing resumes after it. An error inside
+    /// a block abandons the rest of that block, so its end tag may be
+    /// reported as well.
+    pub fn parse_to_ast(&mut self) -> TemplateAst {
+        let mut ast = TemplateAst::default();
         while let Some(token) = self.lexer.next() {
-            let node = match token.token_type {
-                TokenType::Text => TokenTree::Text(Text::new(token.at)),
-                TokenType::Comment => continue,
-                TokenType::Variable => self
-                    .parse_variable(
-                        token.content(self.template),
-                        token.at,
-                        token.at.0 + START_TAG_LEN,
-                    )?
-                    .into(),
-                TokenType::Tag => match self.parse_tag(token.content(self.template), token.at)? {
-                    Either::Left(token_tree) => token_tree,
-                    Either::Right(end_tag) => {
-                        return Err(ParseError::UnexpectedEndTag {
-                            at: end_tag.at.into(),
-                            unexpected: end_tag.as_cow(),
-                        }
-                        .into());
-                    }
-                },
+            let token = match token {
+                Ok(token) => token,
+                Err(error) => {
+                    ast.push_error(ParseError::from(error).into(), (self.lexer.position(), 0));
+                    continue;
+                }
             };
-            nodes.push(node)
+            let (kind, detail) = match token.token_type {
+                TokenType::Text => (AstKind::Text, String::new()),
+                TokenType::Comment => (AstKind::Comment, String::new()),
+                TokenType::Variable => (
+                    AstKind::Variable,
+                    token.content(self.template).trim().to_string(),
+                ),
+                TokenType::Tag => (
+                    AstKind::Tag,
+                    token
+                        .content(self.template)
+                        .split_whitespace()
+                        .next()
+                        .unwrap_or_default()
+                        .to_string(),
+                ),
+            };
+            let start = token.at.0;
+            let token_at = token.at;
+            let result = self.parse_top_level(token);
+            let at = (start, self.lexer.position() - start);
+            ast.items.push(AstItem { kind, at, detail });
+            match result {
+                Ok(node) => ast.nodes.extend(node),
+                Err(error) => ast.push_error(error, token_at),
+            }
         }
-        Ok(nodes)
+        ast.items.sort_by_key(|item| item.at.0);
+        ast
+    }
+
+    fn parse_top_level(&mut self, token: Token) -> Result<Option<TokenTree>, PyParseError> {
+        let node = match token.token_type {
+            TokenType::Text => TokenTree::Text(Text::new(token.at)),
+            TokenType::Comment => return Ok(None),
+            TokenType::Variable => self
+                .parse_variable(
+                    token.content(self.template),
+                    token.at,
+                    token.at.0 + START_TAG_LEN,
+                )?
+                .into(),
+            TokenType::Tag => match self.parse_tag(token.content(self.template), token.at)? {
+                Either::Left(token_tree) => token_tree,
+                Either::Right(end_tag) => {
+                    return Err(ParseError::UnexpectedEndTag {
+                        at: end_tag.at.into(),
+                        unexpected: end_tag.as_cow(),
+                    }
+                    .into());
+                }
+            },
+        };
+        // Like Django, any tag is too early for `extends`, even one like
+        // `{% comment %}` which parses to text.
+        if matches!(token.token_type, TokenType::Tag) || !matches!(node, TokenTree::Text(_)) {
+            self.seen_nontext = true;
+        }
+        Ok(Some(node))
     }
 
     fn parse_until(
@@ -954,9 +1544,28 @@ impl<'t, 'l, 'py> Parser<'t, 'l, 'py> {
         until: Vec<EndTagType>,
         start: Cow<'static, str>,
         start_at: (usize, usize),
+    ) -> Result<(Vec<TokenTree>, EndTag), PyParseError> {
+        if self.depth >= self.max_depth {
+            return Err(ParseError::TooDeeplyNested {
+                max_depth: self.max_depth,
+                at: start_at.into(),
+            }
+            .into());
+        }
+        self.depth += 1;
+        let parsed = self.parse_block(until, start, start_at);
+        self.depth -= 1;
+        parsed
+    }
+
+    fn parse_block(
+        &mut self,
+        until: Vec<EndTagType>,
+        start: Cow<'static, str>,
+        start_at: (usize, usize),
     ) -> Result<(Vec<TokenTree>, EndTag), PyParseError> {
         let mut nodes = Vec::new();
-        while let Some(token) = self.lexer.next() {
+        while let Some(token) = self.lexer.next().transpose().map_err(ParseError::from)? {
             let node = match token.token_type {
                 TokenType::Text => TokenTree::Text(Text::new(token.at)),
                 TokenType::Comment => continue,
@@ -1004,7 +1613,13 @@ impl<'t, 'l, 'py> Parser<'t, 'l, 'py> {
 
     fn parse_for_variable(&self, at: (usize, usize)) -> Either<Variable, ForVariable> {
         let mut parts = self.template.content(at).split('.');
-        if self.forloop_depth == 0
+        if parts.clone().next().map(str::trim) == Some("block")
+            && let Some(uses_super) = self.block_supers.borrow_mut().last_mut()
+        {
+            *uses_super = true;
+        }
+        let forloop_depth = self.forloops.borrow().len();
+        if forloop_depth == 0
             || parts
                 .next()
                 .expect("a variable can always be split into at least one part")
@@ -1014,6 +1629,8 @@ impl<'t, 'l, 'py> Parser<'t, 'l, 'py> {
             return Either::Left(Variable::new(at));
         }
         let Some(part) = parts.next_back() else {
+            // Rendering `forloop` includes the lengths of the enclosing loops.
+            self.forloops.borrow_mut().fill(true);
             return Either::Right(ForVariable {
                 variant: ForVariableName::Object,
                 parent_count: 0,
@@ -1039,10 +1656,21 @@ impl<'t, 'l, 'py> Parser<'t, 'l, 'py> {
         if variant == ForVariableName::Object {
             parent_count += 1;
         }
-        if parent_count > self.forloop_depth {
+        if parent_count > forloop_depth {
             return Either::Left(Variable::new(at));
         }
-        Either::Right(ForVariable {
+        let mut forloops = self.forloops.borrow_mut();
+        match variant {
+            ForVariableName::RevCounter | ForVariableName::RevCounter0 => {
+                if let Some(index) = (forloop_depth - parent_count).checked_sub(1) {
+                    forloops[index] = true;
+                }
+            }
+            // Rendering `forloop` includes the lengths of the enclosing loops.
+            ForVariableName::Object => forloops.fill(true),
+            _ => {}
+        }
+        Either::Right(ForVariable {
             variant,
             parent_count,
         })
@@ -1089,68 +1717,101 @@ impl<'t, 'l, 'py> Parser<'t, 'l, 'py> {
         let Some((tag, parts)) = maybe_tag else {
             return Err(ParseError::EmptyTag { at: at.into() }.into());
         };
-        Ok(match self.template.content(tag.at) {
-            "url" => Either::Left(self.parse_url(at, parts)?),
-            "load" => Either::Left(self.parse_load(at, parts)?),
-            "autoescape" => Either::Left(self.parse_autoescape(at, parts)?),
-            "endautoescape" => Either::Right(EndTag {
-                end: EndTagType::Autoescape,
-                at,
-                parts,
-            }),
-            "endverbatim" => Either::Right(EndTag {
-                end: EndTagType::Verbatim,
-                at,
-                parts,
-            }),
-            "if" => Either::Left(self.parse_if(at, parts, "if")?),
-            "elif" => Either::Right(EndTag {
-                end: EndTagType::Elif,
-                at,
-                parts,
-            }),
-            "else" => Either::Right(EndTag {
-                end: EndTagType::Else,
-                at,
-                parts,
-            }),
-            "endif" => Either::Right(EndTag {
-                end: EndTagType::EndIf,
-                at,
-                parts,
-            }),
-            "for" => Either::Left(self.parse_for(at, parts)?),
-            "empty" => Either::Right(EndTag {
-                end: EndTagType::Empty,
+        let tag_name = self.template.content(tag.at);
+        // Like Django, tags from an explicitly loaded library take precedence
+        // over the built-in tags.
+        if let Some(context) = self.external_tags.get(tag_name).cloned() {
+            return self.parse_external_tag(tag_name, context, at, parts);
+        }
+        // Keep the match arms free of temporaries, since this is part of the
+        // recursion for nested tags and each one adds to the stack frame.
+        let end = match tag_name {
+            "endautoescape" => EndTagType::Autoescape,
+            "endblock" => EndTagType::EndBlock,
+            "endverbatim" => EndTagType::Verbatim,
+            "elif" => EndTagType::Elif,
+            "else" => EndTagType::Else,
+            "endif" => EndTagType::EndIf,
+            "empty" => EndTagType::Empty,
+            "endfor" => EndTagType::EndFor,
+            "endifequal" => EndTagType::EndIfEqual,
+            "endifnotequal" => EndTagType::EndIfNotEqual,
+            "endspaceless" => EndTagType::EndSpaceless,
+            "endwith" => EndTagType::EndWith,
+            _ => {
+                return self.parse_start_tag(tag_name, at, parts).map(Either::Left);
+            }
+        };
+        Ok(Either::Right(EndTag { end, at, parts }))
+    }
+
+    fn parse_start_tag(
+        &mut self,
+        tag_name: &'t str,
+        at: (usize, usize),
+        parts: TagParts,
+    ) -> Result<TokenTree, PyParseError> {
+        match tag_name {
+            "url" => Ok(self.parse_url(at, parts)?),
+            "load" => self.parse_load(at, parts),
+            "autoescape" => self.parse_autoescape(at, parts),
+            "block" => self.parse_block_tag(at, parts),
+            "extends" => self.parse_extends(at, parts),
+            "verbatim" => self.parse_verbatim(at),
+            "comment" => self.parse_comment(at),
+            "if" => self.parse_if(at, parts, "if"),
+            "for" => self.parse_for(at, parts),
+            "ifequal" => self.parse_if_equal(at, parts, "ifequal"),
+            "ifnotequal" => self.parse_if_equal(at, parts, "ifnotequal"),
+            "include" => self.parse_include(at, parts),
+            "now" => self.parse_now(at, parts),
+            "widthratio" => self.parse_width_ratio(at, parts),
+            "spaceless" => self.parse_spaceless(at),
+            "with" => self.parse_with(at, parts),
+            "csrf_token" => Ok(TokenTree::Tag(Tag::CsrfToken)),
+            tag_name => todo!("{tag_name}"),
+        }
+    }
+
+    fn parse_external_tag(
+        &mut self,
+        tag_name: &'t str,
+        context: TagContext<'py>,
+        at: (usize, usize),
+        parts: TagParts,
+    ) -> Result<Either<TokenTree, EndTag>, PyParseError> {
+        Ok(match context {
+            TagContext::BlockTranslate => {
+                let tag_name = match tag_name {
+                    "blocktrans" => "blocktrans",
+                    _ => "blocktranslate",
+                };
+                Either::Left(self.parse_block_translate(at, parts, tag_name)?)
+            }
+            TagContext::Cache => Either::Left(self.parse_cache(at, parts)?),
+            TagContext::GetCurrentTimezone => {
+                Either::Left(self.parse_get_current_timezone(at, parts)?)
+            }
+            TagContext::Timezone => Either::Left(self.parse_timezone(at, parts)?),
+            TagContext::Unsupported(reason) => todo!("{reason}"),
+            TagContext::Simple(context) => {
+                Either::Left(self.parse_simple_tag(&context, at, parts)?)
+            }
+            TagContext::SimpleBlock {
+                context,
+                end_tag_name,
+            } => Either::Left(self.parse_simple_block_tag(
+                context,
+                tag_name.to_string(),
+                end_tag_name,
                 at,
                 parts,
-            }),
-            "endfor" => Either::Right(EndTag {
-                end: EndTagType::EndFor,
+            )?),
+            TagContext::EndSimpleBlock => Either::Right(EndTag {
+                end: EndTagType::Custom(tag_name.to_string()),
                 at,
                 parts,
             }),
-            tag_name => match self.external_tags.get(tag_name) {
-                Some(TagContext::Simple(context)) => {
-                    Either::Left(self.parse_simple_tag(context, at, parts)?)
-                }
-                Some(TagContext::SimpleBlock {
-                    context,
-                    end_tag_name,
-                }) => Either::Left(self.parse_simple_block_tag(
-                    context.clone(),
-                    tag_name.to_string(),
-                    end_tag_name.clone(),
-                    at,
-                    parts,
-                )?),
-                Some(TagContext::EndSimpleBlock) => Either::Right(EndTag {
-                    end: EndTagType::Custom(tag_name.to_string()),
-                    at,
-                    parts,
-                }),
-                None => todo!("{tag_name}"),
-            },
         })
     }
 
@@ -1349,8 +2010,21 @@ impl<'t, 'l, 'py> Parser<'t, 'l, 'py> {
         tag: &Bound<'py, PyAny>,
     ) -> Result<(), PyParseError> {
         let closure = tag.getattr("__closure__")?;
-        let tag = if closure.is_none() {
-            todo!("Fully custom tag")
+        let tag = if let Some(tag) = native_tag(tag) {
+            match tag {
+                TagContext::Cache => {
+                    self.external_tags
+                        .insert("endcache".to_string(), TagContext::EndSimpleBlock);
+                }
+                TagContext::Timezone => {
+                    self.external_tags
+                        .insert("endtimezone".to_string(), TagContext::EndSimpleBlock);
+                }
+                _ => {}
+            }
+            tag
+        } else if closure.is_none() {
+            TagContext::Unsupported("Fully custom tag")
         } else {
             let tag_code = tag.getattr("__code__")?;
             let closure_names: Vec<String> = tag_code.getattr("co_freevars")?.extract()?;
@@ -1379,7 +2053,7 @@ impl<'t, 'l, 'py> Parser<'t, 'l, 'py> {
             }
 
             if closure_names.contains(&"filename".to_string()) {
-                todo!("Inclusion tag")
+                TagContext::Unsupported("Inclusion tag")
             } else if closure_names.contains(&"end_name".to_string()) {
                 let defaults_count = get_defaults_count(&closure_values[0])?;
                 let end_tag_name: String = closure_values[1].extract()?;
@@ -1489,6 +2163,32 @@ impl<'t, 'l, 'py> Parser<'t, 'l, 'py> {
         library.getattr(intern!(self.py, "tags"))?.extract()
     }
 
+    /// Emit a `SyntaxWarning` for template code that is valid but likely a mistake.
+    /// These can be silenced or turned into errors with Python's warning filters.
+    fn warn(&self, warning: IsLiteralWarning) -> PyResult<()> {
+        let report = miette::Report::new(warning).with_source_code(self.template.0.to_string());
+        let message = CString::new(format!("{report:?}"))?;
+        let category = self.py.get_type::<PySyntaxWarning>();
+        PyErr::warn(self.py, &category, &message, 1)
+    }
+
+    /// Find a library providing `filter`, so we can suggest loading it when it's
+    /// used before (or without) the corresponding `{% load %}`.
+    fn find_filter_library(&self, filter: &str) -> Option<&'l str> {
+        let mut libraries: Vec<_> = self.libraries.iter().collect();
+        libraries.sort_unstable_by_key(|(name, _)| name.as_str());
+        libraries.into_iter().find_map(|(name, library)| {
+            let filters = library
+                .bind(self.py)
+                .getattr(intern!(self.py, "filters"))
+                .ok()?;
+            match filters.contains(filter) {
+                Ok(true) => Some(name.as_str()),
+                _ => None,
+            }
+        })
+    }
+
     fn get_filters(
         &mut self,
         library: &Bound<'py, PyAny>,
@@ -1557,6 +2257,100 @@ impl<'t, 'l, 'py> Parser<'t, 'l, 'py> {
         Ok(TokenTree::Tag(Tag::Url(url)))
     }
 
+    fn parse_block_translate(
+        &mut self,
+        at: (usize, usize),
+        parts: TagParts,
+        tag_name: &'static str,
+    ) -> Result<TokenTree, PyParseError> {
+        let content = self.template.content(parts.at);
+        let mut options = content
+            .split_whitespace()
+            .map(|option| {
+                let start = parts.at.0 + option.as_ptr() as usize - content.as_ptr() as usize;
+                (option, (start, option.len()))
+            })
+            .peekable();
+        let mut trimmed = None;
+        let mut asvar = None;
+        let mut seen: HashMap<&str, (usize, usize)> = HashMap::new();
+        while let Some((option, option_at)) = options.next() {
+            if let Some(first_at) = seen.insert(option, option_at) {
+                return Err(ParseError::RepeatedOption {
+                    option: option.to_string(),
+                    at: option_at.into(),
+                    first_at: first_at.into(),
+                }
+                .into());
+            }
+            match option {
+                "trimmed" => trimmed = Some(option_at),
+                "asvar" => match options.next() {
+                    Some((name, _)) => asvar = Some(name.to_string()),
+                    None => {
+                        return Err(ParseError::BlockTranslateMissingAsvar {
+                            tag: tag_name,
+                            at: option_at.into(),
+                        }
+                        .into());
+                    }
+                },
+                "with" | "count" | "context" => todo!("{tag_name} {option}"),
+                _ => {
+                    return Err(ParseError::UnknownArgument {
+                        tag: tag_name,
+                        argument: option.to_string(),
+                        at: option_at.into(),
+                    }
+                    .into());
+                }
+            }
+        }
+
+        let mut message = String::new();
+        let mut variables = Vec::new();
+        let end_tag = format!("end{tag_name}");
+        loop {
+            let Some(token) = self.lexer.next().transpose().map_err(ParseError::from)? else {
+                return Err(ParseError::MissingEndTag {
+                    start: tag_name.into(),
+                    expected: end_tag,
+                    at: at.into(),
+                }
+                .into());
+            };
+            let content = token.content(self.template);
+            match token.token_type {
+                TokenType::Text => message.push_str(&content.replace('%', "%%")),
+                TokenType::Variable => {
+                    let variable = content.trim();
+                    message.push_str(&format!("%({variable})s"));
+                    variables.push(variable.to_string());
+                }
+                TokenType::Tag if content.trim() == end_tag => break,
+                TokenType::Tag | TokenType::Comment => {
+                    return Err(ParseError::BlockTranslateInnerTag {
+                        tag: tag_name,
+                        seen: content.trim().to_string(),
+                        at: token.at.into(),
+                        start_at: at.into(),
+                    }
+                    .into());
+                }
+            }
+        }
+        if trimmed.is_some() {
+            message = TRIM_WHITESPACE_RE
+                .replace_all(message.trim(), " ")
+                .into_owned();
+        }
+        Ok(TokenTree::Tag(Tag::BlockTranslate(BlockTranslate {
+            message,
+            variables,
+            asvar,
+        })))
+    }
+
     fn parse_autoescape(
         &mut self,
         at: (usize, usize),
@@ -1570,42 +2364,500 @@ impl<'t, 'l, 'py> Parser<'t, 'l, 'py> {
         }))
     }
 
+    fn parse_block_tag(
+        &mut self,
+        at: (usize, usize),
+        parts: TagParts,
+    ) -> Result<TokenTree, PyParseError> {
+        let content = self.template.content(parts.at);
+        let mut words = content.split_whitespace();
+        let (Some(name), None) = (words.next(), words.next()) else {
+            return Err(ParseError::BlockArguments { at: at.into() }.into());
+        };
+        if let Some(first_at) = self.block_names.insert(name.to_string(), at) {
+            return Err(ParseError::DuplicateBlock {
+                name: name.to_string(),
+                at: at.into(),
+                first_at: first_at.into(),
+            }
+            .into());
+        }
+        self.block_supers.borrow_mut().push(false);
+        let parsed = self.parse_until(vec![EndTagType::EndBlock], "block".into(), at);
+        let uses_super = self
+            .block_supers
+            .borrow_mut()
+            .pop()
+            .expect("pushed before parsing the block");
+        let (nodes, end_tag) = parsed?;
+        let end_name = self.template.content(end_tag.parts.at).trim();
+        if !end_name.is_empty() && end_name != name {
+            return Err(ParseError::WrongEndTag {
+                unexpected: format!("endblock {end_name}").into(),
+                expected: format!("endblock, endblock {name}"),
+                at: end_tag.at.into(),
+                start_at: at.into(),
+            }
+            .into());
+        }
+        let block = Arc::new(Block {
+            name: name.to_string(),
+            nodes,
+            uses_super,
+        });
+        self.blocks.insert(block.name.clone(), block.clone());
+        Ok(TokenTree::Tag(Tag::Block(block)))
+    }
+
+    fn parse_extends(
+        &mut self,
+        at: (usize, usize),
+        parts: TagParts,
+    ) -> Result<TokenTree, PyParseError> {
+        if self.seen_extends {
+            return Err(ParseError::RepeatedExtends { at: at.into() }.into());
+        }
+        if self.depth > 0 || self.seen_nontext {
+            return Err(ParseError::ExtendsNotFirst { at: at.into() }.into());
+        }
+        let tokens = SimpleTagLexer::new(self.template, parts)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(ParseError::from)?;
+        let [parent] = tokens.as_slice() else {
+            return Err(ParseError::ExtendsArguments { at: at.into() }.into());
+        };
+        let parent = parent.parse(self)?;
+        self.seen_extends = true;
+        // Like Django, the rest of the template belongs to the `extends` tag.
+        let mut nodes = Vec::new();
+        while let Some(token) = self.lexer.next().transpose().map_err(ParseError::from)? {
+            nodes.extend(self.parse_top_level(token)?);
+        }
+        Ok(TokenTree::Tag(Tag::Extends(Extends { parent, nodes })))
+    }
+
+    fn parse_spaceless(&mut self, at: (usize, usize)) -> Result<TokenTree, PyParseError> {
+        let (nodes, _) =
+            self.parse_until(vec![EndTagType::EndSpaceless], "spaceless".into(), at)?;
+        Ok(TokenTree::Tag(Tag::Spaceless { nodes }))
+    }
+
+    fn parse_verbatim(&mut self, at: (usize, usize)) -> Result<TokenTree, PyParseError> {
+        // The lexer emits everything up to the matching `endverbatim` as a
+        // single text token, so there is at most one node.
+        let (mut nodes, _) = self.parse_until(vec![EndTagType::Verbatim], "verbatim".into(), at)?;
+        Ok(nodes
+            .pop()
+            .unwrap_or_else(|| TokenTree::Text(Text::new((at.0 + at.1, 0)))))
+    }
+
+    fn parse_comment(&mut self, at: (usize, usize)) -> Result<TokenTree, PyParseError> {
+        // Like Django, everything up to `{% endcomment %}` is skipped without
+        // being parsed, so it can contain invalid syntax.
+        while let Some(token) = self.lexer.next().transpose().map_err(ParseError::from)? {
+            if matches!(token.token_type, TokenType::Tag)
+                && token.content(self.template).trim() == "endcomment"
+            {
+                return Ok(TokenTree::Text(Text::new((token.at.0 + token.at.1, 0))));
+            }
+        }
+        Err(ParseError::MissingEndTag {
+            start: "comment".into(),
+            expected: "endcomment".to_string(),
+            at: at.into(),
+        }
+        .into())
+    }
+
+    fn parse_cache(
+        &mut self,
+        at: (usize, usize),
+        parts: TagParts,
+    ) -> Result<TokenTree, PyParseError> {
+        let tokens = SimpleTagLexer::new(self.template, parts)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(ParseError::from)?;
+        let mut arguments = tokens.as_slice();
+        let mut cache_name = None;
+        if let [_, _, _, .., last] = arguments
+            && let Some(name_at) = last.kwarg
+            && self.template.content(name_at) == "using"
+        {
+            cache_name = Some(last.parse(self)?);
+            arguments = &arguments[..arguments.len() - 1];
+        }
+        let [expire_time, fragment_name, vary_on @ ..] = arguments else {
+            return Err(ParseError::CacheArguments { at: at.into() }.into());
+        };
+        if let Some(token) = arguments.iter().find(|token| token.kwarg.is_some()) {
+            return Err(ParseError::UnexpectedKeywordArgument {
+                at: token.full_at().into(),
+            }
+            .into());
+        }
+        let expire_time = expire_time.parse(self)?;
+        // Like Django, the fragment name is used as written, even if it
+        // looks like a variable or string literal.
+        let fragment_name = self.template.content(fragment_name.at).to_string();
+        let vary_on = vary_on
+            .iter()
+            .map(|token| token.parse(self))
+            .collect::<Result<_, _>>()?;
+        let end_tag = EndTagType::Custom("endcache".to_string());
+        let (nodes, _) = self.parse_until(vec![end_tag], "cache".into(), at)?;
+        Ok(TokenTree::Tag(Tag::Cache(Cache {
+            expire_time,
+            fragment_name,
+            vary_on,
+            cache_name,
+            nodes,
+        })))
+    }
+
+    fn parse_timezone(
+        &mut self,
+        at: (usize, usize),
+        parts: TagParts,
+    ) -> Result<TokenTree, PyParseError> {
+        let tokens = SimpleTagLexer::new(self.template, parts)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(ParseError::from)?;
+        let [timezone] = tokens.as_slice() else {
+            return Err(ParseError::TimezoneArguments { at: at.into() }.into());
+        };
+        if timezone.kwarg.is_some() {
+            return Err(ParseError::UnexpectedKeywordArgument {
+                at: timezone.full_at().into(),
+            }
+            .into());
+        }
+        let timezone = timezone.parse(self)?;
+        let end_tag = EndTagType::Custom("endtimezone".to_string());
+        let (nodes, _) = self.parse_until(vec![end_tag], "timezone".into(), at)?;
+        Ok(TokenTree::Tag(Tag::Timezone { timezone, nodes }))
+    }
+
+    fn parse_get_current_timezone(
+        &mut self,
+        at: (usize, usize),
+        parts: TagParts,
+    ) -> Result<TokenTree, PyParseError> {
+        // Like Django, split on whitespace since the variable name is taken
+        // as written.
+        let args: Vec<_> = self.template.content(parts.at).split_whitespace().collect();
+        match args.as_slice() {
+            ["as", variable] => Ok(TokenTree::Tag(Tag::GetCurrentTimezone {
+                variable: variable.to_string(),
+            })),
+            _ => {
+                let args = std::iter::once("get_current_timezone")
+                    .chain(args)
+                    .map(|arg| format!("'{arg}'"))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                Err(ParseError::GetCurrentTimezoneArguments {
+                    args,
+                    at: at.into(),
+                }
+                .into())
+            }
+        }
+    }
+
+    fn parse_width_ratio(
+        &mut self,
+        at: (usize, usize),
+        parts: TagParts,
+    ) -> Result<TokenTree, PyParseError> {
+        let tokens = SimpleTagLexer::new(self.template, parts)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(ParseError::from)?;
+        let (value, max_value, max_width, asvar) = match tokens.as_slice() {
+            [value, max_value, max_width] => (value, max_value, max_width, None),
+            [value, max_value, max_width, as_token, asvar] => {
+                if as_token.kwarg.is_some() || self.template.content(as_token.at) != "as" {
+                    return Err(ParseError::WidthRatioExpectedAs {
+                        at: as_token.full_at().into(),
+                    }
+                    .into());
+                }
+                let asvar = self.template.content(asvar.full_at()).to_string();
+                (value, max_value, max_width, Some(asvar))
+            }
+            _ => return Err(ParseError::WidthRatioArguments { at: at.into() }.into()),
+        };
+        Ok(TokenTree::Tag(Tag::WidthRatio(WidthRatio {
+            value: value.parse(self)?,
+            max_value: max_value.parse(self)?,
+            max_width: max_width.parse(self)?,
+            asvar,
+        })))
+    }
+
+    fn parse_now(
+        &mut self,
+        at: (usize, usize),
+        parts: TagParts,
+    ) -> Result<TokenTree, PyParseError> {
+        let tokens = SimpleTagLexer::new(self.template, parts)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(ParseError::from)?;
+        let (format, asvar) = match tokens.as_slice() {
+            [format] => (format, None),
+            [format, as_token, asvar]
+                if as_token.kwarg.is_none() && self.template.content(as_token.at) == "as" =>
+            {
+                (format, Some(self.template.content(asvar.full_at())))
+            }
+            _ => return Err(ParseError::NowArguments { at: at.into() }.into()),
+        };
+        if format.kwarg.is_some() || format.token_type != SimpleTagTokenType::Text {
+            return Err(ParseError::NowArguments { at: at.into() }.into());
+        }
+        Ok(TokenTree::Tag(Tag::Now(Now {
+            format: Text::new(format.content_at()),
+            asvar: asvar.map(str::to_string),
+        })))
+    }
+
+    fn parse_include(
+        &mut self,
+        at: (usize, usize),
+        parts: TagParts,
+    ) -> Result<TokenTree, PyParseError> {
+        let tokens = SimpleTagLexer::new(self.template, parts)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(ParseError::from)?;
+        let Some((template_name, mut remaining)) = tokens.split_first() else {
+            return Err(ParseError::IncludeMissingTemplate { at: at.into() }.into());
+        };
+        let template_name = template_name.parse(self)?;
+        let mut variables = Vec::new();
+        let mut only = false;
+        let mut seen: HashMap<&str, (usize, usize)> = HashMap::new();
+        while let Some((token, rest)) = remaining.split_first() {
+            remaining = rest;
+            let option = match token.kwarg {
+                Some(_) => "",
+                None => self.template.content(token.at),
+            };
+            if matches!(option, "with" | "only")
+                && let Some(first_at) = seen.insert(option, token.at)
+            {
+                return Err(ParseError::RepeatedOption {
+                    option: option.to_string(),
+                    at: token.at.into(),
+                    first_at: first_at.into(),
+                }
+                .into());
+            }
+            match option {
+                "with" => {
+                    while let Some((kwarg, rest)) = remaining.split_first() {
+                        let Some(name_at) = kwarg.kwarg else {
+                            break;
+                        };
+                        variables.push((
+                            self.template.content(name_at).to_string(),
+                            kwarg.parse(self)?,
+                        ));
+                        remaining = rest;
+                    }
+                    if variables.is_empty() {
+                        return Err(ParseError::IncludeWithMissingKeyword {
+                            at: token.at.into(),
+                        }
+                        .into());
+                    }
+                }
+                "only" => only = true,
+                _ => {
+                    let token_at = token.full_at();
+                    return Err(ParseError::UnknownArgument {
+                        tag: "include",
+                        argument: self.template.content(token_at).to_string(),
+                        at: token_at.into(),
+                    }
+                    .into());
+                }
+            }
+        }
+        Ok(TokenTree::Tag(Tag::Include(Include {
+            template_name,
+            variables,
+            only,
+        })))
+    }
+
+    fn parse_with(
+        &mut self,
+        at: (usize, usize),
+        parts: TagParts,
+    ) -> Result<TokenTree, PyParseError> {
+        let tokens = SimpleTagLexer::new(self.template, parts)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(ParseError::from)?;
+        let mut variables: Vec<(String, TagElement)> = Vec::new();
+        let mut add_variable = |name: &str, value: TagElement| {
+            // Like Django, a repeated name keeps its first position but takes
+            // the last value.
+            match variables.iter_mut().find(|(n, _)| n == name) {
+                Some((_, existing)) => *existing = value,
+                None => variables.push((name.to_string(), value)),
+            }
+        };
+        let mut remaining = tokens.as_slice();
+        if tokens.first().is_some_and(|token| token.kwarg.is_some()) {
+            while let Some((token, rest)) = remaining.split_first() {
+                let Some(name_at) = token.kwarg else {
+                    break;
+                };
+                add_variable(self.template.content(name_at), token.parse(self)?);
+                remaining = rest;
+            }
+        } else {
+            // The legacy `{% with value as name and other as name2 %}` format.
+            while let [value, as_token, name, rest @ ..] = remaining {
+                if value.kwarg.is_some()
+                    || as_token.kwarg.is_some()
+                    || self.template.content(as_token.at) != "as"
+                {
+                    break;
+                }
+                add_variable(self.template.content(name.at), value.parse(self)?);
+                remaining = rest;
+                match remaining.split_first() {
+                    Some((and, rest)) if self.template.content(and.at) == "and" => remaining = rest,
+                    _ => break,
+                }
+            }
+        }
+        if variables.is_empty() {
+            return Err(ParseError::WithMissingAssignment { at: at.into() }.into());
+        }
+        if let Some(token) = remaining.first() {
+            let token_at = token.full_at();
+            return Err(ParseError::WithInvalidToken {
+                token: self.template.content(token_at).to_string(),
+                at: token_at.into(),
+            }
+            .into());
+        }
+        let (nodes, _) = self.parse_until(vec![EndTagType::EndWith], "with".into(), at)?;
+        Ok(TokenTree::Tag(Tag::With { variables, nodes }))
+    }
+
     fn parse_if(
         &mut self,
         at: (usize, usize),
         parts: TagParts,
         start: &'static str,
     ) -> Result<TokenTree, PyParseError> {
-        let condition = parse_if_condition(self, parts, at)?;
-        let (nodes, end_tag) = self.parse_until(
-            vec![EndTagType::Elif, EndTagType::Else, EndTagType::EndIf],
-            start.into(),
-            at,
-        )?;
-        let falsey = match end_tag {
-            EndTag {
-                at,
-                end: EndTagType::Elif,
-                parts,
-            } => Some(vec![self.parse_if(at, parts, "elif")?]),
-            EndTag {
+        // An `elif` chain is parsed in a loop, so it doesn't count towards
+        // the nesting depth. Each `elif` becomes an `if` nested in the
+        // `else` of the previous one.
+        let mut branches = Vec::new();
+        let (mut at, mut parts, mut start) = (at, parts, start);
+        let falsey = loop {
+            let condition = parse_if_condition(self, parts, at)?;
+            for warning in std::mem::take(&mut self.warnings) {
+                self.warn(warning)?;
+            }
+            let (nodes, end_tag) = self.parse_until(
+                vec![EndTagType::Elif, EndTagType::Else, EndTagType::EndIf],
+                start.into(),
                 at,
-                end: EndTagType::Else,
-                parts: _parts,
-            } => {
-                let (nodes, _) = self.parse_until(vec![EndTagType::EndIf], "else".into(), at)?;
+            )?;
+            branches.push((condition, nodes));
+            match end_tag {
+                EndTag {
+                    at: elif_at,
+                    end: EndTagType::Elif,
+                    parts: elif_parts,
+                } => {
+                    (at, parts, start) = (elif_at, elif_parts, "elif");
+                }
+                EndTag {
+                    at,
+                    end: EndTagType::Else,
+                    parts: _parts,
+                } => {
+                    let (nodes, _) =
+                        self.parse_until(vec![EndTagType::EndIf], "else".into(), at)?;
+                    break Some(nodes);
+                }
+                EndTag {
+                    at: _end_at,
+                    end: EndTagType::EndIf,
+                    parts: _parts,
+                } => break None,
+                _ => unreachable!(),
+            }
+        };
+        let (condition, truthy) = branches.pop().expect("an if tag has at least one branch");
+        let mut tag = Tag::If {
+            condition,
+            truthy,
+            falsey,
+        };
+        while let Some((condition, truthy)) = branches.pop() {
+            tag = Tag::If {
+                condition,
+                truthy,
+                falsey: Some(vec![TokenTree::Tag(tag)]),
+            };
+        }
+        Ok(TokenTree::Tag(tag))
+    }
+
+    /// Parse the legacy `{% ifequal a b %}` and `{% ifnotequal a b %}` tags
+    /// into the equivalent `if` tag.
+    fn parse_if_equal(
+        &mut self,
+        at: (usize, usize),
+        parts: TagParts,
+        start: &'static str,
+    ) -> Result<TokenTree, PyParseError> {
+        if !self.legacy_tags {
+            return Err(ParseError::LegacyTagDisabled {
+                tag: start,
+                at: at.into(),
+            }
+            .into());
+        }
+        let tokens = SimpleTagLexer::new(self.template, parts)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(ParseError::from)?;
+        let (lhs, rhs) = match tokens.as_slice() {
+            [lhs, rhs] if lhs.kwarg.is_none() && rhs.kwarg.is_none() => (lhs, rhs),
+            _ => {
+                return Err(ParseError::IfEqualArguments {
+                    tag: start,
+                    at: at.into(),
+                }
+                .into());
+            }
+        };
+        let operands = Box::new((
+            IfCondition::Variable(lhs.parse(self)?),
+            IfCondition::Variable(rhs.parse(self)?),
+        ));
+        let (condition, end) = match start {
+            "ifequal" => (IfCondition::Equal(operands), EndTagType::EndIfEqual),
+            _ => (IfCondition::NotEqual(operands), EndTagType::EndIfNotEqual),
+        };
+        let (truthy, end_tag) =
+            self.parse_until(vec![EndTagType::Else, end.clone()], start.into(), at)?;
+        let falsey = match end_tag.end {
+            EndTagType::Else => {
+                let (nodes, _) = self.parse_until(vec![end], "else".into(), end_tag.at)?;
                 Some(nodes)
             }
-            EndTag {
-                at: _end_at,
-                end: EndTagType::EndIf,
-                parts: _parts,
-            } => None,
-            _ => unreachable!(),
+            _ => None,
         };
         Ok(TokenTree::Tag(Tag::If {
             condition,
-            truthy: nodes,
+            truthy,
             falsey,
         }))
     }
@@ -1615,14 +2867,25 @@ impl<'t, 'l, 'py> Parser<'t, 'l, 'py> {
         at: (usize, usize),
         parts: TagParts,
     ) -> Result<TokenTree, PyParseError> {
-        self.forloop_depth += 1;
-        let (iterable, variables, reversed) = parse_for_loop(self, parts, at)?;
-        let (nodes, end_tag) = self.parse_until(
-            vec![EndTagType::Empty, EndTagType::EndFor],
-            "for".into(),
-            at,
-        )?;
-        self.forloop_depth -= 1;
+        self.forloops.get_mut().push(false);
+        let parsed = parse_for_loop(self, parts, at)
+            .map_err(PyParseError::from)
+            .and_then(|for_loop| {
+                let body = self.parse_until(
+                    vec![EndTagType::Empty, EndTagType::EndFor],
+                    "for".into(),
+                    at,
+                )?;
+                Ok((for_loop, body))
+            });
+        // Pop before returning any error, so a parser recovering from it
+        // doesn't treat later variables as inside this loop.
+        let uses_length = self
+            .forloops
+            .get_mut()
+            .pop()
+            .expect("Pushed before parsing the loop body");
+        let ((iterable, variables, reversed), (nodes, end_tag)) = parsed?;
         let empty = match end_tag {
             EndTag {
                 at,
@@ -1645,6 +2908,7 @@ impl<'t, 'l, 'py> Parser<'t, 'l, 'py> {
             reversed,
             body: nodes,
             empty,
+            uses_length,
         })))
     }
 }
@@ -1652,7 +2916,7 @@ impl<'t, 'l, 'py> Parser<'t, 'l, 'py> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use pyo3::types::{PyDict, PyDictMethods};
+    use pyo3::types::{IntoPyDict, PyDict, PyDictMethods};
 
     use crate::lex::common::LexerError;
     use crate::{
@@ -1684,44 +2948,115 @@ mod tests {
     }
 
     #[test]
-    fn test_empty_template() {
+    fn test_empty_template() {
+        Python::initialize();
+
+        Python::attach(|py| {
+            let libraries = HashMap::new();
+            let template = "";
+            let mut parser = Parser::new(py, template.into(), &libraries);
+            let nodes = parser.parse().unwrap();
+            assert_eq!(nodes, vec![]);
+        })
+    }
+
+    #[test]
+    fn test_text() {
+        Python::initialize();
+
+        Python::attach(|py| {
+            let libraries = HashMap::new();
+            let template = "Some text";
+            let template_string = TemplateString(template);
+            let mut parser = Parser::new(py, template_string, &libraries);
+            let nodes = parser.parse().unwrap();
+            let text = Text::new((0, template.len()));
+            assert_eq!(nodes, vec![TokenTree::Text(text)]);
+            assert_eq!(template_string.content(text.at), template);
+        })
+    }
+
+    #[test]
+    fn test_comment() {
+        Python::initialize();
+
+        Python::attach(|py| {
+            let libraries = HashMap::new();
+            let template = "{# A commment #}";
+            let mut parser = Parser::new(py, template.into(), &libraries);
+            let nodes = parser.parse().unwrap();
+            assert_eq!(nodes, vec![]);
+        })
+    }
+
+    #[test]
+    fn test_comment_block_skips_invalid_syntax() {
         Python::initialize();
 
         Python::attach(|py| {
             let libraries = HashMap::new();
-            let template = "";
+            let template = "a{% comment \"note\" %}{{ }}{% bogus %}{% if %}{% endcomment %}b";
             let mut parser = Parser::new(py, template.into(), &libraries);
             let nodes = parser.parse().unwrap();
-            assert_eq!(nodes, vec![]);
+            assert_eq!(
+                nodes,
+                vec![
+                    TokenTree::Text(Text::new((0, 1))),
+                    TokenTree::Text(Text::new((61, 0))),
+                    TokenTree::Text(Text::new((61, 1))),
+                ]
+            );
         })
     }
 
     #[test]
-    fn test_text() {
+    fn test_comment_block_unclosed() {
         Python::initialize();
 
         Python::attach(|py| {
             let libraries = HashMap::new();
-            let template = "Some text";
-            let template_string = TemplateString(template);
-            let mut parser = Parser::new(py, template_string, &libraries);
-            let nodes = parser.parse().unwrap();
-            let text = Text::new((0, template.len()));
-            assert_eq!(nodes, vec![TokenTree::Text(text)]);
-            assert_eq!(template_string.content(text.at), template);
+            let template = "{% comment %}{{ }}";
+            let mut parser = Parser::new(py, template.into(), &libraries);
+            let error = parser.parse().unwrap_err().unwrap_parse_error();
+            assert_eq!(
+                error,
+                ParseError::MissingEndTag {
+                    start: "comment".into(),
+                    expected: "endcomment".to_string(),
+                    at: (0, 13).into(),
+                }
+            );
         })
     }
 
     #[test]
-    fn test_comment() {
+    fn test_comment_block_before_extends() {
         Python::initialize();
 
         Python::attach(|py| {
             let libraries = HashMap::new();
-            let template = "{# A commment #}";
+            let template = "{% comment %}{% endcomment %}{% extends base %}";
+            let mut parser = Parser::new(py, template.into(), &libraries);
+            let error = parser.parse().unwrap_err().unwrap_parse_error();
+            assert_eq!(
+                error,
+                ParseError::ExtendsNotFirst {
+                    at: (29, 18).into()
+                }
+            );
+        })
+    }
+
+    #[test]
+    fn test_verbatim_skips_invalid_syntax() {
+        Python::initialize();
+
+        Python::attach(|py| {
+            let libraries = HashMap::new();
+            let template = "{% verbatim %}{{ }}{% bogus %}{% endverbatim %}";
             let mut parser = Parser::new(py, template.into(), &libraries);
             let nodes = parser.parse().unwrap();
-            assert_eq!(nodes, vec![]);
+            assert_eq!(nodes, vec![TokenTree::Text(Text::new((14, 16)))]);
         })
     }
 
@@ -1825,6 +3160,202 @@ mod tests {
         })
     }
 
+    #[test]
+    fn test_filter_not_loaded() {
+        Python::initialize();
+
+        Python::attach(|py| {
+            let filters = PyDict::new(py);
+            filters.set_item("bar", py.None()).unwrap();
+            let kwargs = PyDict::new(py);
+            kwargs.set_item("filters", filters).unwrap();
+            let library = py
+                .import("types")
+                .unwrap()
+                .getattr("SimpleNamespace")
+                .unwrap()
+                .call((), Some(&kwargs))
+                .unwrap();
+            let libraries = HashMap::from([("foo_filters".to_string(), library.unbind())]);
+            let template = TemplateString("{{ foo|bar }}{% load foo_filters %}");
+            let mut parser = Parser::new(py, template, &libraries);
+            let error = parser.parse().unwrap_err().unwrap_parse_error();
+            assert_eq!(
+                error,
+                ParseError::FilterNotLoaded {
+                    filter: "bar".to_string(),
+                    at: (7, 3).into(),
+                    help: "Load it with {% load foo_filters %}".to_string(),
+                }
+            );
+        })
+    }
+
+    fn i18n_libraries(py: Python<'_>) -> HashMap<String, Py<PyAny>> {
+        let globals = PyDict::new(py);
+        py.run(
+            c"import types\n\
+def do_block_translate(parser, token): pass\n\
+do_block_translate.__module__ = 'django.templatetags.i18n'\n\
+tags = {'blocktranslate': do_block_translate, 'blocktrans': do_block_translate}\n\
+library = types.SimpleNamespace(tags=tags, filters={})",
+            Some(&globals),
+            None,
+        )
+        .unwrap();
+        let library = globals.get_item("library").unwrap().unwrap();
+        HashMap::from([("i18n".to_string(), library.unbind())])
+    }
+
+    #[test]
+    fn test_parse_blocktranslate_asvar() {
+        Python::initialize();
+
+        Python::attach(|py| {
+            let libraries = i18n_libraries(py);
+            let template = TemplateString(
+                "{% load i18n %}{% blocktranslate trimmed asvar greeting %}\n  Hello {{ name }}, 100%\n{% endblocktranslate %}",
+            );
+            let mut parser = Parser::new(py, template, &libraries);
+            let nodes = parser.parse().unwrap();
+            assert_eq!(
+                nodes[1],
+                TokenTree::Tag(Tag::BlockTranslate(BlockTranslate {
+                    message: "Hello %(name)s, 100%%".to_string(),
+                    variables: vec!["name".to_string()],
+                    asvar: Some("greeting".to_string()),
+                }))
+            );
+        })
+    }
+
+    #[test]
+    fn test_parse_blocktrans_missing_asvar() {
+        Python::initialize();
+
+        Python::attach(|py| {
+            let libraries = i18n_libraries(py);
+            let template =
+                TemplateString("{% load i18n %}{% blocktrans asvar %}Hello{% endblocktrans %}");
+            let mut parser = Parser::new(py, template, &libraries);
+            let error = parser.parse().unwrap_err().unwrap_parse_error();
+            assert_eq!(
+                error,
+                ParseError::BlockTranslateMissingAsvar {
+                    tag: "blocktrans",
+                    at: (29, 5).into(),
+                }
+            );
+        })
+    }
+
+    #[test]
+    fn test_parse_blocktranslate_inner_tag() {
+        Python::initialize();
+
+        Python::attach(|py| {
+            let libraries = i18n_libraries(py);
+            let template = TemplateString(
+                "{% load i18n %}{% blocktranslate %}{% if a %}{% endif %}{% endblocktranslate %}",
+            );
+            let mut parser = Parser::new(py, template, &libraries);
+            let error = parser.parse().unwrap_err().unwrap_parse_error();
+            assert_eq!(
+                error,
+                ParseError::BlockTranslateInnerTag {
+                    tag: "blocktranslate",
+                    seen: "if a".to_string(),
+                    at: (35, 10).into(),
+                    start_at: (15, 20).into(),
+                }
+            );
+        })
+    }
+
+    fn parse_with_warnings(py: Python<'_>, template: &str) -> Vec<String> {
+        let warnings = py.import("warnings").unwrap();
+        let catcher = warnings
+            .call_method(
+                "catch_warnings",
+                (),
+                Some(&[("record", true)].into_py_dict(py).unwrap()),
+            )
+            .unwrap();
+        let caught = catcher.call_method0("__enter__").unwrap();
+        warnings.call_method1("simplefilter", ("always",)).unwrap();
+
+        let libraries = HashMap::new();
+        let template = TemplateString(template);
+        let mut parser = Parser::new(py, template, &libraries);
+        let result = temp_env::with_var("NO_COLOR", Some("1"), || parser.parse());
+
+        catcher
+            .call_method1("__exit__", (py.None(), py.None(), py.None()))
+            .unwrap();
+        result.unwrap();
+        caught
+            .try_iter()
+            .unwrap()
+            .map(|warning| {
+                let warning = warning.unwrap();
+                let category = warning.getattr("category").unwrap();
+                assert!(category.is(py.get_type::<PySyntaxWarning>()));
+                warning
+                    .getattr("message")
+                    .unwrap()
+                    .str()
+                    .unwrap()
+                    .to_string()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_if_is_none_no_warning() {
+        Python::initialize();
+
+        Python::attach(|py| {
+            let warnings = parse_with_warnings(py, "{% if x is None %}{% endif %}");
+            assert!(warnings.is_empty());
+        })
+    }
+
+    #[test]
+    fn test_if_is_literal_warning() {
+        Python::initialize();
+
+        Python::attach(|py| {
+            let warnings = parse_with_warnings(py, "{% if x is 5 %}{% endif %}");
+            let expected = "  ⚠ \"is\" with a literal is unreliable
+   ╭────
+ 1 │ {% if x is 5 %}{% endif %}
+   ·            ┬
+   ·            ╰── literal
+   ╰────
+  help: Did you mean \"==\"?
+";
+            assert_eq!(warnings, [expected]);
+        })
+    }
+
+    #[test]
+    fn test_if_is_not_literal_warning() {
+        Python::initialize();
+
+        Python::attach(|py| {
+            let warnings = parse_with_warnings(py, "{% if x is not 'foo' %}{% endif %}");
+            let expected = "  ⚠ \"is not\" with a literal is unreliable
+   ╭────
+ 1 │ {% if x is not 'foo' %}{% endif %}
+   ·                ──┬──
+   ·                  ╰── literal
+   ╰────
+  help: Did you mean \"!=\"?
+";
+            assert_eq!(warnings, [expected]);
+        })
+    }
+
     #[test]
     fn test_filter_multiple() {
         Python::initialize();
@@ -2562,4 +4093,408 @@ mod tests {
             );
         })
     }
+
+    #[test]
+    fn test_ifequal() {
+        Python::initialize();
+
+        Python::attach(|py| {
+            let libraries = HashMap::new();
+            let template = "{% ifequal a b %}yes{% else %}no{% endifequal %}";
+            let mut parser = Parser::new(py, template.into(), &libraries).with_legacy_tags(true);
+            let nodes = parser.parse().unwrap();
+            let condition = IfCondition::Equal(Box::new((
+                IfCondition::Variable(TagElement::Variable(Variable { at: (11, 1) })),
+                IfCondition::Variable(TagElement::Variable(Variable { at: (13, 1) })),
+            )));
+            let expected = TokenTree::Tag(Tag::If {
+                condition,
+                truthy: vec![TokenTree::Text(Text::new((17, 3)))],
+                falsey: Some(vec![TokenTree::Text(Text::new((30, 2)))]),
+            });
+            assert_eq!(nodes, vec![expected]);
+        })
+    }
+
+    #[test]
+    fn test_parse_to_ast() {
+        Python::initialize();
+
+        Python::attach(|py| {
+            let template = "Hi {{ name }}{% if x %}y{% endif %}{% for %}!";
+            let libraries = HashMap::new();
+            let mut parser = Parser::new(py, TemplateString(template), &libraries);
+            let ast = parser.parse_to_ast();
+
+            let item = |kind, at, detail: &str| AstItem {
+                kind,
+                at,
+                detail: detail.to_string(),
+            };
+            assert_eq!(
+                ast.items,
+                vec![
+                    item(AstKind::Text, (0, 3), ""),
+                    item(AstKind::Variable, (3, 10), "name"),
+                    item(AstKind::Tag, (13, 22), "if"),
+                    item(AstKind::Tag, (35, 9), "for"),
+                    item(
+                        AstKind::Error,
+                        (35, 9),
+                        "Expected at least one variable name in for loop:",
+                    ),
+                    item(AstKind::Text, (44, 1), ""),
+                ]
+            );
+            assert_eq!(ast.diagnostics().count(), 1);
+            assert_eq!(ast.nodes.len(), 4);
+        })
+    }
+
+    #[test]
+    fn test_parse_to_ast_for_error_leaves_loop() {
+        Python::initialize();
+
+        Python::attach(|py| {
+            let libraries = HashMap::new();
+            for template in [
+                "{% for x %}{{ forloop }}",
+                "{% for x in y %}{% if %}{{ forloop }}",
+            ] {
+                let mut parser = Parser::new(py, TemplateString(template), &libraries);
+                let ast = parser.parse_to_ast();
+
+                assert!(parser.forloops.borrow().is_empty(), "{template}");
+                assert_eq!(ast.diagnostics().count(), 1, "{template}");
+            }
+        })
+    }
+
+    #[test]
+    fn test_if_not_binding_power() {
+        Python::initialize();
+
+        Python::attach(|py| {
+            let libraries = HashMap::new();
+            let variable = |at| IfCondition::Variable(TagElement::Variable(Variable { at }));
+            let cases = [
+                (
+                    "{% if not a == b %}{% endif %}",
+                    IfCondition::Not(Box::new(IfCondition::Equal(Box::new((
+                        variable((10, 1)),
+                        variable((15, 1)),
+                    ))))),
+                ),
+                (
+                    "{% if not a in b %}{% endif %}",
+                    IfCondition::Not(Box::new(IfCondition::In(Box::new((
+                        variable((10, 1)),
+                        variable((15, 1)),
+                    ))))),
+                ),
+                (
+                    "{% if a and not b %}{% endif %}",
+                    IfCondition::And(Box::new((
+                        variable((6, 1)),
+                        IfCondition::Not(Box::new(variable((16, 1)))),
+                    ))),
+                ),
+                (
+                    "{% if not a or b %}{% endif %}",
+                    IfCondition::Or(Box::new((
+                        IfCondition::Not(Box::new(variable((10, 1)))),
+                        variable((15, 1)),
+                    ))),
+                ),
+            ];
+            for (template, condition) in cases {
+                let mut parser = Parser::new(py, template.into(), &libraries);
+                let nodes = parser.parse().unwrap();
+                let expected = TokenTree::Tag(Tag::If {
+                    condition,
+                    truthy: vec![],
+                    falsey: None,
+                });
+                assert_eq!(nodes, vec![expected], "{template}");
+            }
+        })
+    }
+
+    #[test]
+    fn test_if_in_comparison_binding_power() {
+        Python::initialize();
+
+        Python::attach(|py| {
+            let libraries = HashMap::new();
+            let variable = |at| IfCondition::Variable(TagElement::Variable(Variable { at }));
+            let cases = [
+                (
+                    "{% if a == b in c %}{% endif %}",
+                    IfCondition::In(Box::new((
+                        IfCondition::Equal(Box::new((variable((6, 1)), variable((11, 1))))),
+                        variable((16, 1)),
+                    ))),
+                ),
+                (
+                    "{% if a in b == c %}{% endif %}",
+                    IfCondition::In(Box::new((
+                        variable((6, 1)),
+                        IfCondition::Equal(Box::new((variable((11, 1)), variable((16, 1))))),
+                    ))),
+                ),
+                (
+                    "{% if a not in b != c %}{% endif %}",
+                    IfCondition::NotIn(Box::new((
+                        variable((6, 1)),
+                        IfCondition::NotEqual(Box::new((variable((15, 1)), variable((20, 1))))),
+                    ))),
+                ),
+                (
+                    "{% if a in b and c %}{% endif %}",
+                    IfCondition::And(Box::new((
+                        IfCondition::In(Box::new((variable((6, 1)), variable((11, 1))))),
+                        variable((17, 1)),
+                    ))),
+                ),
+                (
+                    "{% if a or b in c %}{% endif %}",
+                    IfCondition::Or(Box::new((
+                        variable((6, 1)),
+                        IfCondition::In(Box::new((variable((11, 1)), variable((16, 1))))),
+                    ))),
+                ),
+                (
+                    "{% if a in b in c %}{% endif %}",
+                    IfCondition::In(Box::new((
+                        IfCondition::In(Box::new((variable((6, 1)), variable((11, 1))))),
+                        variable((16, 1)),
+                    ))),
+                ),
+            ];
+            for (template, condition) in cases {
+                let mut parser = Parser::new(py, template.into(), &libraries);
+                let nodes = parser.parse().unwrap();
+                let expected = TokenTree::Tag(Tag::If {
+                    condition,
+                    truthy: vec![],
+                    falsey: None,
+                });
+                assert_eq!(nodes, vec![expected], "{template}");
+            }
+        })
+    }
+
+    #[test]
+    fn test_if_numeric_literal() {
+        Python::initialize();
+
+        Python::attach(|py| {
+            let libraries = HashMap::new();
+            let cases = [
+                ("{% if 1 %}{% endif %}", TagElement::Int(1.into())),
+                ("{% if 0 %}{% endif %}", TagElement::Int(0.into())),
+                ("{% if -1 %}{% endif %}", TagElement::Int((-1).into())),
+                ("{% if 0.0 %}{% endif %}", TagElement::Float(0.0)),
+            ];
+            for (template, element) in cases {
+                let mut parser = Parser::new(py, template.into(), &libraries);
+                let nodes = parser.parse().unwrap();
+                let expected = TokenTree::Tag(Tag::If {
+                    condition: IfCondition::Variable(element),
+                    truthy: vec![],
+                    falsey: None,
+                });
+                assert_eq!(nodes, vec![expected], "{template}");
+            }
+        })
+    }
+
+    #[test]
+    fn test_if_operator_in_operand_position() {
+        Python::initialize();
+
+        Python::attach(|py| {
+            let libraries = HashMap::new();
+            let operators = [
+                "and", "or", "in", "not in", "is", "is not", "==", "!=", "<", ">", "<=", ">=",
+            ];
+            for operator in operators {
+                for prefix in ["", "a == ", "not "] {
+                    // `not in` is lexed as one operator, like Django does.
+                    if prefix == "not " && operator == "in" {
+                        continue;
+                    }
+                    let template = format!("{{% if {prefix}{operator} b %}}{{% endif %}}");
+                    let mut parser = Parser::new(py, template.as_str().into(), &libraries);
+                    let error = parser.parse().unwrap_err().unwrap_parse_error();
+                    let start = 6 + prefix.len();
+                    assert_eq!(
+                        error,
+                        ParseError::InvalidIfPosition {
+                            token: operator.to_string(),
+                            at: (start, operator.len()).into(),
+                        },
+                        "{template}"
+                    );
+                }
+            }
+        })
+    }
+
+    #[test]
+    fn test_ifnotequal_wrong_arguments() {
+        Python::initialize();
+
+        Python::attach(|py| {
+            let libraries = HashMap::new();
+            let template = "{% ifnotequal a %}{% endifnotequal %}";
+            let mut parser = Parser::new(py, template.into(), &libraries).with_legacy_tags(true);
+            let error = parser.parse().unwrap_err().unwrap_parse_error();
+            assert_eq!(
+                error,
+                ParseError::IfEqualArguments {
+                    tag: "ifnotequal",
+                    at: (0, 18).into(),
+                }
+            );
+        })
+    }
+
+    #[test]
+    fn test_ifequal_legacy_tags_disabled() {
+        Python::initialize();
+
+        Python::attach(|py| {
+            let libraries = HashMap::new();
+            let template = "{% ifequal a b %}{% endifequal %}";
+            let mut parser = Parser::new(py, template.into(), &libraries);
+            let error = parser.parse().unwrap_err().unwrap_parse_error();
+            assert_eq!(
+                error,
+                ParseError::LegacyTagDisabled {
+                    tag: "ifequal",
+                    at: (0, 17).into(),
+                }
+            );
+        })
+    }
+
+    /// The stack the max depth tests parse on, fixed so they don't depend on
+    /// `RUST_MIN_STACK`. Parsing block tags nested `DEFAULT_MAX_DEPTH` levels
+    /// deep must fit in it, so a change which makes each level use much more
+    /// stack fails these tests. An unoptimised build currently needs about
+    /// 1.4 MiB, around 11 KiB per level, and a release build far less.
+    const MAX_DEPTH_STACK_SIZE: usize = 2 * 1024 * 1024;
+
+    /// Run `test` on a thread with a `MAX_DEPTH_STACK_SIZE` stack.
+    fn with_max_depth_stack(test: impl FnOnce() + Send + 'static) {
+        let thread = std::thread::Builder::new()
+            .stack_size(MAX_DEPTH_STACK_SIZE)
+            .spawn(test)
+            .expect("Spawning a test thread should succeed");
+        if let Err(panic) = thread.join() {
+            std::panic::resume_unwind(panic);
+        }
+    }
+
+    fn nested_ifs(depth: usize) -> String {
+        format!(
+            "{}x{}",
+            "{% if a %}".repeat(depth),
+            "{% endif %}".repeat(depth)
+        )
+    }
+
+    #[test]
+    fn test_max_depth() {
+        Python::initialize();
+
+        with_max_depth_stack(|| {
+            Python::attach(|py| {
+                let libraries = HashMap::new();
+                let template = nested_ifs(DEFAULT_MAX_DEPTH);
+                let mut parser = Parser::new(py, template.as_str().into(), &libraries);
+                assert!(parser.parse().is_ok());
+
+                let template = nested_ifs(DEFAULT_MAX_DEPTH + 1);
+                let mut parser = Parser::new(py, template.as_str().into(), &libraries);
+                let error = parser.parse().unwrap_err().unwrap_parse_error();
+                assert_eq!(
+                    error,
+                    ParseError::TooDeeplyNested {
+                        max_depth: DEFAULT_MAX_DEPTH,
+                        at: (DEFAULT_MAX_DEPTH * 10, 10).into(),
+                    }
+                );
+            })
+        })
+    }
+
+    #[test]
+    fn test_max_depth_far_exceeded() {
+        Python::initialize();
+
+        with_max_depth_stack(|| {
+            Python::attach(|py| {
+                let libraries = HashMap::new();
+                let template = nested_ifs(100_000);
+                let mut parser = Parser::new(py, template.as_str().into(), &libraries);
+                let error = parser.parse().unwrap_err().unwrap_parse_error();
+                assert!(matches!(error, ParseError::TooDeeplyNested { .. }));
+            })
+        })
+    }
+
+    #[test]
+    fn test_max_depth_configured() {
+        Python::initialize();
+
+        Python::attach(|py| {
+            let libraries = HashMap::new();
+            let template =
+                "{% for a in b %}{% with c=a %}{% if c %}{% endif %}{% endwith %}{% endfor %}";
+            let mut parser = Parser::new(py, template.into(), &libraries).with_max_depth(3);
+            assert!(parser.parse().is_ok());
+
+            let mut parser = Parser::new(py, template.into(), &libraries).with_max_depth(2);
+            let error = parser.parse().unwrap_err().unwrap_parse_error();
+            assert_eq!(
+                error,
+                ParseError::TooDeeplyNested {
+                    max_depth: 2,
+                    at: (30, 10).into(),
+                }
+            );
+        })
+    }
+
+    #[test]
+    fn test_max_depth_elif_chain() {
+        Python::initialize();
+
+        with_max_depth_stack(|| {
+            Python::attach(|py| {
+                let libraries = HashMap::new();
+                // A flat `elif` chain isn't nested, however long it is.
+                let template = format!(
+                    "{{% if a %}}{}{{% else %}}{{% endif %}}",
+                    "{% elif b %}".repeat(DEFAULT_MAX_DEPTH * 4)
+                );
+                let mut parser =
+                    Parser::new(py, template.as_str().into(), &libraries).with_max_depth(1);
+                assert!(parser.parse().is_ok());
+
+                let template = "{% if a %}{% elif b %}{% if c %}{% endif %}{% endif %}";
+                let mut parser = Parser::new(py, template.into(), &libraries).with_max_depth(1);
+                let error = parser.parse().unwrap_err().unwrap_parse_error();
+                assert_eq!(
+                    error,
+                    ParseError::TooDeeplyNested {
+                        max_depth: 1,
+                        at: (22, 10).into(),
+                    }
+                );
+            })
+        })
+    }
 }
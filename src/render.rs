@@ -1,7 +1,10 @@
 pub mod common;
 pub mod filters;
+pub mod numberformat;
 pub mod tags;
+pub mod timezone;
 pub mod types;
+pub mod urlize;
 
 use std::borrow::Cow;
 
@@ -43,6 +46,18 @@ pub trait Render {
     ) -> RenderResult<'t>;
 }
 
+/// Somewhere to write a template's output as each top-level node is rendered.
+pub trait RenderSink {
+    fn write(&mut self, content: &str) -> PyResult<()>;
+}
+
+impl RenderSink for String {
+    fn write(&mut self, content: &str) -> PyResult<()> {
+        self.push_str(content);
+        Ok(())
+    }
+}
+
 /// Trait for evaluating an expression in a boolean context
 pub trait Evaluate {
     fn evaluate(
@@ -98,12 +113,19 @@ where
         template: TemplateString<'t>,
         context: &mut Context,
     ) -> RenderResult<'t> {
-        Ok(Cow::Owned(
-            self.iter()
-                .map(|node| node.render(py, template, context))
-                .collect::<Result<Vec<_>, _>>()?
-                .join(""),
-        ))
+        // Empty and single node branches (like `{% if x %}{% endif %}` or an
+        // all-text block) are common, so avoid allocating to join them.
+        match self.as_slice() {
+            [] => Ok(Cow::Borrowed("")),
+            [node] => node.render(py, template, context),
+            nodes => Ok(Cow::Owned(
+                nodes
+                    .iter()
+                    .map(|node| node.render(py, template, context))
+                    .collect::<Result<Vec<_>, _>>()?
+                    .join(""),
+            )),
+        }
     }
 }
 
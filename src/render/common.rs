@@ -1,5 +1,6 @@
 use std::borrow::Cow;
 
+use pyo3::exceptions::{PyAttributeError, PyIndexError, PyKeyError, PyTypeError, PyValueError};
 use pyo3::intern;
 use pyo3::prelude::*;
 use pyo3::types::PyString;
@@ -38,6 +39,54 @@ fn resolve_callable(variable: Bound<'_, PyAny>) -> PyResult<Option<Bound<'_, PyA
     Ok(Some(variable.call0()?))
 }
 
+/// Look up `part` on `variable` the way Django does: first by key, then by
+/// attribute and finally by list index.
+///
+/// Only the exceptions Django treats as "try the next kind of lookup" fall
+/// through. `Ok(None)` means no lookup succeeded.
+fn lookup<'py>(variable: &Bound<'py, PyAny>, part: &str) -> PyResult<Option<Bound<'py, PyAny>>> {
+    let py = variable.py();
+    match variable.get_item(part) {
+        Ok(item) => return Ok(Some(item)),
+        Err(err)
+            if err.is_instance_of::<PyTypeError>(py)
+                || err.is_instance_of::<PyAttributeError>(py)
+                || err.is_instance_of::<PyKeyError>(py)
+                || err.is_instance_of::<PyValueError>(py)
+                || err.is_instance_of::<PyIndexError>(py) => {}
+        Err(err) => return Err(err),
+    }
+    match variable.getattr(part) {
+        Ok(attr) => return Ok(Some(attr)),
+        Err(err)
+            if err.is_instance_of::<PyTypeError>(py)
+                || err.is_instance_of::<PyAttributeError>(py) => {}
+        Err(err) => return Err(err),
+    }
+    let Ok(index) = part.parse::<usize>() else {
+        return Ok(None);
+    };
+    match variable.get_item(index) {
+        Ok(item) => Ok(Some(item)),
+        Err(err)
+            if err.is_instance_of::<PyIndexError>(py)
+                || err.is_instance_of::<PyValueError>(py)
+                || err.is_instance_of::<PyKeyError>(py)
+                || err.is_instance_of::<PyTypeError>(py) =>
+        {
+            Ok(None)
+        }
+        Err(err) => Err(err),
+    }
+}
+
+fn is_silent_variable_failure(py: Python<'_>, err: &PyErr) -> bool {
+    err.value(py)
+        .getattr(intern!(py, "silent_variable_failure"))
+        .and_then(|silent| silent.is_truthy())
+        .unwrap_or(false)
+}
+
 impl Resolve for Variable {
     fn resolve<'t, 'py>(
         &self,
@@ -49,6 +98,15 @@ impl Resolve for Variable {
         let mut parts = self.parts(template);
         let (first, mut object_at) = parts.next().expect("Variable names cannot be empty");
         let Some(variable) = context.get(first) else {
+            if context.strict_variables {
+                return Err(RenderError::VariableDoesNotExist {
+                    key: first.to_string(),
+                    object: context.display(py),
+                    key_at: object_at.into(),
+                    object_at: None,
+                }
+                .into());
+            }
             return Ok(None);
         };
         let Some(mut variable) = resolve_callable(variable.bind(py).clone())? else {
@@ -56,29 +114,29 @@ impl Resolve for Variable {
         };
 
         for (part, key_at) in parts {
-            variable = match variable.get_item(part) {
-                Ok(variable) => variable,
-                Err(_) => match variable.getattr(part) {
-                    Ok(variable) => variable,
-                    Err(_) => {
-                        let Ok(int) = part.parse::<usize>() else {
-                            return match failures {
-                                ResolveFailures::Raise => Err(RenderError::VariableDoesNotExist {
-                                    key: part.to_string(),
-                                    object: variable.str()?.to_string(),
-                                    key_at: key_at.into(),
-                                    object_at: Some(object_at.into()),
-                                }
-                                .into()),
-                                ResolveFailures::IgnoreVariableDoesNotExist => Ok(None),
-                            };
-                        };
-                        match variable.get_item(int) {
-                            Ok(variable) => variable,
-                            Err(_) => todo!(),
+            let item = match lookup(&variable, part) {
+                Ok(item) => item,
+                Err(err) if is_silent_variable_failure(py, &err) => return Ok(None),
+                Err(err) => return Err(err.into()),
+            };
+            variable = match item {
+                Some(variable) => variable,
+                None => {
+                    let failures = match context.strict_variables {
+                        true => ResolveFailures::Raise,
+                        false => failures,
+                    };
+                    return match failures {
+                        ResolveFailures::Raise => Err(RenderError::VariableDoesNotExist {
+                            key: part.to_string(),
+                            object: variable.str()?.to_string(),
+                            key_at: key_at.into(),
+                            object_at: Some(object_at.into()),
                         }
-                    }
-                },
+                        .into()),
+                        ResolveFailures::IgnoreVariableDoesNotExist => Ok(None),
+                    };
+                }
             };
             variable = match resolve_callable(variable)? {
                 Some(variable) => variable,
@@ -259,6 +317,35 @@ mod tests {
 
     use pyo3::types::{PyDict, PyList, PyString};
 
+    use crate::error::PyRenderError;
+    use crate::parse::Parser;
+
+    #[test]
+    fn test_render_text_borrows_template() {
+        Python::initialize();
+
+        Python::attach(|py| {
+            let source = format!("{}{{{{ name }}}}", "<td>x</td>".repeat(10_000));
+            let template = TemplateString(&source);
+            let len = source.len() - "{{ name }}".len();
+            let text = Text::new((0, len));
+            let nodes = vec![TokenTree::Text(text)];
+
+            for autoescape in [false, true] {
+                let mut context = Context::new(HashMap::new(), None, autoescape);
+                for rendered in [
+                    text.render(py, template, &mut context).unwrap(),
+                    nodes.render(py, template, &mut context).unwrap(),
+                ] {
+                    let Cow::Borrowed(rendered) = rendered else {
+                        panic!("text should not be copied");
+                    };
+                    assert!(std::ptr::eq(rendered, &source[..len]));
+                }
+            }
+        })
+    }
+
     #[test]
     fn test_render_variable() {
         Python::initialize();
@@ -339,6 +426,179 @@ user = User('Lily')
         })
     }
 
+    #[test]
+    fn test_render_dynamic_attribute_lookup() {
+        Python::initialize();
+
+        Python::attach(|py| {
+            let locals = PyDict::new(py);
+            py.run(
+                c"
+class Dynamic:
+    def __getattr__(self, name):
+        if name.startswith('missing'):
+            raise AttributeError(name)
+        if name.startswith('method'):
+            return lambda: f'called {name}'
+        return f'computed {name}'
+
+obj = Dynamic()
+",
+                None,
+                Some(&locals),
+            )
+            .unwrap();
+
+            let context = locals.extract().unwrap();
+            let mut context = Context::new(context, None, false);
+            let template = TemplateString("{{ obj.anything }}{{ obj.missing }}{{ obj.method }}");
+
+            let variable = Variable::new((3, 12));
+            let rendered = variable.render(py, template, &mut context).unwrap();
+            assert_eq!(rendered, "computed anything");
+
+            let variable = Variable::new((21, 11));
+            let error = variable.render(py, template, &mut context).unwrap_err();
+            assert!(matches!(
+                error,
+                PyRenderError::RenderError(RenderError::VariableDoesNotExist { .. })
+            ));
+
+            let variable = Variable::new((38, 10));
+            let rendered = variable.render(py, template, &mut context).unwrap();
+            assert_eq!(rendered, "called method");
+        })
+    }
+
+    #[test]
+    fn test_render_mapping_lookup() {
+        Python::initialize();
+
+        Python::attach(|py| {
+            let locals = PyDict::new(py);
+            py.run(
+                c"
+from collections import defaultdict
+from collections.abc import Mapping
+
+class Names(Mapping):
+    def __getitem__(self, key):
+        if key == 'first':
+            return 'Lily'
+        raise KeyError(key)
+
+    def __iter__(self):
+        return iter(['first'])
+
+    def __len__(self):
+        return 1
+
+names = Names()
+counts = defaultdict(lambda: 'default')
+",
+                None,
+                Some(&locals),
+            )
+            .unwrap();
+
+            let context = locals.extract().unwrap();
+            let mut context = Context::new(context, None, false);
+            let template = TemplateString("{{ names.first }}{{ counts.missing }}");
+            let names = Variable::new((3, 11));
+            let counts = Variable::new((20, 14));
+
+            let rendered = names.render(py, template, &mut context).unwrap();
+            assert_eq!(rendered, "Lily");
+            let rendered = counts.render(py, template, &mut context).unwrap();
+            assert_eq!(rendered, "default");
+        })
+    }
+
+    #[test]
+    fn test_render_lookup_error_propagates() {
+        Python::initialize();
+
+        Python::attach(|py| {
+            let locals = PyDict::new(py);
+            py.run(
+                c"
+class Broken:
+    def __getitem__(self, key):
+        raise RuntimeError('broken')
+
+class Silent(Exception):
+    silent_variable_failure = True
+
+class Quiet:
+    def __getitem__(self, key):
+        raise Silent
+
+broken = Broken()
+quiet = Quiet()
+items = ['a']
+",
+                Some(&locals),
+                None,
+            )
+            .unwrap();
+
+            let context = locals.extract().unwrap();
+            let mut context = Context::new(context, None, false);
+            let template = TemplateString("{{ broken.x }}{{ quiet.x }}{{ items.5 }}");
+            let broken = Variable::new((3, 8));
+            let quiet = Variable::new((17, 7));
+            let items = Variable::new((30, 7));
+
+            let error = broken.render(py, template, &mut context).unwrap_err();
+            let PyRenderError::PyErr(error) = error else {
+                panic!("Expected a Python error");
+            };
+            assert!(error.is_instance_of::<pyo3::exceptions::PyRuntimeError>(py));
+            let rendered = quiet.render(py, template, &mut context).unwrap();
+            assert_eq!(rendered, "");
+            let error = items.render(py, template, &mut context).unwrap_err();
+            assert!(matches!(
+                error,
+                PyRenderError::RenderError(RenderError::VariableDoesNotExist { .. })
+            ));
+        })
+    }
+
+    #[test]
+    fn test_render_do_not_call_in_templates_attribute() {
+        Python::initialize();
+
+        Python::attach(|py| {
+            let locals = PyDict::new(py);
+            py.run(
+                c"
+class Manager:
+    do_not_call_in_templates = True
+
+    def __call__(self):
+        raise AssertionError('Managers should not be called in templates')
+
+    def __str__(self):
+        return 'manager'
+
+class MyModel:
+    objects = Manager()
+",
+                Some(&locals),
+                None,
+            )
+            .unwrap();
+
+            let context = locals.extract().unwrap();
+            let mut context = Context::new(context, None, false);
+            let template = TemplateString("{{ MyModel.objects }}");
+            let variable = Variable::new((3, 15));
+
+            let rendered = variable.render(py, template, &mut context).unwrap();
+            assert_eq!(rendered, "manager");
+        })
+    }
+
     #[test]
     fn test_render_html_autoescape() {
         Python::initialize();
@@ -354,4 +614,132 @@ user = User('Lily')
             assert_eq!(rendered, "&lt;p&gt;Hello World!&lt;/p&gt;");
         })
     }
+
+    #[test]
+    fn test_render_strict_variables() {
+        Python::initialize();
+
+        Python::attach(|py| {
+            let user = PyDict::new(py).into_any().unbind();
+            let context = HashMap::from([("user".to_string(), user)]);
+            let mut context = Context::new(context, None, false);
+            let template = TemplateString("{{ missing }}{{ user.name }}");
+            let missing = Variable::new((3, 7));
+            let name = Variable::new((17, 9));
+
+            assert_eq!(missing.render(py, template, &mut context).unwrap(), "");
+            assert_eq!(name.render(py, template, &mut context).unwrap(), "");
+
+            context.strict_variables = true;
+            let error = missing.render(py, template, &mut context).unwrap_err();
+            let PyRenderError::RenderError(RenderError::VariableDoesNotExist {
+                key, key_at, ..
+            }) = error
+            else {
+                panic!("expected VariableDoesNotExist, got {error:?}");
+            };
+            assert_eq!(key, "missing");
+            assert_eq!(key_at, (3, 7).into());
+            let error = name.render(py, template, &mut context).unwrap_err();
+            assert!(matches!(
+                error,
+                PyRenderError::RenderError(RenderError::VariableDoesNotExist { .. })
+            ));
+        })
+    }
+
+    #[test]
+    fn test_render_for_loop_error_pops_variables() {
+        Python::initialize();
+
+        Python::attach(|py| {
+            let x = PyString::new(py, "outer").into_any().unbind();
+            let y = PyList::new(py, [[1, 2]]).unwrap().into_any().unbind();
+            let context = HashMap::from([("x".to_string(), x), ("y".to_string(), y)]);
+            let mut context = Context::new(context, None, false);
+            let template = TemplateString(
+                "{% for x in y %}{{ x }}{% for a, b in x %}{% endfor %}{% endfor %}",
+            );
+            let libraries = HashMap::new();
+            let nodes = Parser::new(py, template, &libraries).parse().unwrap();
+
+            let error = nodes.render(py, template, &mut context).unwrap_err();
+            assert!(matches!(
+                error,
+                PyRenderError::RenderError(RenderError::TupleUnpackError { .. })
+            ));
+            let x = context.get("x").unwrap().bind(py);
+            assert_eq!(x.to_string(), "outer");
+            assert_eq!(context.scope_depth(), 0);
+            assert!(context.get_for_loop(0).is_none());
+        })
+    }
+
+    #[test]
+    fn test_render_for_loop_unpack_three() {
+        Python::initialize();
+
+        Python::attach(|py| {
+            let triples = PyList::new(py, [(1, 2, 3), (4, 5, 6)]).unwrap();
+            let context = HashMap::from([("triples".to_string(), triples.into_any().unbind())]);
+            let mut context = Context::new(context, None, false);
+            let template =
+                TemplateString("{% for a, b, c in triples %}{{ c }}{{ b }}{{ a }},{% endfor %}");
+            let libraries = HashMap::new();
+            let nodes = Parser::new(py, template, &libraries).parse().unwrap();
+
+            let rendered = nodes.render(py, template, &mut context).unwrap();
+            assert_eq!(rendered, "321,654,");
+        })
+    }
+
+    #[test]
+    fn test_render_for_loop_unpack_too_few_values() {
+        Python::initialize();
+
+        Python::attach(|py| {
+            let pairs = PyList::new(py, [(1, 2)]).unwrap();
+            let context = HashMap::from([("pairs".to_string(), pairs.into_any().unbind())]);
+            let mut context = Context::new(context, None, false);
+            let template = TemplateString("{% for a, b, c in pairs %}{% endfor %}");
+            let libraries = HashMap::new();
+            let nodes = Parser::new(py, template, &libraries).parse().unwrap();
+
+            let error = nodes.render(py, template, &mut context).unwrap_err();
+            let PyRenderError::RenderError(RenderError::TupleUnpackError {
+                expected_count,
+                actual_count,
+                expected_at,
+                actual_at,
+            }) = error
+            else {
+                panic!("expected a TupleUnpackError, got {error:?}");
+            };
+            assert_eq!(expected_count, 3);
+            assert_eq!(actual_count, 2);
+            assert_eq!(expected_at, (7, 7).into());
+            assert_eq!(actual_at, (18, 5).into());
+        })
+    }
+
+    #[test]
+    fn test_render_with_restores_shadowed_variable() {
+        Python::initialize();
+
+        Python::attach(|py| {
+            let x = 10i64.into_pyobject(py).unwrap().into_any().unbind();
+            let context = HashMap::from([("x".to_string(), x)]);
+            let mut context = Context::new(context, None, false);
+            let template = TemplateString(
+                "{% with x=20 %}{{ x }}{% with x=30 y=x %}{{ x }}{{ y }}{% endwith %}{{ x }}{% endwith %}{{ x }}",
+            );
+            let libraries = HashMap::new();
+            let nodes = Parser::new(py, template, &libraries).parse().unwrap();
+
+            let rendered = nodes.render(py, template, &mut context).unwrap();
+            assert_eq!(rendered, "2030202010");
+            assert_eq!(context.scope_depth(), 0);
+            assert!(context.get("y").is_none());
+        })
+    }
 }
@@ -2,21 +2,34 @@ use std::borrow::Cow;
 use std::sync::LazyLock;
 
 use html_escape::encode_quoted_attribute_to_string;
-use num_bigint::{BigInt, ToBigInt};
-use num_traits::ToPrimitive;
+use num_bigint::{BigInt, BigUint, Sign, ToBigInt};
+use num_traits::{Euclid, ToPrimitive};
+use pyo3::exceptions::{
+    PyAttributeError, PyIndexError, PyKeyError, PyOverflowError, PyTypeError, PyValueError,
+};
+use pyo3::intern;
 use pyo3::prelude::*;
 use pyo3::sync::PyOnceLock;
-use pyo3::types::PyType;
+use pyo3::types::{IntoPyDict, PyFloat, PyInt, PyList, PySlice, PyString, PyTuple, PyType};
 
 use crate::error::RenderError;
 use crate::filters::{
-    AddFilter, AddSlashesFilter, CapfirstFilter, CenterFilter, DefaultFilter, EscapeFilter,
-    ExternalFilter, FilterType, LowerFilter, SafeFilter, SlugifyFilter, UpperFilter,
+    AddFilter, AddSlashesFilter, CapfirstFilter, CenterFilter, CutFilter, DefaultFilter,
+    DefaultIfNoneFilter, DictSortFilter, EscapeFilter, ExternalFilter, FilterType,
+    FloatFormatFilter, IntCommaFilter, IntWordFilter, JoinFilter, JsonFilter, LengthFilter,
+    LinebreaksbrFilter, LjustFilter, LowerFilter, MakeListFilter, NaturalTimeFilter, OrdinalFilter,
+    PluralizeFilter, RjustFilter, SafeFilter, SliceFilter, SlugifyFilter, TruncateWordsFilter,
+    UnicodeSlugifyFilter, UpperFilter, UrlizeFilter, UrlizeTruncFilter,
 };
 use crate::parse::Filter;
-use crate::render::types::{AsBorrowedContent, Content, ContentString, Context, IntoOwnedContent};
-use crate::render::{Resolve, ResolveFailures, ResolveResult};
-use crate::types::TemplateString;
+use crate::render::numberformat::{Grouping, group_digits, number_format};
+use crate::render::timezone::naturaltime;
+use crate::render::types::{
+    AsBorrowedContent, Content, ContentString, Context, IntoOwnedContent, conditional_escape,
+};
+use crate::render::urlize::Urlizer;
+use crate::render::{Evaluate, Resolve, ResolveFailures, ResolveResult};
+use crate::types::{Argument, TemplateString};
 use regex::Regex;
 use unicode_normalization::UnicodeNormalization;
 
@@ -29,6 +42,16 @@ static WHITESPACE_RE: LazyLock<Regex> =
     LazyLock::new(|| Regex::new(r"[-\s]+").expect("Static string will never panic"));
 
 static SAFEDATA: PyOnceLock<Py<PyType>> = PyOnceLock::new();
+static JSON_DUMPS: PyOnceLock<Py<PyAny>> = PyOnceLock::new();
+static DJANGO_JSON_ENCODER: PyOnceLock<Py<PyType>> = PyOnceLock::new();
+static DECIMAL: PyOnceLock<Py<PyAny>> = PyOnceLock::new();
+static DECIMAL_CONTEXT: PyOnceLock<Py<PyAny>> = PyOnceLock::new();
+static GET_DECIMAL_CONTEXT: PyOnceLock<Py<PyAny>> = PyOnceLock::new();
+static NGETTEXT: PyOnceLock<Py<PyAny>> = PyOnceLock::new();
+static PGETTEXT: PyOnceLock<Py<PyAny>> = PyOnceLock::new();
+
+static LEADING_INTEGER_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^-?\d+").expect("Static string will never panic"));
 
 impl Resolve for Filter {
     fn resolve<'t, 'py>(
@@ -44,13 +67,34 @@ impl Resolve for Filter {
             FilterType::AddSlashes(filter) => filter.resolve(left, py, template, context),
             FilterType::Capfirst(filter) => filter.resolve(left, py, template, context),
             FilterType::Center(filter) => filter.resolve(left, py, template, context),
+            FilterType::Cut(filter) => filter.resolve(left, py, template, context),
             FilterType::Default(filter) => filter.resolve(left, py, template, context),
+            FilterType::DefaultIfNone(filter) => filter.resolve(left, py, template, context),
+            FilterType::DictSort(filter) => filter.resolve(left, py, template, context),
             FilterType::Escape(filter) => filter.resolve(left, py, template, context),
             FilterType::External(filter) => filter.resolve(left, py, template, context),
+            FilterType::FloatFormat(filter) => filter.resolve(left, py, template, context),
+            FilterType::IntComma(filter) => filter.resolve(left, py, template, context),
+            FilterType::IntWord(filter) => filter.resolve(left, py, template, context),
+            FilterType::Join(filter) => filter.resolve(left, py, template, context),
+            FilterType::Json(filter) => filter.resolve(left, py, template, context),
+            FilterType::Length(filter) => filter.resolve(left, py, template, context),
+            FilterType::Linebreaksbr(filter) => filter.resolve(left, py, template, context),
+            FilterType::Ljust(filter) => filter.resolve(left, py, template, context),
             FilterType::Lower(filter) => filter.resolve(left, py, template, context),
+            FilterType::MakeList(filter) => filter.resolve(left, py, template, context),
+            FilterType::NaturalTime(filter) => filter.resolve(left, py, template, context),
+            FilterType::Ordinal(filter) => filter.resolve(left, py, template, context),
+            FilterType::Pluralize(filter) => filter.resolve(left, py, template, context),
+            FilterType::Rjust(filter) => filter.resolve(left, py, template, context),
             FilterType::Safe(filter) => filter.resolve(left, py, template, context),
+            FilterType::Slice(filter) => filter.resolve(left, py, template, context),
             FilterType::Slugify(filter) => filter.resolve(left, py, template, context),
+            FilterType::TruncateWords(filter) => filter.resolve(left, py, template, context),
+            FilterType::UnicodeSlugify(filter) => filter.resolve(left, py, template, context),
             FilterType::Upper(filter) => filter.resolve(left, py, template, context),
+            FilterType::Urlize(filter) => filter.resolve(left, py, template, context),
+            FilterType::UrlizeTrunc(filter) => filter.resolve(left, py, template, context),
         }
     }
 }
@@ -125,32 +169,89 @@ impl ResolveFilter for CapfirstFilter {
         context: &mut Context,
     ) -> ResolveResult<'t, 'py> {
         let content = match variable {
-            Some(content) => {
-                let content_string = content.render(context)?.into_owned();
-                let mut chars = content_string.chars();
-                let first_char = match chars.next() {
-                    Some(c) => c.to_uppercase(),
-                    None => return Ok(Some("".as_content())),
+            Some(content) => content.resolve_string(context)?.map_content(|content| {
+                let Some(first) = content.chars().next() else {
+                    return content;
                 };
-                let string: String = first_char.chain(chars).collect();
-                string.into_content()
-            }
+                let rest = &content[first.len_utf8()..];
+                Cow::Owned(first.to_uppercase().chain(rest.chars()).collect())
+            }),
             None => "".as_content(),
         };
         Ok(Some(content))
     }
 }
 
-fn resolve_bigint(bigint: BigInt, at: (usize, usize)) -> Result<usize, RenderError> {
-    match bigint.to_isize() {
-        Some(n) => Ok(n.max(0) as usize),
-        None => Err(RenderError::OverflowError {
+/// How to handle integer arguments which don't fit in a `usize`.
+#[derive(Clone, Copy)]
+enum Overflow {
+    /// Raise an `OverflowError`, like Python does when the argument is used
+    /// as an index or size.
+    Raise,
+    /// Clamp to `usize::MAX`, for arguments Python only compares against.
+    Clamp,
+}
+
+fn resolve_bigint(
+    bigint: BigInt,
+    at: (usize, usize),
+    overflow: Overflow,
+) -> Result<usize, RenderError> {
+    match (bigint.to_isize(), overflow) {
+        (Some(n), _) => Ok(n.max(0) as usize),
+        (None, Overflow::Clamp) => match bigint.sign() {
+            Sign::Minus => Ok(0),
+            _ => Ok(usize::MAX),
+        },
+        (None, Overflow::Raise) => Err(RenderError::OverflowError {
             argument: bigint.to_string(),
             argument_at: at.into(),
         }),
     }
 }
 
+/// Convert a filter argument to a `usize` like Python's `int()` would, clamping
+/// negative numbers to zero.
+fn resolve_usize_argument(
+    arg: Content<'_, '_>,
+    at: (usize, usize),
+    overflow: Overflow,
+) -> Result<usize, RenderError> {
+    match arg {
+        Content::Int(left) => resolve_bigint(left, at, overflow),
+        Content::String(left) => match left.as_raw().parse::<BigInt>() {
+            Ok(n) => resolve_bigint(n, at, overflow),
+            Err(_) => Err(RenderError::InvalidArgumentInteger {
+                argument: format!("'{}'", left.as_raw()),
+                argument_at: at.into(),
+            }),
+        },
+        Content::Float(left) => match left.trunc().to_bigint() {
+            Some(n) => resolve_bigint(n, at, overflow),
+            None => Err(RenderError::InvalidArgumentFloat {
+                argument: left.to_string(),
+                argument_at: at.into(),
+            }),
+        },
+        Content::Py(left) => {
+            if let Ok(int) = left.extract::<BigInt>() {
+                return resolve_bigint(int, at, overflow);
+            }
+            if let Ok(float) = left.extract::<f64>() {
+                return resolve_usize_argument(Content::Float(float), at, overflow);
+            }
+            match left.extract::<&str>().map(str::parse::<BigInt>) {
+                Ok(Ok(int)) => resolve_bigint(int, at, overflow),
+                _ => Err(RenderError::InvalidArgumentInteger {
+                    argument: left.to_string(),
+                    argument_at: at.into(),
+                }),
+            }
+        }
+        Content::Bool(b) => Ok(b.into()),
+    }
+}
+
 impl ResolveFilter for CenterFilter {
     fn resolve<'t, 'py>(
         &self,
@@ -164,74 +265,152 @@ impl ResolveFilter for CenterFilter {
         let Some(content) = variable else {
             return Ok(Some("".as_content()));
         };
-        let content = content.render(context)?;
+        let content = content.resolve_string(context)?;
         let arg = self
             .argument
             .resolve(py, template, context, ResolveFailures::Raise)?
             .expect("missing argument in context should already have raised");
 
+        let len = content.as_raw().chars().count();
         let size = match arg {
-            Content::Int(left) => resolve_bigint(left, self.argument.at)?,
-            Content::String(left) => match left.as_raw().parse::<BigInt>() {
-                Ok(n) => resolve_bigint(n, self.argument.at)?,
-                Err(_) => {
-                    return Err(RenderError::InvalidArgumentInteger {
-                        argument: format!("'{}'", left.as_raw()),
-                        argument_at: self.argument.at.into(),
-                    }
-                    .into());
-                }
-            },
-            Content::Float(left) => match left.trunc().to_bigint() {
-                Some(n) => resolve_bigint(n, self.argument.at)?,
-                None => {
-                    return Err(RenderError::InvalidArgumentFloat {
-                        argument: left.to_string(),
-                        argument_at: self.argument.at.into(),
-                    }
-                    .into());
-                }
-            },
-            Content::Py(left) => match left.extract::<BigInt>() {
-                Ok(left) => resolve_bigint(left, self.argument.at)?,
-                Err(_) => {
-                    let argument = left.to_string();
-                    let argument_at = self.argument.at.into();
-                    let err = match left.extract::<f64>() {
-                        Ok(_) => RenderError::InvalidArgumentFloat {
-                            argument,
-                            argument_at,
-                        },
-                        Err(_) => RenderError::InvalidArgumentInteger {
-                            argument,
-                            argument_at,
-                        },
-                    };
-                    return Err(err.into());
-                }
-            },
-            Content::Bool(true) if content.is_empty() => return Ok(Some(" ".as_content())),
-            Content::Bool(_) => return Ok(Some(content.into_content())),
+            Content::Bool(true) if len == 0 => return Ok(Some(" ".as_content())),
+            Content::Bool(_) => return Ok(Some(Content::String(content))),
+            arg => resolve_usize_argument(arg, self.argument.at, Overflow::Raise)?,
         };
 
-        if size <= content.len() {
-            return Ok(Some(content.into_content()));
+        if size <= len {
+            return Ok(Some(Content::String(content)));
         }
-        if size % 2 == 0 && content.len() % 2 != 0 {
+        if size % 2 == 0 && len % 2 != 0 {
             // If the size is even and the content length is odd, we need to adjust the centering
-            right = (size - content.len()).div_ceil(2);
-            left = size - content.len() - right;
+            right = (size - len).div_ceil(2);
+            left = size - len - right;
         } else {
-            right = (size - content.len()) / 2;
-            left = size - content.len() - right;
+            right = (size - len) / 2;
+            left = size - len - right;
         }
-        let mut centered = String::with_capacity(size);
+        Ok(Some(content.map_content(|content| {
+            let mut centered = String::with_capacity(size);
+            centered.push_str(&" ".repeat(left));
+            centered.push_str(&content);
+            centered.push_str(&" ".repeat(right));
+            Cow::Owned(centered)
+        })))
+    }
+}
+
+enum Justify {
+    Left,
+    Right,
+}
+
+/// Pad `variable` with spaces to the width given by `argument`, like Python's
+/// `str.ljust` and `str.rjust`. A missing variable is padded like Django's
+/// empty `string_if_invalid`.
+fn justify<'t, 'py>(
+    variable: Option<Content<'t, 'py>>,
+    argument: &Argument,
+    py: Python<'py>,
+    template: TemplateString<'t>,
+    context: &mut Context,
+    justify: Justify,
+) -> ResolveResult<'t, 'py> {
+    let content = match variable {
+        Some(content) => content.resolve_string(context)?,
+        None => ContentString::String(Cow::Borrowed("")),
+    };
+    let width = argument
+        .resolve(py, template, context, ResolveFailures::Raise)?
+        .expect("missing argument in context should already have raised");
+    let width = resolve_usize_argument(width, argument.at, Overflow::Raise)?;
+    let len = content.as_raw().chars().count();
+    if width <= len {
+        return Ok(Some(Content::String(content)));
+    }
+    let padding = " ".repeat(width - len);
+    Ok(Some(content.map_content(|content| {
+        Cow::Owned(match justify {
+            Justify::Left => content.into_owned() + &padding,
+            Justify::Right => padding + &content,
+        })
+    })))
+}
+
+impl ResolveFilter for LjustFilter {
+    fn resolve<'t, 'py>(
+        &self,
+        variable: Option<Content<'t, 'py>>,
+        py: Python<'py>,
+        template: TemplateString<'t>,
+        context: &mut Context,
+    ) -> ResolveResult<'t, 'py> {
+        justify(
+            variable,
+            &self.argument,
+            py,
+            template,
+            context,
+            Justify::Left,
+        )
+    }
+}
 
-        centered.push_str(&" ".repeat(left));
-        centered.push_str(&content);
-        centered.push_str(&" ".repeat(right));
+impl ResolveFilter for RjustFilter {
+    fn resolve<'t, 'py>(
+        &self,
+        variable: Option<Content<'t, 'py>>,
+        py: Python<'py>,
+        template: TemplateString<'t>,
+        context: &mut Context,
+    ) -> ResolveResult<'t, 'py> {
+        justify(
+            variable,
+            &self.argument,
+            py,
+            template,
+            context,
+            Justify::Right,
+        )
+    }
+}
 
-        Ok(Some(centered.into_content()))
+impl ResolveFilter for CutFilter {
+    fn resolve<'t, 'py>(
+        &self,
+        variable: Option<Content<'t, 'py>>,
+        py: Python<'py>,
+        template: TemplateString<'t>,
+        context: &mut Context,
+    ) -> ResolveResult<'t, 'py> {
+        let Some(content) = variable else {
+            return Ok(Some("".as_content()));
+        };
+        let arg = self
+            .argument
+            .resolve(py, template, context, ResolveFailures::Raise)?
+            .expect("missing argument in context should already have raised");
+        let arg = match arg {
+            Content::String(arg) => arg.into_raw(),
+            Content::Py(arg) if arg.is_instance_of::<PyString>() => {
+                Cow::Owned(arg.extract::<String>()?)
+            }
+            arg => {
+                let arg_type = arg.to_py(py).get_type().name()?;
+                let error = PyTypeError::new_err(format!(
+                    "replace() argument 1 must be str, not {arg_type}"
+                ));
+                return Err(error.into());
+            }
+        };
+        let cut = |content: Cow<'t, str>| Cow::Owned(content.replace(arg.as_ref(), ""));
+        Ok(Some(match content.resolve_string(context)? {
+            // Like Django, cutting semicolons could break HTML entities, so
+            // the result is no longer considered safe.
+            ContentString::HtmlSafe(content) if arg == ";" => {
+                Content::String(ContentString::HtmlUnsafe(cut(content)))
+            }
+            content => content.map_content(cut),
+        }))
     }
 }
 
@@ -243,11 +422,163 @@ impl ResolveFilter for DefaultFilter {
         template: TemplateString<'t>,
         context: &mut Context,
     ) -> ResolveResult<'t, 'py> {
+        // Django resolves filter arguments before calling the filter, so a
+        // missing argument raises even when it isn't used.
+        let argument = self
+            .argument
+            .resolve(py, template, context, ResolveFailures::Raise)?;
+        // Like Django's `value or arg`, so any falsy value takes the default
+        // and errors from `__bool__` propagate.
+        match variable {
+            Some(Content::Py(left)) => match left.is_truthy()? {
+                true => Ok(Some(Content::Py(left))),
+                false => Ok(argument),
+            },
+            Some(left) if left.evaluate(py, template, context) == Some(true) => Ok(Some(left)),
+            _ => Ok(argument),
+        }
+    }
+}
+
+impl ResolveFilter for DefaultIfNoneFilter {
+    fn resolve<'t, 'py>(
+        &self,
+        variable: Option<Content<'t, 'py>>,
+        py: Python<'py>,
+        template: TemplateString<'t>,
+        context: &mut Context,
+    ) -> ResolveResult<'t, 'py> {
+        let argument = self
+            .argument
+            .resolve(py, template, context, ResolveFailures::Raise)?;
         match variable {
-            Some(left) => Ok(Some(left)),
-            None => self
-                .argument
-                .resolve(py, template, context, ResolveFailures::Raise),
+            Some(Content::Py(left)) if left.is_none() => Ok(argument),
+            left => Ok(left),
+        }
+    }
+}
+
+/// How `dictsort` finds the key of each element, like Django's
+/// `_property_resolver`.
+enum SortKey<'py> {
+    /// A number, used as an index into each element.
+    Index(Bound<'py, PyAny>),
+    /// A dotted path of keys or attributes.
+    Path(Vec<String>),
+}
+
+impl<'py> SortKey<'py> {
+    fn new(py: Python<'py>, argument: Content<'_, 'py>) -> PyResult<Self> {
+        let path = match argument {
+            Content::Int(_) | Content::Float(_) | Content::Bool(_) => {
+                return Ok(Self::Index(argument.to_py(py)));
+            }
+            Content::String(path) => path.as_raw().to_string(),
+            Content::Py(object) if object.is_instance_of::<PyString>() => object.extract()?,
+            Content::Py(object) => {
+                let float = PyType::new::<PyFloat>(py);
+                return match float.call1((&object,)) {
+                    Ok(_) => Ok(Self::Index(object)),
+                    Err(error) if error.is_instance_of::<PyValueError>(py) => Err(
+                        PyTypeError::new_err("dictsort argument must be a number or a string"),
+                    ),
+                    Err(error) => Err(error),
+                };
+            }
+        };
+        let float = PyType::new::<PyFloat>(py);
+        if float.call1((&path,)).is_ok() {
+            return Ok(Self::Index(PyString::new(py, &path).into_any()));
+        }
+        if path.contains("._") || path.starts_with('_') {
+            return Err(PyAttributeError::new_err(
+                "Access to private variables is forbidden.",
+            ));
+        }
+        Ok(Self::Path(path.split('.').map(str::to_string).collect()))
+    }
+
+    fn resolve(&self, element: Bound<'py, PyAny>) -> PyResult<Bound<'py, PyAny>> {
+        match self {
+            Self::Index(index) => element.get_item(index),
+            Self::Path(parts) => {
+                let py = element.py();
+                let mut value = element;
+                for part in parts {
+                    value = match value.get_item(part) {
+                        Ok(item) => item,
+                        Err(error)
+                            if error.is_instance_of::<PyAttributeError>(py)
+                                || error.is_instance_of::<PyIndexError>(py)
+                                || error.is_instance_of::<PyKeyError>(py)
+                                || error.is_instance_of::<PyTypeError>(py)
+                                || error.is_instance_of::<PyValueError>(py) =>
+                        {
+                            value.getattr(part.as_str())?
+                        }
+                        Err(error) => return Err(error),
+                    };
+                }
+                Ok(value)
+            }
+        }
+    }
+}
+
+/// Sort `value` like Python's `sorted(value, key=key)`.
+fn dictsort<'py>(
+    py: Python<'py>,
+    value: &Content<'_, 'py>,
+    key: SortKey<'py>,
+) -> PyResult<Bound<'py, PyList>> {
+    // Like `sorted`, every key is found before any are compared.
+    let (keys, elements): (Vec<_>, Vec<_>) = value
+        .iter(py)?
+        .map(|element| {
+            let element = element?;
+            Ok((key.resolve(element.clone())?, element))
+        })
+        .collect::<PyResult<Vec<_>>>()?
+        .into_iter()
+        .unzip();
+    // Let Python sort the positions by their keys, so the ordering and any
+    // comparison errors match Django's exactly.
+    let keys = PyList::new(py, keys)?;
+    let order = PyList::new(py, 0..elements.len())?;
+    let kwargs = [("key", keys.getattr(intern!(py, "__getitem__"))?)].into_py_dict(py)?;
+    order.call_method(intern!(py, "sort"), (), Some(&kwargs))?;
+    let sorted = order
+        .iter()
+        .map(|index| Ok(elements[index.extract::<usize>()?].clone()))
+        .collect::<PyResult<Vec<_>>>()?;
+    PyList::new(py, sorted)
+}
+
+impl ResolveFilter for DictSortFilter {
+    fn resolve<'t, 'py>(
+        &self,
+        variable: Option<Content<'t, 'py>>,
+        py: Python<'py>,
+        template: TemplateString<'t>,
+        context: &mut Context,
+    ) -> ResolveResult<'t, 'py> {
+        // A missing variable sorts like Django's empty `string_if_invalid`.
+        let value = variable.unwrap_or_else(|| "".as_content());
+        let argument = self
+            .argument
+            .resolve(py, template, context, ResolveFailures::Raise)?
+            .expect("missing argument in context should already have raised");
+        let sorted = SortKey::new(py, argument).and_then(|key| dictsort(py, &value, key));
+        match sorted {
+            Ok(sorted) => Ok(Some(Content::Py(sorted.into_any()))),
+            // Like Django, render nothing when the values can't be sorted.
+            Err(error)
+                if error.is_instance_of::<PyAttributeError>(py)
+                    || error.is_instance_of::<PyTypeError>(py) =>
+            {
+                Ok(Some("".as_content()))
+            }
+            Err(error) => Err(error.into()),
         }
     }
 }
@@ -271,12 +602,7 @@ impl ResolveFilter for EscapeFilter {
                     }
                     Content::Int(n) => Cow::Owned(n.to_string()),
                     Content::Float(n) => Cow::Owned(n.to_string()),
-                    Content::Py(object) => {
-                        let content = object.str()?.extract::<String>()?;
-                        let mut encoded = String::new();
-                        encode_quoted_attribute_to_string(&content, &mut encoded);
-                        Cow::Owned(encoded)
-                    }
+                    Content::Py(object) => conditional_escape(&object)?.content(),
                     Content::Bool(true) => Cow::Borrowed("True"),
                     Content::Bool(false) => Cow::Borrowed("False"),
                 },
@@ -307,140 +633,1003 @@ impl ResolveFilter for ExternalFilter {
     }
 }
 
-impl ResolveFilter for LowerFilter {
+/// Escape the characters which could end a `<script>` tag or start an HTML
+/// entity, matching `django.utils.html.json_script`.
+fn escape_json(json: &str) -> String {
+    let mut escaped = String::with_capacity(json.len());
+    for c in json.chars() {
+        match c {
+            '<' => escaped.push_str("\\u003C"),
+            '>' => escaped.push_str("\\u003E"),
+            '&' => escaped.push_str("\\u0026"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// The precision and formatting options parsed from a `floatformat` argument.
+struct FloatFormatOptions {
+    precision: i64,
+    use_l10n: bool,
+    force_grouping: bool,
+}
+
+impl FloatFormatOptions {
+    /// Parse an argument like `"2"`, `"-3g"` or `"2gu"`, returning `None` if
+    /// the precision isn't an integer.
+    fn from_str(arg: &str) -> Option<Self> {
+        let (arg, use_l10n, force_grouping) =
+            if let Some(arg) = arg.strip_suffix("gu").or_else(|| arg.strip_suffix("ug")) {
+                (arg, false, true)
+            } else if let Some(arg) = arg.strip_suffix('g') {
+                (arg, true, true)
+            } else if let Some(arg) = arg.strip_suffix('u') {
+                (arg, false, false)
+            } else {
+                (arg, true, false)
+            };
+        let precision = match arg {
+            "" => -1,
+            arg => arg.trim().parse().ok()?,
+        };
+        Some(Self {
+            precision,
+            use_l10n,
+            force_grouping,
+        })
+    }
+
+    fn from_content(arg: Option<Content<'_, '_>>) -> PyResult<Option<Self>> {
+        let precision = match arg {
+            None => -1,
+            Some(Content::String(arg)) => return Ok(Self::from_str(arg.as_raw())),
+            Some(Content::Py(arg)) if arg.is_instance_of::<PyString>() => {
+                return Ok(Self::from_str(arg.extract::<&str>()?));
+            }
+            Some(arg) => match arg.to_bigint().and_then(|precision| precision.to_i64()) {
+                Some(precision) => precision,
+                None => return Ok(None),
+            },
+        };
+        Ok(Some(Self {
+            precision,
+            use_l10n: true,
+            force_grouping: false,
+        }))
+    }
+}
+
+/// Build the decimal string for a `Decimal` rounded by `floatformat`.
+fn decimal_string(sign: bool, digits: &[u8], exponent: i64) -> String {
+    let decimal_places = exponent.unsigned_abs() as usize;
+    let mut number: Vec<char> = digits
+        .iter()
+        .rev()
+        .map(|digit| char::from(b'0' + digit))
+        .collect();
+    while number.len() <= decimal_places {
+        number.push('0');
+    }
+    number.insert(decimal_places, '.');
+    if sign {
+        number.push('-');
+    }
+    number.into_iter().rev().collect()
+}
+
+impl ResolveFilter for FloatFormatFilter {
     fn resolve<'t, 'py>(
         &self,
         variable: Option<Content<'t, 'py>>,
-        _py: Python<'py>,
-        _template: TemplateString<'t>,
+        py: Python<'py>,
+        template: TemplateString<'t>,
         context: &mut Context,
     ) -> ResolveResult<'t, 'py> {
-        let content = match variable {
-            Some(content) => content
-                .resolve_string(context)?
-                .map_content(|content| Cow::Owned(content.to_lowercase())),
-            None => "".as_content(),
+        let Some(variable) = variable else {
+            return Ok(Some("".as_content()));
         };
-        Ok(Some(content))
+        let text = variable.to_py(py);
+        let input_val = text.str()?;
+
+        let decimal = DECIMAL.import(py, "decimal", "Decimal")?;
+        let d = match decimal.call1((&input_val,)) {
+            Ok(d) => d,
+            Err(_) => match py.get_type::<PyFloat>().call1((&text,)) {
+                Ok(float) => decimal.call1((float.str()?,))?,
+                Err(error)
+                    if error.is_instance_of::<PyValueError>(py)
+                        || error.is_instance_of::<PyTypeError>(py) =>
+                {
+                    return Ok(Some("".as_content()));
+                }
+                Err(error) => return Err(error.into()),
+            },
+        };
+
+        let arg = match &self.argument {
+            Some(argument) => Some(
+                argument
+                    .resolve(py, template, context, ResolveFailures::Raise)?
+                    .expect("missing argument in context should already have raised"),
+            ),
+            None => None,
+        };
+        let Some(options) = FloatFormatOptions::from_content(arg)? else {
+            return Ok(Some(Content::Py(input_val.into_any())));
+        };
+        let precision = options.precision;
+
+        let int_d = match py.get_type::<PyInt>().call1((&d,)) {
+            Ok(int_d) => int_d,
+            Err(error)
+                if error.is_instance_of::<PyValueError>(py)
+                    || error.is_instance_of::<PyOverflowError>(py) =>
+            {
+                return Ok(Some(Content::Py(input_val.into_any())));
+            }
+            Err(error) => return Err(error.into()),
+        };
+        let has_fraction = int_d.sub(&d)?.is_truthy()?;
+        let (number, decimal_pos) = if !has_fraction && precision <= 0 {
+            (int_d.str()?.to_string(), Some(0))
+        } else {
+            let exp = decimal
+                .call1((1,))?
+                .call_method1("scaleb", (-precision.abs(),))?;
+            let (_, digits, exponent): (u8, Vec<u8>, i64) =
+                d.call_method0("as_tuple")?.extract()?;
+            let units = digits.len() as i64 + if has_fraction { -exponent } else { exponent };
+            let default_prec: i64 = GET_DECIMAL_CONTEXT
+                .import(py, "decimal", "getcontext")?
+                .call0()?
+                .getattr("prec")?
+                .extract()?;
+            let prec = default_prec.max(precision.abs() + units + 1);
+            let decimal_context = DECIMAL_CONTEXT
+                .import(py, "decimal", "Context")?
+                .call((), Some(&[("prec", prec)].into_py_dict(py)?))?;
+            let rounded_d = d.call_method1("quantize", (exp, "ROUND_HALF_UP", decimal_context))?;
+            let (sign, digits, exponent): (u8, Vec<u8>, i64) =
+                rounded_d.call_method0("as_tuple")?.extract()?;
+            let sign = sign == 1 && rounded_d.is_truthy()?;
+            (
+                decimal_string(sign, &digits, exponent),
+                Some(precision.unsigned_abs() as usize),
+            )
+        };
+        let formatted = number_format(
+            py,
+            &number,
+            decimal_pos,
+            options.use_l10n,
+            options.force_grouping,
+        )?;
+        Ok(Some(Content::String(ContentString::HtmlSafe(Cow::Owned(
+            formatted,
+        )))))
     }
 }
 
-impl ResolveFilter for SafeFilter {
+/// Convert a Python number into the string Django's `number_format` would
+/// start from, or `None` if it isn't a number `intcomma` formats directly.
+fn intcomma_number(py: Python<'_>, content: &Content<'_, '_>) -> PyResult<Option<String>> {
+    let value = match content {
+        Content::Int(n) => return Ok(Some(n.to_string())),
+        Content::Bool(b) => return Ok(Some(u8::from(*b).to_string())),
+        content => content.to_py(py),
+    };
+    let decimal = DECIMAL.import(py, "decimal", "Decimal")?;
+    let number = if value.is_instance_of::<PyFloat>() {
+        let number = value.str()?.to_string();
+        match number.to_lowercase().contains('e') {
+            true => decimal.call1((number,))?,
+            false => return Ok(Some(number)),
+        }
+    } else if value.is_instance(decimal)? {
+        value
+    } else {
+        return match py.get_type::<PyInt>().call1((&value,)) {
+            Ok(number) => Ok(Some(number.str()?.to_string())),
+            Err(error)
+                if error.is_instance_of::<PyValueError>(py)
+                    || error.is_instance_of::<PyTypeError>(py) =>
+            {
+                Ok(None)
+            }
+            Err(error) => Err(error),
+        };
+    };
+    Ok(Some(number.call_method1("__format__", ("f",))?.extract()?))
+}
+
+impl ResolveFilter for IntCommaFilter {
     fn resolve<'t, 'py>(
         &self,
         variable: Option<Content<'t, 'py>>,
-        _py: Python<'py>,
-        _template: TemplateString<'t>,
-        _context: &mut Context,
+        py: Python<'py>,
+        template: TemplateString<'t>,
+        context: &mut Context,
     ) -> ResolveResult<'t, 'py> {
-        Ok(Some(Content::String(ContentString::HtmlSafe(
-            match variable {
-                Some(content) => match content {
-                    Content::String(content) => content.into_raw(),
-                    Content::Int(n) => Cow::Owned(n.to_string()),
-                    Content::Float(n) => Cow::Owned(n.to_string()),
-                    Content::Py(object) => {
-                        let content = object.str()?.extract::<String>()?;
-                        Cow::Owned(content)
-                    }
-                    Content::Bool(true) => Cow::Borrowed("True"),
-                    Content::Bool(false) => Cow::Borrowed("False"),
-                },
-                None => Cow::Borrowed(""),
+        let Some(content) = variable else {
+            return Ok(Some("".as_content()));
+        };
+        let use_l10n = match &self.argument {
+            Some(argument) => argument
+                .resolve(py, template, context, ResolveFailures::Raise)?
+                .expect("missing argument in context should already have raised")
+                .to_py(py)
+                .is_truthy()?,
+            None => true,
+        };
+        if use_l10n && let Some(number) = intcomma_number(py, &content)? {
+            let formatted = Cow::Owned(number_format(py, &number, None, true, true)?);
+            return Ok(Some(Content::String(match context.autoescape {
+                false => ContentString::String(formatted),
+                true => ContentString::HtmlUnsafe(formatted),
+            })));
+        }
+        Ok(Some(content.resolve_string(context)?.map_content(
+            |content| {
+                let Some(prefix) = LEADING_INTEGER_RE.find(&content) else {
+                    return content;
+                };
+                let (sign, digits) = match prefix.as_str().strip_prefix('-') {
+                    Some(digits) => ("-", digits),
+                    None => ("", prefix.as_str()),
+                };
+                let grouped = group_digits(digits, &Grouping::Uniform(3), ",");
+                Cow::Owned(format!("{sign}{grouped}{}", &content[prefix.end()..]))
             },
-        ))))
+        )))
     }
 }
 
-fn slugify(content: Cow<str>) -> Cow<str> {
-    let content = content
-        .nfkd()
-        // first decomposing characters, then only keeping
-        // the ascii ones, filtering out diacritics for example.
-        .filter(|c| c.is_ascii())
-        .collect::<String>()
-        .to_lowercase();
-    let content = NON_WORD_RE.replace_all(&content, "");
-    let content = content.trim();
-    let content = WHITESPACE_RE.replace_all(content, "-");
-    Cow::Owned(content.to_string())
+/// The powers of ten `intword` names, with the message for each.
+const INTWORD_MAGNITUDES: [(u32, &str); 11] = [
+    (6, "%(value)s million"),
+    (9, "%(value)s billion"),
+    (12, "%(value)s trillion"),
+    (15, "%(value)s quadrillion"),
+    (18, "%(value)s quintillion"),
+    (21, "%(value)s sextillion"),
+    (24, "%(value)s septillion"),
+    (27, "%(value)s octillion"),
+    (30, "%(value)s nonillion"),
+    (33, "%(value)s decillion"),
+    (100, "%(value)s googol"),
+];
+
+/// Round away from one, so only exactly one is singular.
+fn round_away_from_one(value: f64) -> i64 {
+    let value = value - 1.0;
+    let rounded = if value >= 0.0 {
+        value.ceil()
+    } else {
+        value.floor()
+    };
+    rounded as i64 + 1
 }
 
-impl ResolveFilter for SlugifyFilter {
+impl ResolveFilter for IntWordFilter {
     fn resolve<'t, 'py>(
         &self,
         variable: Option<Content<'t, 'py>>,
         py: Python<'py>,
         _template: TemplateString<'t>,
-        _context: &mut Context,
+        context: &mut Context,
     ) -> ResolveResult<'t, 'py> {
-        let content = match variable {
-            Some(content) => match content {
-                Content::Py(content) => {
-                    let slug = slugify(Cow::Owned(content.str()?.extract::<String>()?));
-                    #[allow(non_snake_case)]
-                    let SafeData = SAFEDATA.import(py, "django.utils.safestring", "SafeData")?;
-                    match content.is_instance(SafeData)? {
-                        true => Content::String(ContentString::HtmlSafe(slug)),
-                        false => Content::String(ContentString::HtmlUnsafe(slug)),
-                    }
-                }
-                // Int and Float requires no slugify, we only need to turn it into a string.
-                Content::Int(content) => content.to_string().into_content(),
-                Content::Float(content) => content.to_string().into_content(),
-                Content::String(content) => content.map_content(slugify),
-                Content::Bool(true) => "true".as_content(),
-                Content::Bool(false) => "false".as_content(),
-            },
-            None => "".as_content(),
+        let Some(content) = variable else {
+            return Ok(Some("".as_content()));
         };
-        Ok(Some(content))
+        let Some(value) = content.to_bigint() else {
+            return Ok(Some(content));
+        };
+        let abs_value = value.magnitude();
+        if *abs_value < BigUint::from(1_000_000u32) {
+            return Ok(Some(Content::Int(value)));
+        }
+        let Some((exponent, message)) = INTWORD_MAGNITUDES
+            .into_iter()
+            .find(|(exponent, _)| *abs_value < BigUint::from(10u32).pow(exponent + 3))
+        else {
+            return Ok(Some(Content::Int(value)));
+        };
+        // Python's true division rounds correctly, even for huge integers.
+        let new_value: f64 = value
+            .into_pyobject(py)?
+            .div(BigInt::from(10).pow(exponent))?
+            .extract()?;
+        let decimal = DECIMAL.import(py, "decimal", "Decimal")?;
+        let rounded = decimal
+            .call1((new_value.to_string(),))?
+            .call_method1("quantize", (decimal.call1(("0.1",))?, "ROUND_HALF_UP"))?;
+        let (sign, digits, exponent): (u8, Vec<u8>, i64) =
+            rounded.call_method0("as_tuple")?.extract()?;
+        let sign = sign == 1 && rounded.is_truthy()?;
+        let number = number_format(
+            py,
+            &decimal_string(sign, &digits, exponent),
+            Some(1),
+            true,
+            false,
+        )?;
+        let count = round_away_from_one(new_value).abs();
+        let ngettext = NGETTEXT.import(py, "django.utils.translation", "ngettext")?;
+        let message: String = ngettext.call1((message, message, count))?.extract()?;
+        let words = Cow::Owned(message.replace("%(value)s", &number));
+        Ok(Some(Content::String(match context.autoescape {
+            false => ContentString::String(words),
+            true => ContentString::HtmlUnsafe(words),
+        })))
     }
 }
 
-impl ResolveFilter for UpperFilter {
+impl ResolveFilter for MakeListFilter {
     fn resolve<'t, 'py>(
         &self,
         variable: Option<Content<'t, 'py>>,
-        _py: Python<'py>,
+        py: Python<'py>,
         _template: TemplateString<'t>,
         context: &mut Context,
     ) -> ResolveResult<'t, 'py> {
         let content = match variable {
-            Some(content) => {
-                let content = content.resolve_string(context)?;
-                content.map_content(|content| Cow::Owned(content.to_uppercase()))
-            }
-            None => "".as_content(),
+            // Use Python's `str` so floats keep their decimal point, like `1.0`.
+            Some(content @ Content::Float(_)) => Cow::Owned(content.to_py(py).str()?.to_string()),
+            Some(content) => content.resolve_string(context)?.into_raw(),
+            None => Cow::Borrowed(""),
         };
-        Ok(Some(content))
+        let list = PyList::new(
+            py,
+            content.chars().map(|c| c.to_string()).collect::<Vec<_>>(),
+        )?;
+        Ok(Some(Content::Py(list.into_any())))
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::filters::{AddSlashesFilter, DefaultFilter, LowerFilter, UpperFilter};
-    use crate::parse::TagElement;
-    use crate::render::Render;
-    use crate::template::django_rusty_templates::{EngineData, Template};
-    use crate::types::{Argument, ArgumentType, Text, Variable};
-
-    use pyo3::types::{PyDict, PyString};
-    static MARK_SAFE: PyOnceLock<Py<PyAny>> = PyOnceLock::new();
-
-    fn mark_safe(py: Python<'_>, string: String) -> PyResult<Py<PyAny>> {
-        let mark_safe = match MARK_SAFE.get(py) {
-            Some(mark_safe) => mark_safe,
-            None => {
-                let py_mark_safe = py.import("django.utils.safestring")?;
-                let py_mark_safe = py_mark_safe.getattr("mark_safe")?;
-                MARK_SAFE.set(py, py_mark_safe.into()).unwrap();
-                MARK_SAFE.get(py).unwrap()
-            }
+impl ResolveFilter for NaturalTimeFilter {
+    fn resolve<'t, 'py>(
+        &self,
+        variable: Option<Content<'t, 'py>>,
+        py: Python<'py>,
+        _template: TemplateString<'t>,
+        context: &mut Context,
+    ) -> ResolveResult<'t, 'py> {
+        let Some(content) = variable else {
+            return Ok(Some("".as_content()));
         };
-        let safe_string = mark_safe.call1(py, (string,))?;
-        Ok(safe_string)
+        let Some(natural) = naturaltime(py, &content.to_py(py))? else {
+            return Ok(Some(content));
+        };
+        let natural = Cow::Owned(natural);
+        Ok(Some(Content::String(match context.autoescape {
+            false => ContentString::String(natural),
+            true => ContentString::HtmlUnsafe(natural),
+        })))
+    }
+}
+
+impl ResolveFilter for OrdinalFilter {
+    fn resolve<'t, 'py>(
+        &self,
+        variable: Option<Content<'t, 'py>>,
+        py: Python<'py>,
+        _template: TemplateString<'t>,
+        _context: &mut Context,
+    ) -> ResolveResult<'t, 'py> {
+        let Some(content) = variable else {
+            return Ok(Some("".as_content()));
+        };
+        let Some(value) = content.to_bigint() else {
+            return Ok(Some(content));
+        };
+        // Like Django, negative numbers have no ordinal suffix.
+        if value.sign() == Sign::Minus {
+            return Ok(Some(value.to_string().into_content()));
+        }
+        let hundreds = value.rem_euclid(&BigInt::from(100)).to_u8();
+        let (context, message) = if matches!(hundreds, Some(11..=13)) {
+            (Cow::Borrowed("ordinal 11, 12, 13"), "{}th")
+        } else {
+            let units = value
+                .rem_euclid(&BigInt::from(10))
+                .to_usize()
+                .expect("The remainder is always a single digit");
+            const SUFFIXES: [&str; 10] = [
+                "{}th", "{}st", "{}nd", "{}rd", "{}th", "{}th", "{}th", "{}th", "{}th", "{}th",
+            ];
+            (Cow::Owned(format!("ordinal {units}")), SUFFIXES[units])
+        };
+        let pgettext = PGETTEXT.import(py, "django.utils.translation", "pgettext")?;
+        let ordinal: String = pgettext.call1((context.as_ref(), message))?.extract()?;
+        let ordinal = ordinal.replace("{}", &value.to_string());
+        // Like Django, the result is safe so translations can use HTML.
+        Ok(Some(Content::String(ContentString::HtmlSafe(Cow::Owned(
+            ordinal,
+        )))))
+    }
+}
+
+/// Like Django, numbers are compared with 1 and anything else with a
+/// length is pluralized by its length. Invalid values render as "".
+impl ResolveFilter for PluralizeFilter {
+    fn resolve<'t, 'py>(
+        &self,
+        variable: Option<Content<'t, 'py>>,
+        py: Python<'py>,
+        template: TemplateString<'t>,
+        context: &mut Context,
+    ) -> ResolveResult<'t, 'py> {
+        let suffixes = match &self.argument {
+            Some(argument) => argument
+                .resolve(py, template, context, ResolveFailures::Raise)?
+                .expect("missing argument in context should already have raised")
+                .resolve_string(context)?
+                .into_raw(),
+            None => Cow::Borrowed("s"),
+        };
+        let mut bits = suffixes.split(',');
+        let (singular, plural) = match (bits.next(), bits.next(), bits.next()) {
+            (Some(plural), None, _) => ("", plural),
+            (Some(singular), Some(plural), None) => (singular, plural),
+            _ => return Ok(Some("".as_content())),
+        };
+        let is_singular = match variable {
+            Some(Content::Int(n)) => n == BigInt::from(1),
+            Some(Content::Float(f)) => f == 1.0,
+            Some(Content::Bool(b)) => b,
+            Some(content) => {
+                let value = content.to_py(py);
+                match py.get_type::<PyFloat>().call1((&value,)) {
+                    Ok(float) => float.extract::<f64>()? == 1.0,
+                    Err(error) if error.is_instance_of::<PyValueError>(py) => {
+                        return Ok(Some("".as_content()));
+                    }
+                    Err(error) if error.is_instance_of::<PyTypeError>(py) => match value.len() {
+                        Ok(length) => length == 1,
+                        Err(error) if error.is_instance_of::<PyTypeError>(py) => {
+                            return Ok(Some("".as_content()));
+                        }
+                        Err(error) => return Err(error.into()),
+                    },
+                    Err(error) => return Err(error.into()),
+                }
+            }
+            // Like Django, a missing variable is not a number.
+            None => return Ok(Some("".as_content())),
+        };
+        let suffix = Cow::Owned(match is_singular {
+            true => singular.to_string(),
+            false => plural.to_string(),
+        });
+        Ok(Some(Content::String(match context.autoescape {
+            false => ContentString::String(suffix),
+            true => ContentString::HtmlUnsafe(suffix),
+        })))
+    }
+}
+
+/// Like Django, the items are escaped when autoescape is on and the result
+/// is safe. Values that can't be joined are returned unchanged.
+impl ResolveFilter for JoinFilter {
+    fn resolve<'t, 'py>(
+        &self,
+        variable: Option<Content<'t, 'py>>,
+        py: Python<'py>,
+        template: TemplateString<'t>,
+        context: &mut Context,
+    ) -> ResolveResult<'t, 'py> {
+        let separator = self
+            .argument
+            .resolve(py, template, context, ResolveFailures::Raise)?
+            .expect("missing argument in context should already have raised")
+            .resolve_string(context)?;
+        let autoescape = context.autoescape;
+        let separator = match autoescape {
+            true => separator.content(),
+            false => separator.into_raw(),
+        };
+        let joined = match variable {
+            None => String::new(),
+            Some(content) => {
+                // Like Python, iterating a string yields plain characters,
+                // so they are escaped even if the string is safe.
+                let join = || -> PyResult<String> {
+                    let mut items = Vec::new();
+                    for item in content.iter(py)? {
+                        let item = item?;
+                        items.push(match autoescape {
+                            true => conditional_escape(&item)?.content().into_owned(),
+                            false => item.cast_into::<PyString>()?.to_str()?.to_string(),
+                        });
+                    }
+                    Ok(items.join(&separator))
+                };
+                match join() {
+                    Ok(joined) => joined,
+                    Err(error) if error.is_instance_of::<PyTypeError>(py) => {
+                        return Ok(Some(content));
+                    }
+                    Err(error) => return Err(error.into()),
+                }
+            }
+        };
+        Ok(Some(Content::String(ContentString::HtmlSafe(Cow::Owned(
+            joined,
+        )))))
+    }
+}
+
+impl ResolveFilter for JsonFilter {
+    fn resolve<'t, 'py>(
+        &self,
+        variable: Option<Content<'t, 'py>>,
+        py: Python<'py>,
+        _template: TemplateString<'t>,
+        _context: &mut Context,
+    ) -> ResolveResult<'t, 'py> {
+        let value = match variable {
+            Some(Content::String(content)) => PyString::new(py, content.as_raw()).into_any(),
+            Some(content) => content.to_py(py),
+            None => PyString::new(py, "").into_any(),
+        };
+        let dumps = JSON_DUMPS.import(py, "json", "dumps")?;
+        let encoder =
+            DJANGO_JSON_ENCODER.import(py, "django.core.serializers.json", "DjangoJSONEncoder")?;
+        let kwargs = [("cls", encoder)].into_py_dict(py)?;
+        let json: String = dumps.call((value,), Some(&kwargs))?.extract()?;
+        Ok(Some(Content::String(ContentString::HtmlSafe(Cow::Owned(
+            escape_json(&json),
+        )))))
+    }
+}
+
+impl ResolveFilter for LengthFilter {
+    fn resolve<'t, 'py>(
+        &self,
+        variable: Option<Content<'t, 'py>>,
+        py: Python<'py>,
+        _template: TemplateString<'t>,
+        _context: &mut Context,
+    ) -> ResolveResult<'t, 'py> {
+        let length = match variable {
+            Some(Content::String(content)) => content.as_raw().chars().count(),
+            // Like Django, treat objects without a length as having length 0.
+            Some(Content::Py(content)) => match content.len() {
+                Ok(length) => length,
+                Err(error)
+                    if error.is_instance_of::<PyTypeError>(py)
+                        || error.is_instance_of::<PyValueError>(py) =>
+                {
+                    0
+                }
+                Err(error) => return Err(error.into()),
+            },
+            Some(Content::Int(_) | Content::Float(_) | Content::Bool(_)) | None => 0,
+        };
+        Ok(Some(Content::Int(length.into())))
+    }
+}
+
+/// Like Django, only the text is escaped: the inserted `<br>` tags are safe.
+impl ResolveFilter for LinebreaksbrFilter {
+    fn resolve<'t, 'py>(
+        &self,
+        variable: Option<Content<'t, 'py>>,
+        _py: Python<'py>,
+        _template: TemplateString<'t>,
+        context: &mut Context,
+    ) -> ResolveResult<'t, 'py> {
+        let content = match variable {
+            Some(content) => content.resolve_string(context)?,
+            None => return Ok(Some("".as_content())),
+        };
+        let escape = context.autoescape && !matches!(content, ContentString::HtmlSafe(_));
+        let text = content.as_raw().replace("\r\n", "\n").replace('\r', "\n");
+        let text = if escape {
+            let mut encoded = String::new();
+            encode_quoted_attribute_to_string(&text, &mut encoded);
+            encoded
+        } else {
+            text
+        };
+        Ok(Some(Content::String(ContentString::HtmlSafe(Cow::Owned(
+            text.replace('\n', "<br>"),
+        )))))
+    }
+}
+
+/// Lowercase `content`, reusing it unchanged if no character would change.
+fn to_lowercase(content: Cow<str>) -> Cow<str> {
+    let unchanged = match content.is_ascii() {
+        true => !content.bytes().any(|b| b.is_ascii_uppercase()),
+        false => content.chars().all(|c| c.to_lowercase().eq([c])),
+    };
+    match unchanged {
+        true => content,
+        false => Cow::Owned(content.to_lowercase()),
+    }
+}
+
+/// Uppercase `content`, reusing it unchanged if no character would change.
+fn to_uppercase(content: Cow<str>) -> Cow<str> {
+    let unchanged = match content.is_ascii() {
+        true => !content.bytes().any(|b| b.is_ascii_lowercase()),
+        false => content.chars().all(|c| c.to_uppercase().eq([c])),
+    };
+    match unchanged {
+        true => content,
+        false => Cow::Owned(content.to_uppercase()),
+    }
+}
+
+impl ResolveFilter for LowerFilter {
+    fn resolve<'t, 'py>(
+        &self,
+        variable: Option<Content<'t, 'py>>,
+        _py: Python<'py>,
+        _template: TemplateString<'t>,
+        context: &mut Context,
+    ) -> ResolveResult<'t, 'py> {
+        let content = match variable {
+            Some(content) => content.resolve_string(context)?.map_content(to_lowercase),
+            None => "".as_content(),
+        };
+        Ok(Some(content))
+    }
+}
+
+/// Like Django, `safe` always produces a string. Filters such as `add` that
+/// come after it see the string and coerce it back to a number if they can.
+impl ResolveFilter for SafeFilter {
+    fn resolve<'t, 'py>(
+        &self,
+        variable: Option<Content<'t, 'py>>,
+        _py: Python<'py>,
+        _template: TemplateString<'t>,
+        _context: &mut Context,
+    ) -> ResolveResult<'t, 'py> {
+        Ok(Some(Content::String(ContentString::HtmlSafe(
+            match variable {
+                Some(content) => match content {
+                    Content::String(content) => content.into_raw(),
+                    Content::Int(n) => Cow::Owned(n.to_string()),
+                    Content::Float(n) => Cow::Owned(n.to_string()),
+                    Content::Py(object) => {
+                        let content = object.str()?.extract::<String>()?;
+                        Cow::Owned(content)
+                    }
+                    Content::Bool(true) => Cow::Borrowed("True"),
+                    Content::Bool(false) => Cow::Borrowed("False"),
+                },
+                None => Cow::Borrowed(""),
+            },
+        ))))
+    }
+}
+
+/// Build a Python `slice` from an argument like `"1:-1"`, the way Django's
+/// `slice` filter does. `None` means Django would fail silently.
+fn parse_slice<'py>(py: Python<'py>, argument: &str) -> PyResult<Option<Bound<'py, PySlice>>> {
+    let int = py.get_type::<PyInt>();
+    let mut bits = Vec::new();
+    for bit in argument.split(':') {
+        bits.push(match bit {
+            "" => py.None().into_bound(py),
+            bit => match int.call1((bit,)) {
+                Ok(bit) => bit,
+                Err(error) if error.is_instance_of::<PyValueError>(py) => return Ok(None),
+                Err(error) => return Err(error),
+            },
+        });
+    }
+    match py.get_type::<PySlice>().call1(PyTuple::new(py, bits)?) {
+        Ok(slice) => Ok(Some(
+            slice
+                .cast_into::<PySlice>()
+                .expect("slice() always returns a slice"),
+        )),
+        // More than three bits.
+        Err(error) if error.is_instance_of::<PyTypeError>(py) => Ok(None),
+        Err(error) => Err(error),
+    }
+}
+
+/// Slice `content` by characters, like Python's `str[slice]`.
+fn slice_chars(content: &str, slice: &Bound<'_, PySlice>) -> PyResult<String> {
+    let chars: Vec<char> = content.chars().collect();
+    let indices = slice.indices(chars.len() as isize)?;
+    Ok((0..indices.slicelength as isize)
+        .map(|i| chars[(indices.start + i * indices.step) as usize])
+        .collect())
+}
+
+impl ResolveFilter for SliceFilter {
+    fn resolve<'t, 'py>(
+        &self,
+        variable: Option<Content<'t, 'py>>,
+        py: Python<'py>,
+        template: TemplateString<'t>,
+        context: &mut Context,
+    ) -> ResolveResult<'t, 'py> {
+        let arg = self
+            .argument
+            .resolve(py, template, context, ResolveFailures::Raise)?
+            .expect("missing argument in context should already have raised")
+            .resolve_string(context)?;
+        let Some(slice) = parse_slice(py, arg.as_raw())? else {
+            return Ok(Some(variable.unwrap_or_else(|| "".as_content())));
+        };
+        let content = match variable {
+            None => return Ok(Some("".as_content())),
+            // Strings are sliced natively below, keeping their safety.
+            Some(Content::Py(value)) if !value.is_instance_of::<PyString>() => {
+                return match value.get_item(&slice) {
+                    Ok(sliced) => Ok(Some(Content::Py(sliced))),
+                    // Like Django, fail silently.
+                    Err(error)
+                        if error.is_instance_of::<PyTypeError>(py)
+                            || error.is_instance_of::<PyValueError>(py)
+                            || error.is_instance_of::<PyKeyError>(py) =>
+                    {
+                        Ok(Some(Content::Py(value)))
+                    }
+                    Err(error) => Err(error.into()),
+                };
+            }
+            // Numbers can't be sliced, so Django returns them unchanged.
+            Some(content @ (Content::Int(_) | Content::Float(_) | Content::Bool(_))) => {
+                return Ok(Some(content));
+            }
+            Some(content) => content.resolve_string(context)?,
+        };
+        match slice_chars(content.as_raw(), &slice) {
+            Ok(sliced) => Ok(Some(content.map_content(|_| Cow::Owned(sliced)))),
+            // A step of zero.
+            Err(error) if error.is_instance_of::<PyValueError>(py) => {
+                Ok(Some(Content::String(content)))
+            }
+            Err(error) => Err(error.into()),
+        }
+    }
+}
+
+/// Like Django's `slugify`. With `allow_unicode`, non-ASCII word characters
+/// are kept after NFKC normalization instead of being stripped to ASCII.
+fn slugify(content: Cow<str>, allow_unicode: bool) -> Cow<str> {
+    let content = if allow_unicode {
+        content.nfkc().collect::<String>()
+    } else {
+        content
+            .nfkd()
+            // first decomposing characters, then only keeping
+            // the ascii ones, filtering out diacritics for example.
+            .filter(|c| c.is_ascii())
+            .collect::<String>()
+    }
+    .to_lowercase();
+    let content = NON_WORD_RE.replace_all(&content, "");
+    let content = content.trim();
+    let content = WHITESPACE_RE.replace_all(content, "-");
+    Cow::Owned(content.to_string())
+}
+
+fn resolve_slugify<'t, 'py>(
+    variable: Option<Content<'t, 'py>>,
+    context: &Context,
+    allow_unicode: bool,
+) -> ResolveResult<'t, 'py> {
+    let content = match variable {
+        Some(content) => match content {
+            // Int and Float requires no slugify, we only need to turn it into a string.
+            Content::Int(content) => content.to_string().into_content(),
+            Content::Float(content) => content.to_string().into_content(),
+            Content::Bool(true) => "true".as_content(),
+            Content::Bool(false) => "false".as_content(),
+            content => content
+                .resolve_string(context)?
+                .map_content(|content| slugify(content, allow_unicode)),
+        },
+        None => "".as_content(),
+    };
+    Ok(Some(content))
+}
+
+impl ResolveFilter for SlugifyFilter {
+    fn resolve<'t, 'py>(
+        &self,
+        variable: Option<Content<'t, 'py>>,
+        _py: Python<'py>,
+        _template: TemplateString<'t>,
+        context: &mut Context,
+    ) -> ResolveResult<'t, 'py> {
+        resolve_slugify(variable, context, false)
+    }
+}
+
+impl ResolveFilter for UnicodeSlugifyFilter {
+    fn resolve<'t, 'py>(
+        &self,
+        variable: Option<Content<'t, 'py>>,
+        _py: Python<'py>,
+        _template: TemplateString<'t>,
+        context: &mut Context,
+    ) -> ResolveResult<'t, 'py> {
+        resolve_slugify(variable, context, true)
+    }
+}
+
+/// Like Python's `str.isspace`, which also counts the ASCII information
+/// separators as whitespace.
+fn is_python_whitespace(c: char) -> bool {
+    c.is_whitespace() || ('\x1c'..='\x1f').contains(&c)
+}
+
+/// Like Django's `Truncator.words`, which joins the kept words with single
+/// spaces and appends `" …"` if any words were dropped.
+fn truncate_words(content: Cow<str>, length: usize) -> Cow<str> {
+    if length == 0 {
+        return Cow::Borrowed("");
+    }
+    let mut words = content
+        .split(is_python_whitespace)
+        .filter(|word| !word.is_empty());
+    let mut truncated = words.by_ref().take(length).collect::<Vec<_>>().join(" ");
+    if words.next().is_some() {
+        truncated.push_str(" …");
+    }
+    Cow::Owned(truncated)
+}
+
+impl ResolveFilter for TruncateWordsFilter {
+    fn resolve<'t, 'py>(
+        &self,
+        variable: Option<Content<'t, 'py>>,
+        py: Python<'py>,
+        template: TemplateString<'t>,
+        context: &mut Context,
+    ) -> ResolveResult<'t, 'py> {
+        let arg = self
+            .argument
+            .resolve(py, template, context, ResolveFailures::Raise)?
+            .expect("missing argument in context should already have raised");
+        let Some(content) = variable else {
+            return Ok(Some("".as_content()));
+        };
+        let content = content.resolve_string(context)?;
+        let length = match resolve_usize_argument(arg, self.argument.at, Overflow::Clamp) {
+            Ok(length) => length,
+            // Django returns the value unchanged if the argument isn't an integer.
+            Err(RenderError::InvalidArgumentInteger { .. }) => {
+                return Ok(Some(Content::String(content)));
+            }
+            Err(err) => return Err(err.into()),
+        };
+        Ok(Some(
+            content.map_content(|content| truncate_words(content, length)),
+        ))
+    }
+}
+
+impl ResolveFilter for UpperFilter {
+    fn resolve<'t, 'py>(
+        &self,
+        variable: Option<Content<'t, 'py>>,
+        _py: Python<'py>,
+        _template: TemplateString<'t>,
+        context: &mut Context,
+    ) -> ResolveResult<'t, 'py> {
+        let content = match variable {
+            // Unlike most string filters, Django doesn't mark `upper` as
+            // `is_safe`, because uppercasing can break HTML entities.
+            Some(content) => match content.resolve_string(context)? {
+                ContentString::HtmlSafe(content) => {
+                    Content::String(ContentString::HtmlUnsafe(to_uppercase(content)))
+                }
+                content => content.map_content(to_uppercase),
+            },
+            None => "".as_content(),
+        };
+        Ok(Some(content))
+    }
+}
+
+fn urlize<'t, 'py>(
+    content: Content<'t, 'py>,
+    py: Python<'py>,
+    urlizer: Urlizer,
+) -> ResolveResult<'t, 'py> {
+    let (text, safe_input) = match content {
+        Content::Py(content) => {
+            #[allow(non_snake_case)]
+            let SafeData = SAFEDATA.import(py, "django.utils.safestring", "SafeData")?;
+            let safe_input = content.is_instance(SafeData)?;
+            (Cow::Owned(content.str()?.extract::<String>()?), safe_input)
+        }
+        Content::String(ContentString::HtmlSafe(content)) => (content, true),
+        Content::String(content) => (content.into_raw(), false),
+        Content::Int(content) => (Cow::Owned(content.to_string()), false),
+        Content::Float(content) => (Cow::Owned(content.to_string()), false),
+        Content::Bool(true) => (Cow::Borrowed("True"), false),
+        Content::Bool(false) => (Cow::Borrowed("False"), false),
+    };
+    let urlized = urlizer.urlize(py, &text, safe_input)?;
+    Ok(Some(Content::String(ContentString::HtmlSafe(Cow::Owned(
+        urlized,
+    )))))
+}
+
+impl ResolveFilter for UrlizeFilter {
+    fn resolve<'t, 'py>(
+        &self,
+        variable: Option<Content<'t, 'py>>,
+        py: Python<'py>,
+        _template: TemplateString<'t>,
+        context: &mut Context,
+    ) -> ResolveResult<'t, 'py> {
+        let urlizer = Urlizer {
+            trim_url_limit: None,
+            nofollow: true,
+            autoescape: context.autoescape,
+        };
+        match variable {
+            Some(content) => urlize(content, py, urlizer),
+            None => Ok(Some("".as_content())),
+        }
+    }
+}
+
+impl ResolveFilter for UrlizeTruncFilter {
+    fn resolve<'t, 'py>(
+        &self,
+        variable: Option<Content<'t, 'py>>,
+        py: Python<'py>,
+        template: TemplateString<'t>,
+        context: &mut Context,
+    ) -> ResolveResult<'t, 'py> {
+        let arg = self
+            .argument
+            .resolve(py, template, context, ResolveFailures::Raise)?
+            .expect("missing argument in context should already have raised");
+        // Django only compares the limit against the URL length, so very
+        // large limits don't overflow.
+        let limit = resolve_usize_argument(arg, self.argument.at, Overflow::Clamp)?;
+        let urlizer = Urlizer {
+            trim_url_limit: Some(limit),
+            nofollow: true,
+            autoescape: context.autoescape,
+        };
+        match variable {
+            Some(content) => urlize(content, py, urlizer),
+            None => Ok(Some("".as_content())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::filters::{AddSlashesFilter, DefaultFilter, LengthFilter, LowerFilter, UpperFilter};
+    use crate::parse::{Parser, TagElement, TokenTree};
+    use crate::render::Render;
+    use crate::template::django_rusty_templates::{EngineData, Template};
+    use crate::types::{Argument, ArgumentType, Text, Variable};
+
+    use pyo3::types::{PyDict, PyList, PyString};
+    static MARK_SAFE: PyOnceLock<Py<PyAny>> = PyOnceLock::new();
+
+    fn mark_safe(py: Python<'_>, string: String) -> PyResult<Py<PyAny>> {
+        let mark_safe = match MARK_SAFE.get(py) {
+            Some(mark_safe) => mark_safe,
+            None => {
+                let py_mark_safe = py.import("django.utils.safestring")?;
+                let py_mark_safe = py_mark_safe.getattr("mark_safe")?;
+                MARK_SAFE.set(py, py_mark_safe.into()).unwrap();
+                MARK_SAFE.get(py).unwrap()
+            }
+        };
+        let safe_string = mark_safe.call1(py, (string,))?;
+        Ok(safe_string)
     }
 
     use std::collections::HashMap;
@@ -450,22 +1639,216 @@ mod tests {
         Python::initialize();
 
         Python::attach(|py| {
-            let name = PyString::new(py, "Lily").into_any();
-            let context = HashMap::from([("name".to_string(), name.unbind())]);
-            let mut context = Context::new(context, None, false);
-            let template = TemplateString("{{ name|default:'Bryony' }}");
-            let variable = Variable::new((3, 4));
-            let filter = Filter {
-                at: (8, 7),
-                left: TagElement::Variable(variable),
-                filter: FilterType::Default(DefaultFilter::new(Argument {
-                    at: (16, 8),
-                    argument_type: ArgumentType::Text(Text::new((17, 6))),
-                })),
-            };
+            let name = PyString::new(py, "Lily").into_any();
+            let context = HashMap::from([("name".to_string(), name.unbind())]);
+            let mut context = Context::new(context, None, false);
+            let template = TemplateString("{{ name|default:'Bryony' }}");
+            let variable = Variable::new((3, 4));
+            let filter = Filter {
+                at: (8, 7),
+                left: TagElement::Variable(variable),
+                filter: FilterType::Default(DefaultFilter::new(Argument {
+                    at: (16, 8),
+                    argument_type: ArgumentType::Text(Text::new((17, 6))),
+                })),
+            };
+
+            let rendered = filter.render(py, template, &mut context).unwrap();
+            assert_eq!(rendered, "Lily");
+        })
+    }
+
+    #[test]
+    fn test_render_filter_dictsort_index() {
+        Python::initialize();
+
+        Python::attach(|py| {
+            let engine = EngineData::empty();
+            let context = PyDict::new(py);
+            let pairs = py.eval(c"[(2, 'b'), (1, 'c'), (3, 'a')]", None, None);
+            context.set_item("pairs", pairs.unwrap()).unwrap();
+            let people = py.eval(c"[{'name': 'b'}, {'name': 'a'}]", None, None);
+            context.set_item("people", people.unwrap()).unwrap();
+
+            for (template_string, expected) in [
+                ("{{ pairs|dictsort:0 }}", "[(1, 'c'), (2, 'b'), (3, 'a')]"),
+                ("{{ pairs|dictsort:1 }}", "[(3, 'a'), (2, 'b'), (1, 'c')]"),
+                ("{{ pairs|dictsort:'0' }}", ""),
+                (
+                    "{{ people|dictsort:'name' }}",
+                    "[{'name': 'a'}, {'name': 'b'}]",
+                ),
+                ("{{ people|dictsort:0 }}", "KeyError"),
+                ("{{ people|dictsort:'_name' }}", ""),
+            ] {
+                let template =
+                    Template::new_from_string(py, template_string.to_string(), &engine).unwrap();
+                let result = match template.render(py, Some(context.clone()), None) {
+                    Ok(result) => result,
+                    Err(error) => error.get_type(py).name().unwrap().to_string(),
+                };
+                assert_eq!(result, expected, "{template_string}");
+            }
+        })
+    }
+
+    #[test]
+    fn test_render_filter_dictsort_partial_order() {
+        Python::initialize();
+
+        Python::attach(|py| {
+            let engine = EngineData::empty();
+            let context = PyDict::new(py);
+            py.run(
+                c"nan = float('nan')
+floats = [(nan, 'a'), (2.0, 'b'), (nan, 'c'), (1.0, 'd'), (0.5, 'e')]
+sets = [({1, 2}, 'a'), ({3}, 'b'), ({1}, 'c'), (set(), 'd')]
+expected = '|'.join(str(sorted(v, key=lambda x: x[0])) for v in [floats, sets])",
+                None,
+                Some(&context),
+            )
+            .unwrap();
+            let expected: String = context
+                .get_item("expected")
+                .unwrap()
+                .unwrap()
+                .extract()
+                .unwrap();
+
+            let template_string = "{{ floats|dictsort:0 }}|{{ sets|dictsort:0 }}".to_string();
+            let template = Template::new_from_string(py, template_string, &engine).unwrap();
+            assert_eq!(template.render(py, Some(context), None).unwrap(), expected);
+        })
+    }
+
+    #[test]
+    fn test_render_filter_slice() {
+        Python::initialize();
+
+        Python::attach(|py| {
+            let engine = EngineData::empty();
+            let template_string = "{{ word|slice:':3' }}|{{ word|slice:'::-1'|upper }}|\
+                {{ items|slice:':3' }}|{% for x in items|slice:'::-1' %}{{ x }}{% endfor %}|\
+                {{ word|slice:'::0' }}|{{ word|slice:'x' }}|{{ 5|slice:'1' }}"
+                .to_string();
+            let template = Template::new_from_string(py, template_string, &engine).unwrap();
+            let context = PyDict::new(py);
+            context.set_item("word", "héllo").unwrap();
+            context
+                .set_item("items", PyList::new(py, [1, 2, 3, 4]).unwrap())
+                .unwrap();
+
+            assert_eq!(
+                template.render(py, Some(context), None).unwrap(),
+                "hél|OLLÉH|[1, 2, 3]|4321|héllo|héllo|5"
+            );
+        })
+    }
+
+    #[test]
+    fn test_render_filter_linebreaksbr() {
+        Python::initialize();
+
+        Python::attach(|py| {
+            let engine = EngineData::empty();
+            let template_string = "{% autoescape on %}{{ text|linebreaksbr }}|\
+                {{ text|safe|linebreaksbr }}|{{ missing|linebreaksbr }}{% endautoescape %}|\
+                {{ text|linebreaksbr }}"
+                .to_string();
+            let template = Template::new_from_string(py, template_string, &engine).unwrap();
+            let context = PyDict::new(py);
+            context.set_item("text", "a < b\r\nc &amp; d\re").unwrap();
+
+            assert_eq!(
+                template.render(py, Some(context), None).unwrap(),
+                "a &lt; b<br>c &amp;amp; d<br>e|a < b<br>c &amp; d<br>e||a < b<br>c &amp; d<br>e"
+            );
+        })
+    }
+
+    #[test]
+    fn test_render_filter_make_list_join() {
+        Python::initialize();
+
+        Python::attach(|py| {
+            let engine = EngineData::empty();
+            let template_string = "{{ 123|make_list|join:\"-\" }}|{{ 1.5|make_list|join:\"-\" }}|\
+                {{ 1.0|make_list|join:\"-\" }}|{{ 123|make_list }}|{{ missing|make_list }}|\
+                {% for c in number|make_list %}{{ c }},{% endfor %}|\
+                {{ items|join:\", \" }}|{{ 5|join:\"-\" }}|{{ missing|join:\"-\" }}|\
+                {% autoescape on %}{{ items|join:\"<br>\" }}|{{ items|join:sep }}|\
+                {{ text|join:\"\" }}|{{ text|safe|join:\"\" }}{% endautoescape %}"
+                .to_string();
+            let template = Template::new_from_string(py, template_string, &engine).unwrap();
+            let context = PyDict::new(py);
+            context.set_item("items", vec!["a", "<b>"]).unwrap();
+            context.set_item("sep", "&").unwrap();
+            context.set_item("text", "&").unwrap();
+            context.set_item("number", 12).unwrap();
+
+            assert_eq!(
+                template.render(py, Some(context), None).unwrap(),
+                "1-2-3|1-.-5|1-.-0|['1', '2', '3']|[]|1,2,|a, <b>|5||\
+                a<br>&lt;b&gt;|a&amp;&lt;b&gt;|&amp;|&amp;"
+            );
+        })
+    }
+
+    #[test]
+    fn test_render_filter_pluralize() {
+        Python::initialize();
+
+        Python::attach(|py| {
+            let engine = EngineData::empty();
+            let template_string = "{{ 1|pluralize }}|{{ 2|pluralize }}|{{ 0|pluralize }}|\
+                {{ one|pluralize }}|{{ two|pluralize }}|{{ count|pluralize:\"y,ies\" }}|\
+                {{ 1.0|pluralize:\"es\" }}|{{ text_one|pluralize }}|{{ text|pluralize }}|\
+                {{ 2|pluralize:\"a,b,c\" }}|{{ missing|pluralize }}|{{ none|pluralize }}"
+                .to_string();
+            let template = Template::new_from_string(py, template_string, &engine).unwrap();
+            let context = PyDict::new(py);
+            context.set_item("one", vec![1]).unwrap();
+            context.set_item("two", vec![1, 2]).unwrap();
+            context.set_item("count", 3).unwrap();
+            context.set_item("text_one", " 1 ").unwrap();
+            context.set_item("text", "a").unwrap();
+            context.set_item("none", py.None()).unwrap();
+
+            assert_eq!(
+                template.render(py, Some(context), None).unwrap(),
+                "|s|s||s|ies||||||"
+            );
+        })
+    }
+
+    #[test]
+    fn test_render_filter_justify_variable_width() {
+        Python::initialize();
+
+        Python::attach(|py| {
+            let engine = EngineData::empty();
+            let template_string =
+                "[{{ label|ljust:width }}][{{ label|rjust:width }}][{{ label|center:width }}]\
+                [{{ missing|ljust:3 }}][{{ label|rjust:2 }}]"
+                    .to_string();
+            let template = Template::new_from_string(py, template_string, &engine).unwrap();
+            let context = PyDict::new(py);
+            context.set_item("label", "héllo").unwrap();
+            context.set_item("width", 9).unwrap();
 
-            let rendered = filter.render(py, template, &mut context).unwrap();
-            assert_eq!(rendered, "Lily");
+            assert_eq!(
+                template.render(py, Some(context), None).unwrap(),
+                "[héllo    ][    héllo][  héllo  ][   ][héllo]"
+            );
+
+            let template_string = "{{ label|ljust:width }}".to_string();
+            let template = Template::new_from_string(py, template_string, &engine).unwrap();
+            let context = PyDict::new(py);
+            context.set_item("label", "hello").unwrap();
+            context.set_item("width", "wide").unwrap();
+
+            let error = template.render(py, Some(context), None).unwrap_err();
+            assert!(error.is_instance_of::<PyValueError>(py));
         })
     }
 
@@ -548,6 +1931,40 @@ mod tests {
         })
     }
 
+    #[test]
+    fn test_render_filter_unicode_slugify() {
+        Python::initialize();
+
+        Python::attach(|py| {
+            let engine = EngineData::empty();
+            let template_string = "{{ var|slugify }}|{{ var|unicode_slugify }}|\
+                {{ ligature|unicode_slugify }}|{{ number|unicode_slugify }}"
+                .to_string();
+            let context = PyDict::new(py);
+            context.set_item("var", "Héllo Wörld!").unwrap();
+            context.set_item("ligature", "e\u{301}\u{fb01}le").unwrap();
+            context.set_item("number", 12).unwrap();
+            let template = Template::new_from_string(py, template_string, &engine).unwrap();
+            let result = template.render(py, Some(context), None).unwrap();
+
+            assert_eq!(result, "hello-world|héllo-wörld|éfile|12");
+        })
+    }
+
+    #[test]
+    fn test_render_filter_unicode_slugify_invalid() {
+        Python::initialize();
+
+        Python::attach(|py| {
+            let engine = EngineData::empty();
+            let template_string = "{{ var|unicode_slugify:True }}".to_string();
+            let error = Template::new_from_string(py, template_string, &engine).unwrap_err();
+
+            let error_string = format!("{error}");
+            assert!(error_string.contains("unicode_slugify filter does not take an argument"));
+        })
+    }
+
     #[test]
     fn test_render_filter_slugify_float() {
         Python::initialize();
@@ -625,6 +2042,26 @@ mod tests {
         })
     }
 
+    #[test]
+    fn test_render_filter_truncatewords() {
+        Python::initialize();
+
+        Python::attach(|py| {
+            let engine = EngineData::empty();
+            let template_string =
+                "{{ var|truncatewords:4 }}|{{ var|truncatewords:10 }}|{{ var|truncatewords:0 }}"
+                    .to_string();
+            let context = PyDict::new(py);
+            context
+                .set_item("var", "Joel  is a slug,\n really.")
+                .unwrap();
+            let template = Template::new_from_string(py, template_string, &engine).unwrap();
+            let result = template.render(py, Some(context), None).unwrap();
+
+            assert_eq!(result, "Joel is a slug, …|Joel is a slug, really.|");
+        })
+    }
+
     #[test]
     fn test_render_filter_slugify_invalid() {
         Python::initialize();
@@ -801,6 +2238,141 @@ mod tests {
         })
     }
 
+    #[test]
+    fn test_render_filter_default_missing_argument() {
+        Python::initialize();
+
+        Python::attach(|py| {
+            let name = PyString::new(py, "Lily").into_any();
+            let context = HashMap::from([("name".to_string(), name.unbind())]);
+            let mut context = Context::new(context, None, false);
+            let template = TemplateString("{{ name|default:missing }}");
+            let filter = Filter {
+                at: (8, 7),
+                left: TagElement::Variable(Variable::new((3, 4))),
+                filter: FilterType::Default(DefaultFilter::new(Argument {
+                    at: (16, 7),
+                    argument_type: ArgumentType::Variable(Variable::new((16, 7))),
+                })),
+            };
+
+            let error = filter.render(py, template, &mut context).unwrap_err();
+            let Ok(RenderError::ArgumentDoesNotExist { key, key_at, .. }) =
+                error.try_into_render_error()
+            else {
+                panic!("expected a missing argument error");
+            };
+            assert_eq!(key, "missing");
+            assert_eq!(key_at, (16, 7).into());
+        })
+    }
+
+    #[test]
+    fn test_render_filter_default_falsy() {
+        Python::initialize();
+
+        Python::attach(|py| {
+            let engine = EngineData::empty();
+            let template_string = "{{ empty|default:'x' }}|{{ zero|default:'x' }}|\
+                {{ list|default:'x' }}|{{ false|default:'x' }}|{{ full|default:'x' }}"
+                .to_string();
+            let template = Template::new_from_string(py, template_string, &engine).unwrap();
+            let context = PyDict::new(py);
+            context.set_item("empty", "").unwrap();
+            context.set_item("zero", 0).unwrap();
+            context.set_item("list", PyList::empty(py)).unwrap();
+            context.set_item("false", false).unwrap();
+            context
+                .set_item("full", PyList::new(py, [1]).unwrap())
+                .unwrap();
+
+            assert_eq!(
+                template.render(py, Some(context), None).unwrap(),
+                "x|x|x|x|[1]"
+            );
+        })
+    }
+
+    #[test]
+    fn test_render_filter_default_bool_error() {
+        Python::initialize();
+
+        Python::attach(|py| {
+            let engine = EngineData::empty();
+            let template_string = "{{ value|default:'x' }}".to_string();
+            let template = Template::new_from_string(py, template_string, &engine).unwrap();
+            let locals = PyDict::new(py);
+            py.run(
+                cr#"
+class Broken:
+    def __bool__(self):
+        raise ValueError("no truth")
+
+value = Broken()
+"#,
+                None,
+                Some(&locals),
+            )
+            .unwrap();
+            let context = PyDict::new(py);
+            context
+                .set_item("value", locals.get_item("value").unwrap().unwrap())
+                .unwrap();
+
+            let error = template.render(py, Some(context), None).unwrap_err();
+            assert_eq!(error.to_string(), "ValueError: no truth");
+        })
+    }
+
+    #[test]
+    fn test_render_html_method() {
+        Python::initialize();
+
+        Python::attach(|py| {
+            let engine = EngineData::empty();
+            let template_string = "{% autoescape on %}{{ widget }}|{{ widget|escape }}|\
+                {{ html }}|{{ html|escape }}{% endautoescape %}"
+                .to_string();
+            let template = Template::new_from_string(py, template_string, &engine).unwrap();
+            let locals = PyDict::new(py);
+            py.run(
+                cr#"
+class SafeText(str):
+    def __html__(self):
+        return self
+
+class Widget:
+    def __html__(self):
+        return "<input>"
+
+    def __str__(self):
+        return "<widget>"
+
+class Html(Widget):
+    def __str__(self):
+        return SafeText("<b>&amp;</b>")
+
+widget = Widget()
+html = Html()
+"#,
+                Some(&locals),
+                None,
+            )
+            .unwrap();
+            let context = PyDict::new(py);
+            for name in ["widget", "html"] {
+                context
+                    .set_item(name, locals.get_item(name).unwrap().unwrap())
+                    .unwrap();
+            }
+
+            assert_eq!(
+                template.render(py, Some(context), None).unwrap(),
+                "&lt;widget&gt;|<input>|<b>&amp;</b>|<input>"
+            );
+        })
+    }
+
     #[test]
     fn test_render_filter_default_integer() {
         Python::initialize();
@@ -871,6 +2443,240 @@ mod tests {
         })
     }
 
+    #[test]
+    fn test_render_filter_default_escaping() {
+        Python::initialize();
+
+        Python::attach(|py| {
+            let engine = EngineData::empty();
+            let none = py.None();
+            // (template, value, autoescaped output, output without autoescape)
+            let cases: &[(&str, Option<&Bound<'_, PyAny>>, &str, &str)] = &[
+                (
+                    "{{ value|default:'<b>' }}",
+                    Some(&PyString::new(py, "<i>")),
+                    "&lt;i&gt;",
+                    "<i>",
+                ),
+                (
+                    "{{ value|safe|default:'<b>' }}",
+                    Some(&PyString::new(py, "<i>")),
+                    "<i>",
+                    "<i>",
+                ),
+                ("{{ value|default:'<b>' }}", None, "<b>", "<b>"),
+                (
+                    "{{ value|default:'<b>' }}",
+                    Some(none.bind(py)),
+                    "<b>",
+                    "<b>",
+                ),
+                (
+                    "{{ value|default:'<b>' }}",
+                    Some(&PyString::new(py, "")),
+                    "<b>",
+                    "<b>",
+                ),
+                ("{{ value|default:fallback }}", None, "&lt;b&gt;", "<b>"),
+                (
+                    "{{ value|default_if_none:'<b>' }}",
+                    Some(&PyString::new(py, "<i>")),
+                    "&lt;i&gt;",
+                    "<i>",
+                ),
+                (
+                    "{{ value|safe|default_if_none:'<b>' }}",
+                    Some(&PyString::new(py, "<i>")),
+                    "<i>",
+                    "<i>",
+                ),
+                ("{{ value|default_if_none:'<b>' }}", None, "", ""),
+                (
+                    "{{ value|default_if_none:'<b>' }}",
+                    Some(none.bind(py)),
+                    "<b>",
+                    "<b>",
+                ),
+                (
+                    "{{ value|default_if_none:'<b>' }}",
+                    Some(&PyString::new(py, "")),
+                    "",
+                    "",
+                ),
+                (
+                    "{{ value|default_if_none:fallback }}",
+                    Some(none.bind(py)),
+                    "&lt;b&gt;",
+                    "<b>",
+                ),
+            ];
+            for (template_string, value, escaped, unescaped) in cases {
+                let mut template =
+                    Template::new_from_string(py, template_string.to_string(), &engine).unwrap();
+                for (autoescape, expected) in [(true, escaped), (false, unescaped)] {
+                    template.autoescape = autoescape;
+                    let context = PyDict::new(py);
+                    context.set_item("fallback", "<b>").unwrap();
+                    if let Some(value) = value {
+                        context.set_item("value", value).unwrap();
+                    }
+                    let rendered = template.render(py, Some(context), None).unwrap();
+                    assert_eq!(
+                        &rendered, expected,
+                        "{template_string} with {value:?} and autoescape {autoescape}"
+                    );
+                }
+            }
+        })
+    }
+
+    /// Resolve `{{ value|<filters> }}` with autoescape on and return the
+    /// output and whether it is marked safe.
+    fn resolve_safety(py: Python<'_>, value: &str, filters: &str) -> (String, bool) {
+        let source = format!("{{{{ value|{filters} }}}}");
+        let template = TemplateString(&source);
+        let libraries = HashMap::new();
+        let nodes = Parser::new(py, template, &libraries).parse().unwrap();
+        let [TokenTree::Filter(filter)] = nodes.as_slice() else {
+            panic!("Expected a single filter");
+        };
+        let value = PyString::new(py, value).into_any().unbind();
+        let context = HashMap::from([("value".to_string(), value)]);
+        let mut context = Context::new(context, None, true);
+        let content = filter
+            .resolve(py, template, &mut context, ResolveFailures::Raise)
+            .unwrap()
+            .unwrap();
+        match content {
+            Content::String(ContentString::HtmlSafe(content)) => (content.into_owned(), true),
+            Content::String(ContentString::HtmlUnsafe(content)) => (content.into_owned(), false),
+            content => panic!("Expected an autoescaped string, got {content:?}"),
+        }
+    }
+
+    #[test]
+    fn test_string_filter_safety() {
+        Python::initialize();
+
+        Python::attach(|py| {
+            // Matches the `is_safe` flag of each filter in Django.
+            let cases = [
+                ("lower", "<B>", "<b>", true),
+                ("upper", "<b>", "<B>", false),
+                ("capfirst", "<b>", "<b>", true),
+                ("slugify", "<b>A b</b>", "ba-bb", true),
+                ("addslashes", "<b>'</b>", "<b>\\'</b>", true),
+                ("center:5", "<b>", " <b> ", true),
+            ];
+            for (filter, value, expected, safe) in cases {
+                let (content, is_safe) = resolve_safety(py, value, &format!("safe|{filter}"));
+                assert_eq!(content, expected, "{filter}");
+                assert_eq!(is_safe, safe, "{filter} with safe input");
+
+                let (content, is_safe) = resolve_safety(py, value, filter);
+                assert_eq!(content, expected, "{filter}");
+                assert!(!is_safe, "{filter} with unsafe input");
+            }
+        })
+    }
+
+    #[test]
+    fn test_render_filter_escape_safe_boundary() {
+        Python::initialize();
+
+        Python::attach(|py| {
+            let engine = EngineData::empty();
+            let template_string = "{% autoescape on %}{{ html|escape|safe }}|\
+                {{ html|safe|escape }}|{{ html|escape|escape }}|{{ html|escape|safe|escape }}\
+                {% endautoescape %}|{{ html|escape|escape }}|{{ html|safe|escape }}"
+                .to_string();
+            let template = Template::new_from_string(py, template_string, &engine).unwrap();
+            let context = PyDict::new(py);
+            context.set_item("html", "<b>&amp;</b>").unwrap();
+
+            assert_eq!(
+                template.render(py, Some(context), None).unwrap(),
+                "&lt;b&gt;&amp;amp;&lt;/b&gt;|<b>&amp;</b>|&lt;b&gt;&amp;amp;&lt;/b&gt;|\
+                &lt;b&gt;&amp;amp;&lt;/b&gt;|&lt;b&gt;&amp;amp;&lt;/b&gt;|<b>&amp;</b>"
+            );
+        })
+    }
+
+    #[test]
+    fn test_escape_json() {
+        assert_eq!(
+            escape_json(r#"{"a": "</script><b>&amp;"}"#),
+            r#"{"a": "\u003C/script\u003E\u003Cb\u003E\u0026amp;"}"#
+        );
+    }
+
+    #[test]
+    fn test_render_filter_length() {
+        Python::initialize();
+
+        Python::attach(|py| {
+            let items = PyList::new(py, [1, 2, 3]).unwrap().into_any();
+            let context = HashMap::from([("items".to_string(), items.unbind())]);
+            let mut context = Context::new(context, None, false);
+            let template = TemplateString("{{ items|length }}");
+            let variable = Variable::new((3, 5));
+            let filter = Filter {
+                at: (9, 6),
+                left: TagElement::Variable(variable),
+                filter: FilterType::Length(LengthFilter),
+            };
+
+            let rendered = filter.render(py, template, &mut context).unwrap();
+            assert_eq!(rendered, "3");
+        })
+    }
+
+    #[test]
+    fn test_render_filter_length_no_len() {
+        Python::initialize();
+
+        Python::attach(|py| {
+            let items = 5i64.into_pyobject(py).unwrap().into_any();
+            let context = HashMap::from([("items".to_string(), items.unbind())]);
+            let mut context = Context::new(context, None, false);
+            let template = TemplateString("{{ items|length }}");
+            let variable = Variable::new((3, 5));
+            let filter = Filter {
+                at: (9, 6),
+                left: TagElement::Variable(variable),
+                filter: FilterType::Length(LengthFilter),
+            };
+
+            let rendered = filter.render(py, template, &mut context).unwrap();
+            assert_eq!(rendered, "0");
+        })
+    }
+
+    #[test]
+    fn test_render_filter_length_after_default() {
+        Python::initialize();
+
+        Python::attach(|py| {
+            let engine = EngineData::empty();
+            let template_string = "{{ none|default_if_none:\"abc\"|length }}|\
+                {{ items|default_if_none:\"abc\"|length }}|{{ none|default:\"abc\"|length }}|\
+                {{ empty|default:\"abc\"|length }}|{{ missing|default_if_none:\"abc\"|length }}|\
+                {{ none|default_if_none:fallback|length }}"
+                .to_string();
+            let template = Template::new_from_string(py, template_string, &engine).unwrap();
+            let context = PyDict::new(py);
+            context.set_item("none", py.None()).unwrap();
+            context.set_item("items", vec![1, 2]).unwrap();
+            context.set_item("empty", "").unwrap();
+            context.set_item("fallback", "abcd").unwrap();
+
+            assert_eq!(
+                template.render(py, Some(context), None).unwrap(),
+                "3|2|3|3|0|4"
+            );
+        })
+    }
+
     #[test]
     fn test_render_filter_lower() {
         Python::initialize();
@@ -980,4 +2786,171 @@ mod tests {
             assert_eq!(rendered, "");
         })
     }
+
+    #[test]
+    fn test_render_filter_urlize_email() {
+        Python::initialize();
+
+        Python::attach(|py| {
+            let engine = EngineData::empty();
+            let template_string = "{{ var|default:'Email lily@example.com.'|urlize }}".to_string();
+            let context = PyDict::new(py);
+            let template = Template::new_from_string(py, template_string, &engine).unwrap();
+            let result = template.render(py, Some(context), None).unwrap();
+
+            assert_eq!(
+                result,
+                "Email <a href=\"mailto:lily@example.com\">lily@example.com</a>."
+            );
+        })
+    }
+
+    #[test]
+    fn test_render_filter_urlizetrunc_email() {
+        Python::initialize();
+
+        Python::attach(|py| {
+            let engine = EngineData::empty();
+            let template_string = "{{ var|default:'lily@example.com'|urlizetrunc:5 }}".to_string();
+            let context = PyDict::new(py);
+            let template = Template::new_from_string(py, template_string, &engine).unwrap();
+            let result = template.render(py, Some(context), None).unwrap();
+
+            assert_eq!(result, "<a href=\"mailto:lily@example.com\">lily…</a>");
+        })
+    }
+
+    #[test]
+    fn test_render_filter_urlizetrunc_huge_limit() {
+        Python::initialize();
+
+        Python::attach(|py| {
+            let engine = EngineData::empty();
+            let template_string =
+                "{{ var|default:'lily@example.com'|urlizetrunc:99999999999999999999 }}".to_string();
+            let context = PyDict::new(py);
+            let template = Template::new_from_string(py, template_string, &engine).unwrap();
+            let result = template.render(py, Some(context), None).unwrap();
+
+            assert_eq!(
+                result,
+                "<a href=\"mailto:lily@example.com\">lily@example.com</a>"
+            );
+        })
+    }
+
+    #[test]
+    fn test_resolve_usize_argument_overflow() {
+        let huge = "99999999999999999999".parse::<BigInt>().unwrap();
+        let error = resolve_usize_argument(Content::Int(huge.clone()), (0, 20), Overflow::Raise)
+            .unwrap_err();
+        assert_eq!(
+            error,
+            RenderError::OverflowError {
+                argument: "99999999999999999999".to_string(),
+                argument_at: (0, 20).into(),
+            }
+        );
+        let clamped =
+            resolve_usize_argument(Content::Int(huge.clone()), (0, 20), Overflow::Clamp).unwrap();
+        assert_eq!(clamped, usize::MAX);
+        let clamped =
+            resolve_usize_argument(Content::Int(-huge), (0, 21), Overflow::Clamp).unwrap();
+        assert_eq!(clamped, 0);
+    }
+
+    #[test]
+    fn test_render_filter_floatformat_special_values() {
+        Python::initialize();
+
+        Python::attach(|py| {
+            let engine = EngineData::empty();
+            let template_string = "{{ inf|floatformat }}|{{ ninf|floatformat:2 }}|\
+                {{ nan|floatformat:0 }}|{{ text|floatformat }}"
+                .to_string();
+            let template = Template::new_from_string(py, template_string, &engine).unwrap();
+            let context = PyDict::new(py);
+            context.set_item("inf", f64::INFINITY).unwrap();
+            context.set_item("ninf", f64::NEG_INFINITY).unwrap();
+            context.set_item("nan", f64::NAN).unwrap();
+            context.set_item("text", "not a number").unwrap();
+
+            assert_eq!(
+                template.render(py, Some(context), None).unwrap(),
+                "inf|-inf|nan|"
+            );
+        })
+    }
+
+    #[test]
+    fn test_floatformat_options() {
+        let options = FloatFormatOptions::from_str("2gu").unwrap();
+        assert_eq!(options.precision, 2);
+        assert!(!options.use_l10n);
+        assert!(options.force_grouping);
+
+        let options = FloatFormatOptions::from_str("-3g").unwrap();
+        assert_eq!(options.precision, -3);
+        assert!(options.use_l10n);
+        assert!(options.force_grouping);
+
+        let options = FloatFormatOptions::from_str("u").unwrap();
+        assert_eq!(options.precision, -1);
+        assert!(!options.use_l10n);
+        assert!(!options.force_grouping);
+
+        assert!(FloatFormatOptions::from_str("bar").is_none());
+    }
+
+    #[test]
+    fn test_round_away_from_one() {
+        assert_eq!(round_away_from_one(1.0), 1);
+        assert_eq!(round_away_from_one(1.2), 2);
+        assert_eq!(round_away_from_one(2.0), 2);
+        assert_eq!(round_away_from_one(0.5), 0);
+        assert_eq!(round_away_from_one(-1.0), -1);
+        assert_eq!(round_away_from_one(-1.2), -2);
+    }
+
+    #[test]
+    fn test_change_case_reuses_unchanged_content() {
+        assert!(matches!(
+            to_lowercase(Cow::Borrowed("already lower, 123!")),
+            Cow::Borrowed("already lower, 123!")
+        ));
+        assert!(matches!(
+            to_uppercase(Cow::Borrowed("ALREADY UPPER, 123!")),
+            Cow::Borrowed("ALREADY UPPER, 123!")
+        ));
+        assert_eq!(to_lowercase(Cow::Borrowed("Mixed Case")), "mixed case");
+        assert_eq!(to_lowercase(Cow::Borrowed("ΟΔΟΣ")), "οδος");
+        assert_eq!(to_uppercase(Cow::Borrowed("straße")), "STRASSE");
+        assert_eq!(to_uppercase(Cow::Borrowed("ǆ")), "Ǆ");
+    }
+
+    #[test]
+    fn test_decimal_string() {
+        assert_eq!(
+            decimal_string(false, &[1, 2, 3, 4, 5, 6, 7, 8, 9], -2),
+            "1234567.89"
+        );
+        assert_eq!(decimal_string(true, &[5], -3), "-0.005");
+        assert_eq!(decimal_string(false, &[7], 0), "7.");
+    }
+
+    #[test]
+    fn test_render_filter_cut() {
+        Python::initialize();
+
+        Python::attach(|py| {
+            let engine = EngineData::empty();
+            let template_string = "{{ var|cut:' ' }}|{{ var|cut:';' }}".to_string();
+            let context = PyDict::new(py);
+            context.set_item("var", "a b&amp;c").unwrap();
+            let template = Template::new_from_string(py, template_string, &engine).unwrap();
+            let result = template.render(py, Some(context), None).unwrap();
+
+            assert_eq!(result, "ab&amp;c|a b&ampc");
+        })
+    }
 }
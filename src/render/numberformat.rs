@@ -0,0 +1,166 @@
+//! A port of Django's `django.utils.numberformat.format`, used by the
+//! `floatformat` filter.
+//!
+//! The locale specific separators and grouping are looked up through Django's
+//! `get_format`, while the formatting itself happens in Rust.
+use pyo3::prelude::*;
+use pyo3::sync::PyOnceLock;
+use pyo3::types::PyDict;
+
+static GET_FORMAT: PyOnceLock<Py<PyAny>> = PyOnceLock::new();
+static GET_LANGUAGE: PyOnceLock<Py<PyAny>> = PyOnceLock::new();
+static SETTINGS: PyOnceLock<Py<PyAny>> = PyOnceLock::new();
+
+/// How to group the digits of the integer part of a number.
+///
+/// Like Django's `NUMBER_GROUPING`, this is either a single group size
+/// repeated indefinitely, or a sequence of sizes where a trailing zero means
+/// the previous size repeats.
+#[derive(Debug, PartialEq)]
+pub enum Grouping {
+    Uniform(usize),
+    Sequence(Vec<usize>),
+}
+
+impl Grouping {
+    fn extract(value: &Bound<'_, PyAny>) -> PyResult<Self> {
+        match value.extract() {
+            Ok(size) => Ok(Self::Uniform(size)),
+            Err(_) => Ok(Self::Sequence(value.extract()?)),
+        }
+    }
+
+    fn intervals(&self) -> Vec<usize> {
+        match self {
+            Self::Uniform(size) => vec![*size, 0],
+            Self::Sequence(sizes) => sizes.clone(),
+        }
+    }
+}
+
+/// Insert `thousand_sep` between the groups of digits in `int_part`.
+pub fn group_digits(int_part: &str, grouping: &Grouping, thousand_sep: &str) -> String {
+    let mut intervals = grouping.intervals().into_iter();
+    let Some(mut active_interval) = intervals.next() else {
+        return int_part.to_string();
+    };
+    let reversed_sep: String = thousand_sep.chars().rev().collect();
+    let mut grouped = String::with_capacity(int_part.len() * 2);
+    let mut count = 0;
+    for digit in int_part.chars().rev() {
+        if count != 0 && count == active_interval {
+            if let Some(interval) = intervals.next().filter(|interval| *interval != 0) {
+                active_interval = interval;
+            }
+            grouped.push_str(&reversed_sep);
+            count = 0;
+        }
+        grouped.push(digit);
+        count += 1;
+    }
+    grouped.chars().rev().collect()
+}
+
+fn get_format<'py>(
+    py: Python<'py>,
+    name: &str,
+    lang: &Bound<'py, PyAny>,
+    use_l10n: bool,
+) -> PyResult<Bound<'py, PyAny>> {
+    let get_format = GET_FORMAT.import(py, "django.utils.formats", "get_format")?;
+    let kwargs = PyDict::new(py);
+    kwargs.set_item("use_l10n", use_l10n)?;
+    get_format.call((name, lang), Some(&kwargs))
+}
+
+/// Format the decimal string `number` like Django's `formats.number_format`.
+///
+/// When given, the decimal part is padded or truncated to `decimal_pos`
+/// digits. Digits are grouped when `settings.USE_THOUSAND_SEPARATOR` is enabled and
+/// localization is in use, or when `force_grouping` is set.
+pub fn number_format(
+    py: Python<'_>,
+    number: &str,
+    decimal_pos: Option<usize>,
+    use_l10n: bool,
+    force_grouping: bool,
+) -> PyResult<String> {
+    let lang = match use_l10n {
+        true => GET_LANGUAGE
+            .import(py, "django.utils.translation", "get_language")?
+            .call0()?,
+        false => py.None().into_bound(py),
+    };
+    let decimal_sep: String = get_format(py, "DECIMAL_SEPARATOR", &lang, use_l10n)?.extract()?;
+
+    let (sign, number) = match number.strip_prefix('-') {
+        Some(number) => ("-", number),
+        None => ("", number),
+    };
+    let (int_part, dec_part) = match number.split_once('.') {
+        Some((int_part, dec_part)) => match decimal_pos {
+            Some(decimal_pos) => {
+                let end = dec_part
+                    .char_indices()
+                    .nth(decimal_pos)
+                    .map_or(dec_part.len(), |(index, _)| index);
+                (int_part, &dec_part[..end])
+            }
+            None => (int_part, dec_part),
+        },
+        None => (number, ""),
+    };
+    let padding = decimal_pos.map_or(0, |decimal_pos| {
+        decimal_pos.saturating_sub(dec_part.chars().count())
+    });
+    let mut formatted_dec = String::new();
+    if !dec_part.is_empty() || padding > 0 {
+        formatted_dec.push_str(&decimal_sep);
+        formatted_dec.push_str(dec_part);
+        formatted_dec.push_str(&"0".repeat(padding));
+    }
+
+    let settings = SETTINGS.import(py, "django.conf", "settings")?;
+    let use_thousand_separator = settings.getattr("USE_THOUSAND_SEPARATOR")?.is_truthy()?;
+    let grouping = Grouping::extract(&get_format(py, "NUMBER_GROUPING", &lang, use_l10n)?)?;
+    let use_grouping = ((use_l10n && use_thousand_separator) || force_grouping)
+        && grouping != Grouping::Uniform(0);
+
+    let int_part = match use_grouping {
+        true => {
+            let thousand_sep: String =
+                get_format(py, "THOUSAND_SEPARATOR", &lang, use_l10n)?.extract()?;
+            group_digits(int_part, &grouping, &thousand_sep)
+        }
+        false => int_part.to_string(),
+    };
+    Ok(format!("{sign}{int_part}{formatted_dec}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_group_digits_uniform() {
+        let grouping = Grouping::Uniform(3);
+        assert_eq!(group_digits("1234567", &grouping, ","), "1,234,567");
+        assert_eq!(group_digits("123", &grouping, ","), "123");
+        assert_eq!(group_digits("1234", &grouping, "\u{a0}"), "1\u{a0}234");
+    }
+
+    #[test]
+    fn test_group_digits_sequence() {
+        let grouping = Grouping::Sequence(vec![3, 2, 0]);
+        assert_eq!(group_digits("123456789", &grouping, ","), "12,34,56,789");
+
+        let grouping = Grouping::Sequence(vec![3, 1]);
+        assert_eq!(group_digits("123456789", &grouping, ","), "1,2,3,4,5,6,789");
+    }
+
+    #[test]
+    fn test_group_digits_multi_character_separator() {
+        let grouping = Grouping::Uniform(3);
+        assert_eq!(group_digits("1234567", &grouping, "ab"), "1ab234ab567");
+    }
+}
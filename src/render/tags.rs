@@ -1,22 +1,42 @@
 use std::borrow::Cow;
-use std::collections::VecDeque;
-use std::sync::Arc;
+use std::cmp::Ordering;
+use std::collections::{HashMap, VecDeque};
+use std::iter::Peekable;
+use std::sync::{Arc, LazyLock};
 
+use html_escape::encode_quoted_attribute;
 use num_bigint::{BigInt, Sign};
-use num_traits::cast::ToPrimitive;
-use pyo3::exceptions::PyAttributeError;
+use num_traits::{FromPrimitive, ToPrimitive};
+use pyo3::exceptions::{PyAttributeError, PyKeyError, PyOverflowError, PyTypeError, PyValueError};
+use pyo3::intern;
 use pyo3::prelude::*;
-use pyo3::sync::MutexExt;
-use pyo3::types::{PyBool, PyDict, PyList, PyNone, PyString, PyTuple};
+use pyo3::sync::{MutexExt, PyOnceLock};
+use pyo3::types::{PyBool, PyDict, PyFloat, PyInt, PyList, PyNone, PyString, PyTuple};
+use regex::Regex;
 
-use super::types::{AsBorrowedContent, Content, Context, PyContext};
+use super::timezone;
+use super::types::{AsBorrowedContent, Content, ContentString, Context, PyContext};
 use super::{Evaluate, Render, RenderResult, Resolve, ResolveFailures, ResolveResult};
+use crate::display::TemplateDisplay;
 use crate::error::{AnnotatePyErr, PyRenderError, RenderError};
-use crate::parse::{For, IfCondition, SimpleBlockTag, SimpleTag, Tag, TagElement, Url};
-use crate::template::django_rusty_templates::NoReverseMatch;
+use crate::parse::{
+    Block, BlockTranslate, Cache, Extends, For, IfCondition, Include, Now, SimpleBlockTag,
+    SimpleTag, Tag, TagElement, TokenTree, Url, WidthRatio,
+};
+use crate::template::django_rusty_templates::{
+    InvalidCacheBackendError, NoReverseMatch, Template, TemplateDoesNotExist, TemplateSyntaxError,
+    annotate_render_error,
+};
 use crate::types::TemplateString;
 use crate::utils::PyResultMethods;
 
+static SPACES_BETWEEN_TAGS: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r">\s+<").expect("Static string will never panic"));
+
+static CACHES: PyOnceLock<Py<PyAny>> = PyOnceLock::new();
+static GETTEXT: PyOnceLock<Py<PyAny>> = PyOnceLock::new();
+static MAKE_TEMPLATE_FRAGMENT_KEY: PyOnceLock<Py<PyAny>> = PyOnceLock::new();
+
 fn current_app(py: Python, request: &Option<Py<PyAny>>) -> PyResult<Py<PyAny>> {
     let Some(request) = request else {
         return Ok(py.None());
@@ -54,10 +74,24 @@ impl Resolve for Url {
         let reverse = urls.getattr("reverse")?;
 
         let current_app = current_app(py, &context.request)?;
+        // Like Django, a missing argument is passed to `reverse` as an empty
+        // string. We remember where it was to point at it if reversing fails.
+        let mut missing_at = None;
+        let mut resolve_argument = |element: &TagElement, context: &mut Context| match element
+            .resolve(py, template, context, failures)?
+        {
+            Some(content) => Ok::<_, PyRenderError>(content),
+            None => {
+                if let TagElement::Variable(variable) = element {
+                    missing_at.get_or_insert(variable.at);
+                }
+                Ok("".as_content())
+            }
+        };
         let url = if self.kwargs.is_empty() {
             let py_args = PyList::empty(py);
             for arg in &self.args {
-                py_args.append(arg.resolve(py, template, context, failures)?)?;
+                py_args.append(resolve_argument(arg, context)?)?;
             }
             reverse.call1((
                 view_name,
@@ -69,10 +103,16 @@ impl Resolve for Url {
         } else {
             let kwargs = PyDict::new(py);
             for (key, value) in &self.kwargs {
-                kwargs.set_item(key, value.resolve(py, template, context, failures)?)?;
+                kwargs.set_item(key, resolve_argument(value, context)?)?;
             }
             reverse.call1((view_name, py.None(), py.None(), kwargs, current_app))
         };
+        let url = match (url, missing_at) {
+            (Err(error), Some(at)) if error.is_instance_of::<NoReverseMatch>(py) => {
+                Err(error.annotate(py, at, "this variable is missing", template))
+            }
+            (url, _) => url,
+        };
         match &self.variable {
             None => Ok(Some(Content::Py(url?))),
             Some(variable) => {
@@ -93,7 +133,9 @@ impl Evaluate for Content<'_, '_> {
         _context: &mut Context,
     ) -> Option<bool> {
         Some(match self {
-            Self::Py(obj) => obj.is_truthy().unwrap_or(false),
+            // Django treats an error from `__bool__` inside an operator as
+            // making the whole condition false.
+            Self::Py(obj) => obj.is_truthy().ok()?,
             Self::String(s) => !s.as_raw().is_empty(),
             Self::Float(f) => *f != 0.0,
             Self::Int(n) => *n != BigInt::ZERO,
@@ -102,6 +144,27 @@ impl Evaluate for Content<'_, '_> {
     }
 }
 
+/// Compare a float with an integer exactly, like Python does, instead of
+/// converting the integer to a float and losing precision.
+fn cmp_float_int(float: f64, int: &BigInt) -> Option<Ordering> {
+    if float.is_nan() {
+        return None;
+    }
+    if float.is_infinite() {
+        return Some(float.partial_cmp(&0.0).expect("infinity is not NaN"));
+    }
+    let floor = BigInt::from_f64(float.floor()).expect("finite floats convert to BigInt");
+    Some(match floor.cmp(int) {
+        Ordering::Equal if float.fract() != 0.0 => Ordering::Greater,
+        ordering => ordering,
+    })
+}
+
+/// Python comparison semantics for `{% if %}` operators.
+///
+/// Where Python would raise a `TypeError`, such as ordering a string against
+/// a number, the comparison is `false` in both directions, because Django
+/// catches the error and treats the condition as false.
 trait PyCmp<T> {
     fn eq(&self, other: &T) -> bool;
 
@@ -139,18 +202,10 @@ impl PyCmp<Content<'_, '_>> for Content<'_, '_> {
                 .map(|o| o == *obj as u8)
                 .unwrap_or(false),
             (Self::Float(obj), Content::Int(other)) => {
-                match other.to_f64().expect("BigInt to f64 is always possible") {
-                    f64::INFINITY => false,
-                    f64::NEG_INFINITY => false,
-                    other => *obj == other,
-                }
+                matches!(cmp_float_int(*obj, other), Some(Ordering::Equal))
             }
             (Self::Int(obj), Content::Float(other)) => {
-                match obj.to_f64().expect("BigInt to f64 is always possible") {
-                    f64::INFINITY => false,
-                    f64::NEG_INFINITY => false,
-                    obj => obj == *other,
-                }
+                matches!(cmp_float_int(*other, obj), Some(Ordering::Equal))
             }
             (Self::Float(obj), Content::Bool(other)) => match other {
                 true => *obj == 1.0,
@@ -188,18 +243,10 @@ impl PyCmp<Content<'_, '_>> for Content<'_, '_> {
                 _ => u8::try_from(other).map(|o| o > *obj as u8).unwrap_or(true),
             },
             (Self::Float(obj), Content::Int(other)) => {
-                match other.to_f64().expect("BigInt to f64 is always possible") {
-                    f64::INFINITY => obj.is_finite() || *obj == f64::NEG_INFINITY,
-                    f64::NEG_INFINITY => *obj == f64::NEG_INFINITY,
-                    other => *obj < other,
-                }
+                matches!(cmp_float_int(*obj, other), Some(Ordering::Less))
             }
             (Self::Int(obj), Content::Float(other)) => {
-                match obj.to_f64().expect("BigInt to f64 is always possible") {
-                    f64::INFINITY => *other == f64::INFINITY,
-                    f64::NEG_INFINITY => other.is_finite() || *other == f64::INFINITY,
-                    obj => obj < *other,
-                }
+                matches!(cmp_float_int(*other, obj), Some(Ordering::Greater))
             }
             (Self::Float(obj), Content::Bool(other)) => match other {
                 true => *obj < 1.0,
@@ -237,18 +284,10 @@ impl PyCmp<Content<'_, '_>> for Content<'_, '_> {
                 _ => u8::try_from(other).map(|o| o < *obj as u8).unwrap_or(false),
             },
             (Self::Float(obj), Content::Int(other)) => {
-                match other.to_f64().expect("BigInt to f64 is always possible") {
-                    f64::INFINITY => *obj == f64::INFINITY,
-                    f64::NEG_INFINITY => obj.is_finite() || *obj == f64::INFINITY,
-                    other => *obj > other,
-                }
+                matches!(cmp_float_int(*obj, other), Some(Ordering::Greater))
             }
             (Self::Int(obj), Content::Float(other)) => {
-                match obj.to_f64().expect("BigInt to f64 is always possible") {
-                    f64::INFINITY => other.is_finite() || *other == f64::NEG_INFINITY,
-                    f64::NEG_INFINITY => *other == f64::NEG_INFINITY,
-                    obj => obj > *other,
-                }
+                matches!(cmp_float_int(*other, obj), Some(Ordering::Less))
             }
             (Self::Float(obj), Content::Bool(other)) => match other {
                 true => *obj > 1.0,
@@ -287,20 +326,14 @@ impl PyCmp<Content<'_, '_>> for Content<'_, '_> {
                 Sign::Minus => false,
                 _ => u8::try_from(other).map(|o| o >= *obj as u8).unwrap_or(true),
             },
-            (Self::Float(obj), Content::Int(other)) => {
-                match other.to_f64().expect("BigInt to f64 is always possible") {
-                    f64::INFINITY => obj.is_finite() || *obj == f64::NEG_INFINITY,
-                    f64::NEG_INFINITY => *obj == f64::NEG_INFINITY,
-                    other => *obj <= other,
-                }
-            }
-            (Self::Int(obj), Content::Float(other)) => {
-                match obj.to_f64().expect("BigInt to f64 is always possible") {
-                    f64::INFINITY => *other == f64::INFINITY,
-                    f64::NEG_INFINITY => other.is_finite() || *other == f64::INFINITY,
-                    obj => obj <= *other,
-                }
-            }
+            (Self::Float(obj), Content::Int(other)) => matches!(
+                cmp_float_int(*obj, other),
+                Some(Ordering::Less | Ordering::Equal)
+            ),
+            (Self::Int(obj), Content::Float(other)) => matches!(
+                cmp_float_int(*other, obj),
+                Some(Ordering::Greater | Ordering::Equal)
+            ),
             (Self::Float(obj), Content::Bool(other)) => match other {
                 true => *obj <= 1.0,
                 false => *obj <= 0.0,
@@ -338,20 +371,14 @@ impl PyCmp<Content<'_, '_>> for Content<'_, '_> {
                     .map(|o| o <= *obj as u8)
                     .unwrap_or(false),
             },
-            (Self::Float(obj), Content::Int(other)) => {
-                match other.to_f64().expect("BigInt to f64 is always possible") {
-                    f64::INFINITY => *obj == f64::INFINITY,
-                    f64::NEG_INFINITY => obj.is_finite() || *obj == f64::INFINITY,
-                    other => *obj >= other,
-                }
-            }
-            (Self::Int(obj), Content::Float(other)) => {
-                match obj.to_f64().expect("BigInt to f64 is always possible") {
-                    f64::INFINITY => other.is_finite() || *other == f64::NEG_INFINITY,
-                    f64::NEG_INFINITY => *other == f64::NEG_INFINITY,
-                    obj => obj >= *other,
-                }
-            }
+            (Self::Float(obj), Content::Int(other)) => matches!(
+                cmp_float_int(*obj, other),
+                Some(Ordering::Greater | Ordering::Equal)
+            ),
+            (Self::Int(obj), Content::Float(other)) => matches!(
+                cmp_float_int(*other, obj),
+                Some(Ordering::Less | Ordering::Equal)
+            ),
             (Self::Float(obj), Content::Bool(other)) => match other {
                 true => *obj >= 1.0,
                 false => *obj >= 0.0,
@@ -412,6 +439,9 @@ trait Contains<T> {
     fn contains(&self, other: T) -> Option<bool>;
 }
 
+/// Like Python's `in`, objects without `__contains__` are iterated, so a
+/// generator is consumed up to the first match, or entirely if there isn't
+/// one. As in Django, later uses of it only see the remaining items.
 impl Contains<Option<Content<'_, '_>>> for Content<'_, '_> {
     fn contains(&self, other: Option<Content<'_, '_>>) -> Option<bool> {
         match other {
@@ -470,23 +500,23 @@ impl<'t, 'py> ResolveTuple<'t, 'py> for (IfCondition, IfCondition) {
             (IfCondition::Variable(l), r) => {
                 let left = l.resolve(py, template, context, IGNORE)?;
                 let right = r
-                    .evaluate(py, template, context)
+                    .try_evaluate(py, template, context)?
                     .expect("Right cannot be an expression that evaluates to None");
                 (left, Some(Content::Bool(right)))
             }
             (l, IfCondition::Variable(r)) => {
                 let left = l
-                    .evaluate(py, template, context)
+                    .try_evaluate(py, template, context)?
                     .expect("Left cannot be an expression that evaluates to None");
                 let right = r.resolve(py, template, context, IGNORE)?;
                 (Some(Content::Bool(left)), right)
             }
             (l, r) => {
                 let left = l
-                    .evaluate(py, template, context)
+                    .try_evaluate(py, template, context)?
                     .expect("Left cannot be an expression that evaluates to None");
                 let right = r
-                    .evaluate(py, template, context)
+                    .try_evaluate(py, template, context)?
                     .expect("Right cannot be an expression that evaluates to None");
                 (Some(Content::Bool(left)), Some(Content::Bool(right)))
             }
@@ -494,23 +524,60 @@ impl<'t, 'py> ResolveTuple<'t, 'py> for (IfCondition, IfCondition) {
     }
 }
 
-impl Evaluate for IfCondition {
-    fn evaluate(
+/// Like Django, errors in an `{% if %}` condition make it false, except that
+/// with `strict_variables` a missing variable still raises.
+fn ignore_error<T>(
+    result: Result<T, PyRenderError>,
+    context: &Context,
+) -> Result<Option<T>, PyRenderError> {
+    match result {
+        Ok(value) => Ok(Some(value)),
+        Err(PyRenderError::RenderError(err @ RenderError::VariableDoesNotExist { .. }))
+            if context.strict_variables =>
+        {
+            Err(err.into())
+        }
+        Err(_) => Ok(None),
+    }
+}
+
+impl IfCondition {
+    fn try_evaluate(
         &self,
         py: Python<'_>,
         template: TemplateString<'_>,
         context: &mut Context,
-    ) -> Option<bool> {
-        Some(match self {
-            Self::Variable(v) => v.evaluate(py, template, context)?,
+    ) -> Result<Option<bool>, PyRenderError> {
+        Ok(Some(match self {
+            Self::Variable(v) => {
+                let resolved = v.resolve(
+                    py,
+                    template,
+                    context,
+                    ResolveFailures::IgnoreVariableDoesNotExist,
+                );
+                let Some(content) = ignore_error(resolved, context)? else {
+                    return Ok(None);
+                };
+                match content.evaluate(py, template, context) {
+                    Some(matched) => matched,
+                    None => return Ok(None),
+                }
+            }
             Self::And(inner) => {
-                let left = inner.0.evaluate(py, template, context).unwrap_or(false);
-                let right = inner.1.evaluate(py, template, context).unwrap_or(false);
+                let left = inner
+                    .0
+                    .try_evaluate(py, template, context)?
+                    .unwrap_or(false);
+                let right = inner
+                    .1
+                    .try_evaluate(py, template, context)?
+                    .unwrap_or(false);
                 if !left { false } else { right }
             }
             Self::Or(inner) => {
-                let left = inner.0.evaluate(py, template, context);
-                let right = inner.1.evaluate(py, template, context);
+                let left = inner.0.try_evaluate(py, template, context)?;
+                let right = inner.1.try_evaluate(py, template, context)?;
                 match left {
                     None => false,
                     Some(left) => {
@@ -522,38 +589,51 @@ impl Evaluate for IfCondition {
                     }
                 }
             }
-            Self::Not(inner) => match inner.evaluate(py, template, context) {
+            Self::Not(inner) => match inner.try_evaluate(py, template, context)? {
                 None => false,
                 Some(true) => false,
                 Some(false) => true,
             },
-            Self::Equal(inner) => match inner.resolve(py, template, context) {
-                Ok((l, r)) => l.eq(&r),
-                Err(_) => false,
-            },
-            Self::NotEqual(inner) => match inner.resolve(py, template, context) {
-                Ok((l, r)) => l.ne(&r),
-                Err(_) => false,
-            },
-            Self::LessThan(inner) => match inner.resolve(py, template, context) {
-                Ok((l, r)) => l.lt(&r),
-                Err(_) => false,
-            },
-            Self::GreaterThan(inner) => match inner.resolve(py, template, context) {
-                Ok((l, r)) => l.gt(&r),
-                Err(_) => false,
-            },
-            Self::LessThanEqual(inner) => match inner.resolve(py, template, context) {
-                Ok((l, r)) => l.lte(&r),
-                Err(_) => false,
-            },
-            Self::GreaterThanEqual(inner) => match inner.resolve(py, template, context) {
-                Ok((l, r)) => l.gte(&r),
-                Err(_) => false,
-            },
+            Self::Equal(inner) => {
+                match ignore_error(inner.resolve(py, template, context), context)? {
+                    Some((l, r)) => l.eq(&r),
+                    None => false,
+                }
+            }
+            Self::NotEqual(inner) => {
+                match ignore_error(inner.resolve(py, template, context), context)? {
+                    Some((l, r)) => l.ne(&r),
+                    None => false,
+                }
+            }
+            Self::LessThan(inner) => {
+                match ignore_error(inner.resolve(py, template, context), context)? {
+                    Some((l, r)) => l.lt(&r),
+                    None => false,
+                }
+            }
+            Self::GreaterThan(inner) => {
+                match ignore_error(inner.resolve(py, template, context), context)? {
+                    Some((l, r)) => l.gt(&r),
+                    None => false,
+                }
+            }
+            Self::LessThanEqual(inner) => {
+                match ignore_error(inner.resolve(py, template, context), context)? {
+                    Some((l, r)) => l.lte(&r),
+                    None => false,
+                }
+            }
+            Self::GreaterThanEqual(inner) => {
+                match ignore_error(inner.resolve(py, template, context), context)? {
+                    Some((l, r)) => l.gte(&r),
+                    None => false,
+                }
+            }
             Self::In(inner) => {
-                let Ok(inner) = inner.resolve(py, template, context) else {
-                    return Some(false);
+                let Some(inner) = ignore_error(inner.resolve(py, template, context), context)?
+                else {
+                    return Ok(Some(false));
                 };
                 match inner {
                     (l, Some(r)) => r.contains(l).unwrap_or(false),
@@ -561,8 +641,9 @@ impl Evaluate for IfCondition {
                 }
             }
             Self::NotIn(inner) => {
-                let Ok(inner) = inner.resolve(py, template, context) else {
-                    return Some(false);
+                let Some(inner) = ignore_error(inner.resolve(py, template, context), context)?
+                else {
+                    return Ok(Some(false));
                 };
                 match inner {
                     (l, Some(r)) => !(r.contains(l).unwrap_or(true)),
@@ -570,8 +651,9 @@ impl Evaluate for IfCondition {
                 }
             }
             Self::Is(inner) => {
-                let Ok(inner) = inner.resolve(py, template, context) else {
-                    return Some(false);
+                let Some(inner) = ignore_error(inner.resolve(py, template, context), context)?
+                else {
+                    return Ok(Some(false));
                 };
                 match inner {
                     (Some(Content::Py(left)), Some(Content::Py(right))) => left.is(&right),
@@ -582,13 +664,18 @@ impl Evaluate for IfCondition {
                     (Some(Content::Bool(left)), Some(Content::Py(right))) => {
                         right.is(PyBool::new(py, left).as_any())
                     }
+                    (Some(Content::Py(left)), Some(Content::Bool(right))) => {
+                        left.is(PyBool::new(py, right).as_any())
+                    }
+                    (Some(Content::Bool(left)), Some(Content::Bool(right))) => left == right,
                     (None, None) => true,
                     _ => false,
                 }
             }
             Self::IsNot(inner) => {
-                let Ok(inner) = inner.resolve(py, template, context) else {
-                    return Some(false);
+                let Some(inner) = ignore_error(inner.resolve(py, template, context), context)?
+                else {
+                    return Ok(Some(false));
                 };
                 match inner {
                     (Some(Content::Py(left)), Some(Content::Py(right))) => !left.is(&right),
@@ -608,8 +695,63 @@ impl Evaluate for IfCondition {
                     _ => true,
                 }
             }
-        })
+        }))
+    }
+}
+
+/// Resolve the `name=value` assignments of tags like `{% with %}`. Missing
+/// variables become empty strings.
+fn resolve_variables<'py>(
+    py: Python<'py>,
+    template: TemplateString<'_>,
+    context: &mut Context,
+    variables: &[(String, TagElement)],
+) -> Result<Vec<(String, Bound<'py, PyAny>)>, PyRenderError> {
+    let mut values = Vec::with_capacity(variables.len());
+    for (name, value) in variables {
+        values.push((
+            name.clone(),
+            resolve_or_empty(py, template, context, value)?,
+        ));
+    }
+    Ok(values)
+}
+
+/// Resolve `element` to a Python object, using an empty string for missing
+/// variables like Django does.
+fn resolve_or_empty<'py>(
+    py: Python<'py>,
+    template: TemplateString<'_>,
+    context: &mut Context,
+    element: &TagElement,
+) -> Result<Bound<'py, PyAny>, PyRenderError> {
+    Ok(element
+        .resolve(
+            py,
+            template,
+            context,
+            ResolveFailures::IgnoreVariableDoesNotExist,
+        )?
+        .map_or_else(|| PyString::new(py, "").into_any(), |value| value.to_py(py)))
+}
+
+fn render_csrf_token<'t>(py: Python<'_>, context: &Context) -> RenderResult<'t> {
+    let Some(csrf_token) = context.get("csrf_token") else {
+        return Ok(Cow::Borrowed(""));
+    };
+    let csrf_token = csrf_token.bind(py);
+    if !csrf_token.is_truthy()? {
+        return Ok(Cow::Borrowed(""));
+    }
+    let csrf_token = csrf_token.str()?;
+    let csrf_token = csrf_token.to_str()?;
+    if csrf_token == "NOTPROVIDED" {
+        return Ok(Cow::Borrowed(""));
     }
+    Ok(Cow::Owned(format!(
+        "<input type=\"hidden\" name=\"csrfmiddlewaretoken\" value=\"{}\">",
+        encode_quoted_attribute(csrf_token)
+    )))
 }
 
 impl Render for Tag {
@@ -637,99 +779,577 @@ impl Render for Tag {
                 truthy,
                 falsey,
             } => {
-                if condition.evaluate(py, template, context).unwrap_or(false) {
-                    truthy.render(py, template, context)?
-                } else {
-                    falsey.render(py, template, context)?
+                // An `elif` chain is an `if` nested in each `else`, so it is
+                // followed in a loop rather than rendered recursively.
+                let (mut condition, mut truthy, mut falsey) = (condition, truthy, falsey);
+                loop {
+                    let matched = match condition {
+                        // Like Django, errors from `__bool__` propagate for a
+                        // lone variable. Operators treat them as false.
+                        IfCondition::Variable(variable) => match ignore_error(
+                            variable.resolve(
+                                py,
+                                template,
+                                context,
+                                ResolveFailures::IgnoreVariableDoesNotExist,
+                            ),
+                            context,
+                        )? {
+                            Some(Some(Content::Py(obj))) => obj.is_truthy()?,
+                            Some(content) => {
+                                content.evaluate(py, template, context).unwrap_or(false)
+                            }
+                            None => false,
+                        },
+                        condition => condition
+                            .try_evaluate(py, template, context)?
+                            .unwrap_or(false),
+                    };
+                    if matched {
+                        break truthy.render(py, template, context)?;
+                    }
+                    match falsey.as_deref() {
+                        Some(
+                            [
+                                TokenTree::Tag(Self::If {
+                                    condition: elif_condition,
+                                    truthy: elif_truthy,
+                                    falsey: elif_falsey,
+                                }),
+                            ],
+                        ) => {
+                            (condition, truthy, falsey) =
+                                (elif_condition, elif_truthy, elif_falsey);
+                        }
+                        _ => break falsey.render(py, template, context)?,
+                    }
                 }
             }
+            Self::Block(block) => block.render(py, template, context)?,
+            Self::BlockTranslate(block_translate) => {
+                block_translate.render(py, template, context)?
+            }
+            Self::Cache(cache) => cache.render(py, template, context)?,
+            Self::CsrfToken => render_csrf_token(py, context)?,
+            Self::Extends(_) => unreachable!("templates render their own `extends` tag"),
+            Self::GetCurrentTimezone { variable } => {
+                let name = timezone::current_timezone_name(py)?;
+                context.insert(variable.clone(), name);
+                Cow::Borrowed("")
+            }
             Self::For(for_tag) => for_tag.render(py, template, context)?,
+            Self::Include(include) => include.render(py, template, context)?,
             Self::Load => Cow::Borrowed(""),
+            Self::Now(now) => now.render(py, template, context)?,
             Self::SimpleTag(simple_tag) => simple_tag.render(py, template, context)?,
             Self::SimpleBlockTag(simple_tag) => simple_tag.render(py, template, context)?,
+            Self::Spaceless { nodes } => {
+                let rendered = nodes.render(py, template, context)?;
+                Cow::Owned(
+                    SPACES_BETWEEN_TAGS
+                        .replace_all(rendered.trim(), "><")
+                        .into_owned(),
+                )
+            }
+            Self::Timezone {
+                timezone: tz,
+                nodes,
+            } => {
+                let tz = resolve_or_empty(py, template, context, tz)?;
+                let (timezone_override, active) = timezone::enter_override(py, &tz)?;
+                let previous = context.replace_timezone(Some(active.unbind()));
+                let rendered = nodes.render(py, template, context);
+                context.replace_timezone(previous);
+                timezone::exit_override(py, &timezone_override)?;
+                rendered?
+            }
             Self::Url(url) => url.render(py, template, context)?,
+            Self::WidthRatio(width_ratio) => width_ratio.render(py, template, context)?,
+            Self::With { variables, nodes } => {
+                let values = resolve_variables(py, template, context, variables)?;
+                let depth = context.scope_depth();
+                context.push_scope(values);
+                let rendered = nodes.render(py, template, context);
+                context.pop_variables_to(depth);
+                rendered?
+            }
         })
     }
 }
 
-impl For {
-    fn render_python<'t>(
+impl Render for Now {
+    fn render<'t>(
         &self,
-        iterable: &Bound<'_, PyAny>,
         py: Python<'_>,
         template: TemplateString<'t>,
         context: &mut Context,
     ) -> RenderResult<'t> {
-        let mut parts = Vec::new();
-        let mut list: Vec<_> = match iterable.try_iter() {
-            Ok(iterator) => iterator.collect(),
-            Err(error) => {
-                let error = error.annotate(py, self.iterable.at, "here", template);
-                return Err(error.into());
+        let now = timezone::now(py, context)?;
+        let formatted = timezone::format(py, &now, template.content(self.format.at))?;
+        match &self.asvar {
+            Some(asvar) => {
+                context.insert(asvar.clone(), PyString::new(py, &formatted).into_any());
+                Ok(Cow::Borrowed(""))
             }
-        };
-        if self.reversed {
-            list.reverse();
+            None => Ok(Cow::Owned(formatted)),
         }
-        context.push_for_loop(list.len());
-        for (index, values) in list.into_iter().enumerate() {
-            let values = match values {
-                Ok(values) => values,
-                Err(error) => {
-                    let error =
-                        error.annotate(py, self.iterable.at, "while iterating this", template);
-                    return Err(error.into());
+    }
+}
+
+impl WidthRatio {
+    fn max_width(
+        &self,
+        py: Python<'_>,
+        template: TemplateString<'_>,
+        context: &mut Context,
+    ) -> Result<BigInt, PyRenderError> {
+        let max_width = resolve_or_empty(py, template, context, &self.max_width)?;
+        match py.get_type::<PyInt>().call1((&max_width,)) {
+            Ok(max_width) => Ok(max_width.extract()?),
+            Err(error)
+                if error.is_instance_of::<PyValueError>(py)
+                    || error.is_instance_of::<PyTypeError>(py) =>
+            {
+                let message = "widthratio final argument must be a number";
+                Err(TemplateSyntaxError::new_err(message).into())
+            }
+            Err(error) => Err(error.into()),
+        }
+    }
+
+    /// Calculate the ratio like Django, returning `None` where Django would
+    /// render an empty string.
+    ///
+    /// Like Python's `round`, ties are rounded to the nearest even number.
+    fn ratio(
+        &self,
+        py: Python<'_>,
+        template: TemplateString<'_>,
+        context: &mut Context,
+        max_width: &BigInt,
+    ) -> Result<Option<String>, PyRenderError> {
+        let mut float = |element| -> Result<Option<f64>, PyRenderError> {
+            let value = resolve_or_empty(py, template, context, element)?;
+            match py.get_type::<PyFloat>().call1((value,)) {
+                Ok(value) => Ok(Some(value.extract()?)),
+                Err(error)
+                    if error.is_instance_of::<PyValueError>(py)
+                        || error.is_instance_of::<PyTypeError>(py)
+                        || error.is_instance_of::<PyOverflowError>(py) =>
+                {
+                    Ok(None)
                 }
+                Err(error) => Err(error.into()),
+            }
+        };
+        let (Some(value), Some(max_value)) = (float(&self.value)?, float(&self.max_value)?) else {
+            return Ok(None);
+        };
+        if max_value == 0.0 {
+            return Ok(Some("0".to_string()));
+        }
+        let Some(max_width) = max_width.to_f64().filter(|max_width| max_width.is_finite()) else {
+            return Ok(None);
+        };
+        let ratio = (value / max_value) * max_width;
+        Ok(BigInt::from_f64(ratio.round_ties_even()).map(|ratio| ratio.to_string()))
+    }
+}
+
+impl Render for WidthRatio {
+    fn render<'t>(
+        &self,
+        py: Python<'_>,
+        template: TemplateString<'t>,
+        context: &mut Context,
+    ) -> RenderResult<'t> {
+        let max_width = self.max_width(py, template, context)?;
+        let ratio = self
+            .ratio(py, template, context, &max_width)?
+            .unwrap_or_default();
+        match &self.asvar {
+            Some(asvar) => {
+                context.insert(asvar.clone(), PyString::new(py, &ratio).into_any());
+                Ok(Cow::Borrowed(""))
+            }
+            None => Ok(Cow::Owned(ratio)),
+        }
+    }
+}
+
+impl Cache {
+    fn expire_time<'py>(
+        &self,
+        py: Python<'py>,
+        template: TemplateString<'_>,
+        context: &mut Context,
+    ) -> Result<Bound<'py, PyAny>, PyRenderError> {
+        let expire_time = resolve_or_empty(py, template, context, &self.expire_time)?;
+        if expire_time.is_none() {
+            return Ok(expire_time);
+        }
+        match py.get_type::<PyInt>().call1((&expire_time,)) {
+            Ok(expire_time) => Ok(expire_time),
+            Err(error)
+                if error.is_instance_of::<PyValueError>(py)
+                    || error.is_instance_of::<PyTypeError>(py) =>
+            {
+                let message = format!(
+                    "\"cache\" tag got a non-integer timeout value: {}",
+                    expire_time.repr()?
+                );
+                Err(TemplateSyntaxError::new_err(message).into())
+            }
+            Err(error) => Err(error.into()),
+        }
+    }
+
+    fn fragment_cache<'py>(
+        &self,
+        py: Python<'py>,
+        template: TemplateString<'_>,
+        context: &mut Context,
+    ) -> Result<Bound<'py, PyAny>, PyRenderError> {
+        let caches = CACHES.import(py, "django.core.cache", "caches")?;
+        let Some(cache_name) = &self.cache_name else {
+            return Ok(
+                match caches
+                    .get_item("template_fragments")
+                    .ok_or_isinstance_of::<InvalidCacheBackendError>(py)?
+                {
+                    Ok(cache) => cache,
+                    Err(_) => caches.get_item("default")?,
+                },
+            );
+        };
+        let cache_name = resolve_or_empty(py, template, context, cache_name)?;
+        match caches
+            .get_item(&cache_name)
+            .ok_or_isinstance_of::<InvalidCacheBackendError>(py)?
+        {
+            Ok(cache) => Ok(cache),
+            Err(_) => {
+                let message = format!(
+                    "Invalid cache name specified for cache tag: {}",
+                    cache_name.repr()?
+                );
+                Err(TemplateSyntaxError::new_err(message).into())
+            }
+        }
+    }
+}
+
+impl Render for Cache {
+    fn render<'t>(
+        &self,
+        py: Python<'_>,
+        template: TemplateString<'t>,
+        context: &mut Context,
+    ) -> RenderResult<'t> {
+        let expire_time = self.expire_time(py, template, context)?;
+        let cache = self.fragment_cache(py, template, context)?;
+        let vary_on = self
+            .vary_on
+            .iter()
+            .map(|element| resolve_or_empty(py, template, context, element))
+            .collect::<Result<Vec<_>, _>>()?;
+        let make_template_fragment_key = MAKE_TEMPLATE_FRAGMENT_KEY.import(
+            py,
+            "django.core.cache.utils",
+            "make_template_fragment_key",
+        )?;
+        let key = make_template_fragment_key.call1((&self.fragment_name, vary_on))?;
+
+        let cached = cache.call_method1("get", (&key,))?;
+        if !cached.is_none() {
+            return Ok(Cow::Owned(cached.extract()?));
+        }
+        let rendered = self.nodes.render(py, template, context)?;
+        cache.call_method1("set", (&key, rendered.as_ref(), expire_time))?;
+        Ok(rendered)
+    }
+}
+
+impl Render for Block {
+    fn render<'t>(
+        &self,
+        py: Python<'_>,
+        template: TemplateString<'t>,
+        context: &mut Context,
+    ) -> RenderResult<'t> {
+        // Inside `{% extends %}`, the most derived version of the block is
+        // rendered in its place.
+        let Some(version) = context.pop_block(&self.name) else {
+            return match context.in_extends() {
+                true => self.render_version(py, template, context),
+                false => self.render_base(py, template, context),
             };
-            context.push_variables(
-                &self.variables.names,
-                self.variables.at,
-                values,
-                self.iterable.at,
-                index,
-                template,
-            )?;
-            parts.push(self.body.render(py, template, context)?);
-            context.increment_for_loop();
+        };
+        let rendered =
+            match version
+                .block
+                .render_version(py, TemplateString(&version.source), context)
+            {
+                Ok(rendered) => Ok(Cow::Owned(rendered.into_owned())),
+                // Errors point into the source of the template defining this
+                // version of the block.
+                Err(err) => Err(annotate_render_error(err, &version.source).into()),
+            };
+        context.push_block(&self.name, version);
+        rendered
+    }
+}
+
+impl Block {
+    fn render_version<'t>(
+        &self,
+        py: Python<'_>,
+        template: TemplateString<'t>,
+        context: &mut Context,
+    ) -> RenderResult<'t> {
+        if !self.uses_super {
+            return self.nodes.render(py, template, context);
         }
-        context.pop_variables();
-        context.pop_for_loop();
-        Ok(Cow::Owned(parts.join("")))
+        // `{{ block.super }}` is the next version of the block, or nothing
+        // for the last one.
+        let block_super = match context.has_block(&self.name) {
+            true => self.render(py, template, context)?.into_owned(),
+            false => String::new(),
+        };
+        let block_super = Content::String(ContentString::HtmlSafe(Cow::Owned(block_super)));
+        let block = PyDict::new(py);
+        block.set_item("super", block_super.to_py(py))?;
+        self.render_with_block(block.into_any(), py, template, context)
     }
 
-    fn render_string<'t>(
+    /// Render a block outside `{% extends %}`, where there is no parent
+    /// version for `{{ block.super }}`.
+    fn render_base<'t>(
         &self,
-        string: &str,
         py: Python<'_>,
         template: TemplateString<'t>,
         context: &mut Context,
     ) -> RenderResult<'t> {
-        if self.variables.names.len() > 1 {
-            return Err(RenderError::TupleUnpackError {
-                expected_count: self.variables.names.len(),
-                actual_count: 1,
-                expected_at: self.variables.at.into(),
-                actual_at: self.iterable.at.into(),
+        if !self.uses_super {
+            return self.nodes.render(py, template, context);
+        }
+        let block = Bound::new(py, BaseBlock)?;
+        self.render_with_block(block.into_any(), py, template, context)
+    }
+
+    fn render_with_block<'t>(
+        &self,
+        block: Bound<'_, PyAny>,
+        py: Python<'_>,
+        template: TemplateString<'t>,
+        context: &mut Context,
+    ) -> RenderResult<'t> {
+        let depth = context.scope_depth();
+        context.push_scope(vec![("block".to_string(), block)]);
+        let rendered = self.nodes.render(py, template, context);
+        context.pop_variables_to(depth);
+        rendered
+    }
+}
+
+/// The `block` variable of a template rendered without `{% extends %}`.
+#[pyclass]
+struct BaseBlock;
+
+#[pymethods]
+impl BaseBlock {
+    /// Like Django, using `{{ block.super }}` here is an error.
+    fn __getattr__(&self, name: &str) -> PyResult<()> {
+        match name {
+            "super" => Err(TemplateSyntaxError::new_err(
+                "'BlockNode' object has no attribute 'context'. Did you use {{ block.super }} in a base template?",
+            )),
+            _ => Err(PyAttributeError::new_err(name.to_string())),
+        }
+    }
+}
+
+impl Extends {
+    /// Find the parent template, like Django's `ExtendsNode.get_parent`. An
+    /// already compiled template is used directly, as is the template
+    /// wrapped by a template backend's `Template`. Otherwise the value is a
+    /// template name, loaded by the engine of the template being rendered.
+    pub(crate) fn parent_template<'py>(
+        &self,
+        py: Python<'py>,
+        template: TemplateString<'_>,
+        context: &mut Context,
+    ) -> Result<Bound<'py, Template>, PyRenderError> {
+        let parent = self.parent.resolve(
+            py,
+            template,
+            context,
+            ResolveFailures::IgnoreVariableDoesNotExist,
+        )?;
+        let parent = match parent {
+            Some(Content::Py(parent)) => parent,
+            Some(Content::String(name)) => PyString::new(py, name.as_raw()).into_any(),
+            Some(content) => content.to_py(py),
+            None => PyString::new(py, "").into_any(),
+        };
+        if !parent.is_truthy()? {
+            let mut message = format!(
+                "Invalid template name in 'extends' tag: {}.",
+                parent.repr()?
+            );
+            if matches!(
+                self.parent,
+                TagElement::Variable(_) | TagElement::ForVariable(_) | TagElement::Filter(_)
+            ) {
+                message.push_str(&format!(
+                    " Got this from the '{}' variable.",
+                    self.parent.display(template)
+                ));
             }
-            .into());
+            return Err(TemplateSyntaxError::new_err(message).into());
         }
-        let mut parts = Vec::new();
-        let mut chars: Vec<_> = string.chars().collect();
-        if self.reversed {
-            chars.reverse()
+        let parent = match parent.cast_into::<Template>() {
+            Ok(parent) => return Ok(parent),
+            Err(error) => error.into_inner(),
+        };
+        if let Ok(wrapped) = parent.getattr(intern!(py, "template"))
+            && let Ok(wrapped) = wrapped.cast_into::<Template>()
+        {
+            return Ok(wrapped);
         }
+        let Some(engine) = &context.engine else {
+            return Err(TemplateDoesNotExist::new_err(parent.str()?.unbind()).into());
+        };
+        let parent = engine.get_template(py, parent.extract()?)?;
+        Ok(Bound::new(py, parent)?)
+    }
+}
 
-        let variable = &self.variables.names[0];
-        context.push_for_loop(chars.len());
-        for (index, c) in chars.into_iter().enumerate() {
-            let c = PyString::new(py, &c.to_string());
-            context.push_variable(variable.clone(), c.into_any(), index);
-            parts.push(self.body.render(py, template, context)?);
-            context.increment_for_loop();
+/// Find the template to include, like Django's `IncludeNode`. An already
+/// compiled template is rendered directly, like Django does for anything
+/// with a `render` method. Otherwise the value is a template name, or a
+/// list of names to try in order, loaded by the engine of the template
+/// being rendered.
+fn include_template<'py>(
+    py: Python<'py>,
+    context: &Context,
+    included: Option<Content<'_, 'py>>,
+) -> PyResult<Bound<'py, Template>> {
+    let included = match included {
+        Some(Content::Py(included)) => match included.cast_into::<Template>() {
+            Ok(included) => return Ok(included),
+            Err(error) => {
+                let included = error.into_inner();
+                if included.hasattr("render")? {
+                    todo!("include a template from another engine")
+                }
+                included
+            }
+        },
+        Some(Content::String(name)) => PyString::new(py, name.as_raw()).into_any(),
+        Some(content) => content.to_py(py),
+        None => py.None().into_bound(py),
+    };
+    if !included.is_truthy()? {
+        return Err(TemplateDoesNotExist::new_err("No template names provided"));
+    }
+    let Some(engine) = &context.engine else {
+        todo!("include by template name without an engine")
+    };
+    let template = match included.cast::<PyString>() {
+        Ok(name) => engine.get_template(py, name.extract()?)?,
+        Err(_) => {
+            let names = included
+                .try_iter()?
+                .map(|name| name?.extract())
+                .collect::<PyResult<_>>()?;
+            engine.select_template(py, names)?
+        }
+    };
+    Bound::new(py, template)
+}
+
+impl Render for Include {
+    fn render<'t>(
+        &self,
+        py: Python<'_>,
+        template: TemplateString<'t>,
+        context: &mut Context,
+    ) -> RenderResult<'t> {
+        let included = self.template_name.resolve(
+            py,
+            template,
+            context,
+            ResolveFailures::IgnoreVariableDoesNotExist,
+        )?;
+        let included = include_template(py, context, included)?;
+        let included = included.borrow();
+
+        let values = resolve_variables(py, template, context, &self.variables)?;
+        // Like Django, the included template doesn't see the blocks of an
+        // `{% extends %}` being rendered around it.
+        let blocks = context.replace_blocks(HashMap::new());
+        let rendered = if self.only {
+            let mut isolated = context.isolated(py, values);
+            included.render_nodes(py, &mut isolated)
+        } else {
+            let depth = context.scope_depth();
+            context.push_scope(values);
+            let rendered = included.render_nodes(py, context);
+            context.pop_variables_to(depth);
+            rendered
+        };
+        context.replace_blocks(blocks);
+        // Errors point into the included template's source, so they need to
+        // be annotated here rather than by the including template.
+        match rendered {
+            Ok(rendered) => Ok(Cow::Owned(rendered.into_owned())),
+            Err(err) => Err(included.render_error(err).into()),
+        }
+    }
+}
+
+impl Render for BlockTranslate {
+    fn render<'t>(
+        &self,
+        py: Python<'_>,
+        _template: TemplateString<'t>,
+        context: &mut Context,
+    ) -> RenderResult<'t> {
+        let gettext = GETTEXT.import(py, "django.utils.translation", "gettext")?;
+        let message = gettext.call1((&self.message,))?;
+
+        let data = PyDict::new(py);
+        for name in &self.variables {
+            let value = match context.get(name) {
+                Some(value) => Content::Py(value.bind(py).clone()).render(context)?,
+                None => Cow::Borrowed(""),
+            };
+            data.set_item(name, value)?;
+        }
+
+        // Like Django, fall back to the untranslated message if the
+        // translation has placeholders that don't match the variables.
+        let result = match message.rem(&data) {
+            Ok(result) => result,
+            Err(error)
+                if error.is_instance_of::<PyKeyError>(py)
+                    || error.is_instance_of::<PyValueError>(py) =>
+            {
+                PyString::new(py, &self.message).rem(&data)?
+            }
+            Err(error) => return Err(error.into()),
+        };
+        let result: String = result.extract()?;
+
+        match &self.asvar {
+            Some(asvar) => {
+                let result = Content::String(ContentString::HtmlSafe(Cow::Owned(result)));
+                context.insert(asvar.clone(), result.to_py(py));
+                Ok(Cow::Borrowed(""))
+            }
+            None => Ok(Cow::Owned(result)),
         }
-        context.pop_variables();
-        context.pop_for_loop();
-        Ok(Cow::Owned(parts.join("")))
     }
 }
 
@@ -747,13 +1367,83 @@ impl Render for For {
         else {
             return self.empty.render(py, template, context);
         };
-        match iterable {
-            Content::Py(iterable) => self.render_python(&iterable, py, template, context),
-            Content::String(s) => self.render_string(s.as_raw(), py, template, context),
-            Content::Float(_) | Content::Int(_) | Content::Bool(_) => {
-                unreachable!("float, int and bool literals are not iterable")
+        let iterator = match iterable.iter(py) {
+            Ok(iterator) => iterator,
+            Err(error) => {
+                let error = error.annotate(py, self.iterable.at, "here", template);
+                return Err(error.into());
+            }
+        };
+        // Like Django, the iterable is iterated once per loop, so a
+        // single-pass iterable such as a generator is exhausted afterwards,
+        // while a `QuerySet` caches its results.
+        // Django materializes the iterable to find its length. Only do so when
+        // it's needed, so large generators can be streamed.
+        if self.reversed || self.uses_length {
+            let mut list: Vec<_> = iterator.collect();
+            if self.reversed {
+                list.reverse();
             }
+            self.render_loop(Some(list.len()), list.into_iter(), py, template, context)
+        } else {
+            self.render_loop(None, iterator, py, template, context)
+        }
+    }
+}
+
+impl For {
+    fn render_loop<'t, 'py>(
+        &self,
+        len: Option<usize>,
+        iterator: impl Iterator<Item = PyResult<Bound<'py, PyAny>>>,
+        py: Python<'py>,
+        template: TemplateString<'t>,
+        context: &mut Context,
+    ) -> RenderResult<'t> {
+        let mut iterator = iterator.peekable();
+        if iterator.peek().is_none() {
+            return self.empty.render(py, template, context);
+        }
+        context.push_for_loop(len);
+        let depth = context.scope_depth();
+        let rendered = self.render_body(iterator, py, template, context);
+        context.pop_variables_to(depth);
+        context.pop_for_loop();
+        rendered
+    }
+
+    fn render_body<'t, 'py>(
+        &self,
+        mut iterator: Peekable<impl Iterator<Item = PyResult<Bound<'py, PyAny>>>>,
+        py: Python<'py>,
+        template: TemplateString<'t>,
+        context: &mut Context,
+    ) -> RenderResult<'t> {
+        let mut parts = Vec::new();
+        let mut index = 0;
+        while let Some(values) = iterator.next() {
+            let values = match values {
+                Ok(values) => values,
+                Err(error) => {
+                    let error =
+                        error.annotate(py, self.iterable.at, "while iterating this", template);
+                    return Err(error.into());
+                }
+            };
+            context.set_for_loop_last(iterator.peek().is_none());
+            context.push_variables(
+                &self.variables.names,
+                self.variables.at,
+                values,
+                self.iterable.at,
+                index,
+                template,
+            )?;
+            parts.push(self.body.render(py, template, context)?);
+            context.increment_for_loop();
+            index += 1;
         }
+        Ok(Cow::Owned(parts.join("")))
     }
 }
 
@@ -929,3 +1619,193 @@ impl Render for SimpleBlockTag {
         Ok(store_target_var(py, context, content, &self.target_var))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use quickcheck::quickcheck;
+
+    fn string(content: &str) -> Content<'_, '_> {
+        Content::String(ContentString::String(Cow::Borrowed(content)))
+    }
+
+    #[test]
+    fn test_string_equality_ignores_safety() {
+        let safe = Content::String(ContentString::HtmlSafe(Cow::Borrowed("a")));
+        let unsafe_ = Content::String(ContentString::HtmlUnsafe(Cow::Borrowed("a")));
+        assert!(safe.eq(&unsafe_));
+        assert!(unsafe_.eq(&safe));
+        assert!(safe.eq(&string("a")));
+        assert!(!safe.ne(&unsafe_));
+        assert!(safe.lte(&unsafe_) && safe.gte(&unsafe_));
+        assert!(!safe.eq(&string("b")));
+
+        Python::initialize();
+        Python::attach(|py| {
+            let py_str = Content::Py(PyString::new(py, "a").into_any());
+            assert!(safe.eq(&py_str));
+            assert!(py_str.eq(&safe));
+        })
+    }
+
+    #[test]
+    fn test_string_number_ordering_is_false() {
+        let a = string("a");
+        for number in [Content::Int(5.into()), Content::Float(5.0)] {
+            assert!(!a.lt(&number) && !a.gt(&number));
+            assert!(!a.lte(&number) && !a.gte(&number));
+            assert!(!number.lt(&a) && !number.gt(&a));
+            assert!(!number.lte(&a) && !number.gte(&a));
+        }
+    }
+
+    #[test]
+    fn test_string_ordering_is_code_point_ordering() {
+        // Rust compares `str` by UTF-8 bytes, which orders the same as
+        // comparing code points. Python also compares strings by code point.
+        assert!(string("z").lt(&string("é")));
+        assert!(string("é").gt(&string("z")));
+        assert!(string("\u{ffff}").lt(&string("\u{1f600}")));
+        assert!(string("\u{ff61}").lte(&string("\u{10000}")));
+        assert!(string("\u{10000}").gte(&string("\u{ff61}")));
+        assert!(string("a\u{1f600}").lt(&string("b")));
+    }
+
+    #[test]
+    fn test_float_int_comparison_is_exact() {
+        let big = BigInt::from(2u64.pow(53) + 1);
+        let float = 2f64.powi(53);
+        assert!(!Content::Float(float).eq(&Content::Int(big.clone())));
+        assert!(Content::Float(float).lt(&Content::Int(big.clone())));
+        assert!(Content::Int(big.clone()).gt(&Content::Float(float)));
+        assert!(Content::Int(big.clone()).gte(&Content::Float(float)));
+        assert!(!Content::Int(big).lte(&Content::Float(float)));
+
+        let huge: BigInt = "1".repeat(400).parse().unwrap();
+        assert!(Content::Float(f64::INFINITY).gt(&Content::Int(huge.clone())));
+        assert!(Content::Float(f64::MAX).lt(&Content::Int(huge.clone())));
+        assert!(!Content::Float(f64::NAN).lte(&Content::Int(huge)));
+        assert!(Content::Float(-0.5).gt(&Content::Int(BigInt::from(-1))));
+        assert!(Content::Float(2.0).eq(&Content::Int(BigInt::from(2))));
+    }
+
+    #[test]
+    fn test_datetime_comparison() {
+        Python::initialize();
+
+        Python::attach(|py| {
+            let locals = PyDict::new(py);
+            py.run(
+                cr#"
+from datetime import date, datetime, timezone
+
+earlier = datetime(2024, 1, 1, 12)
+later = datetime(2024, 6, 1)
+day = date(2024, 1, 1)
+aware = datetime(2024, 1, 1, 12, tzinfo=timezone.utc)
+"#,
+                None,
+                Some(&locals),
+            )
+            .unwrap();
+            let get = |name| Content::Py(locals.get_item(name).unwrap().unwrap());
+            let (earlier, later, day, aware) =
+                (get("earlier"), get("later"), get("day"), get("aware"));
+
+            assert!(earlier.lt(&later) && earlier.lte(&later) && later.gt(&earlier));
+            assert!(!earlier.gte(&later) && earlier.ne(&later));
+            assert!(earlier.eq(&get("earlier")) && earlier.lte(&earlier));
+            // Python raises `TypeError` ordering these, so every ordering is
+            // false, while equality is simply false.
+            for (left, right) in [(&day, &earlier), (&earlier, &aware)] {
+                assert!(!left.lt(right) && !left.gt(right));
+                assert!(!left.lte(right) && !left.gte(right));
+                assert!(!left.eq(right) && left.ne(right));
+            }
+        })
+    }
+
+    #[test]
+    fn test_big_int_comparison_with_python_int() {
+        Python::initialize();
+
+        Python::attach(|py| {
+            let digits = "1234567890".repeat(4);
+            let literal: BigInt = digits.parse().unwrap();
+            let int = py.get_type::<PyInt>().call1((&digits,)).unwrap();
+            let larger = int.add(1).unwrap();
+
+            let literal = Content::Int(literal);
+            assert!(literal.eq(&Content::Py(int.clone())));
+            assert!(!literal.lt(&Content::Py(int.clone())));
+            assert!(literal.lt(&Content::Py(larger.clone())));
+            assert!(Content::Py(larger).gt(&literal));
+            assert!(Content::Py(int).gte(&literal));
+        })
+    }
+
+    #[test]
+    fn test_string_ordering_matches_python() {
+        Python::initialize();
+
+        fn matches(left: String, right: String) -> bool {
+            Python::attach(|py| {
+                let py_left = PyString::new(py, &left);
+                let py_lt = py_left.lt(&right).unwrap();
+                let py_gt = py_left.gt(&right).unwrap();
+                let (left, right) = (string(&left), string(&right));
+                left.lt(&right) == py_lt && left.gt(&right) == py_gt
+            })
+        }
+        quickcheck(matches as fn(String, String) -> bool)
+    }
+
+    #[test]
+    fn test_contains_non_py_container() {
+        Python::initialize();
+
+        Python::attach(|py| {
+            let int = || Content::Int(5.into());
+            let float = || Content::Float(5.0);
+            assert_eq!(int().contains(Some(int())), None);
+            assert_eq!(int().contains(Some(float())), None);
+            assert_eq!(float().contains(Some(int())), None);
+            assert_eq!(Content::Bool(true).contains(Some(int())), None);
+            assert_eq!(string("5").contains(Some(int())), None);
+            assert_eq!(int().contains(Some(string("5"))), None);
+            assert_eq!(int().contains(None), None);
+
+            let list = PyList::new(py, [5]).unwrap().into_any();
+            assert_eq!(int().contains(Some(Content::Py(list))), None);
+        })
+    }
+
+    #[test]
+    fn test_contains_py_container() {
+        Python::initialize();
+
+        Python::attach(|py| {
+            let range = py
+                .import("builtins")
+                .unwrap()
+                .getattr("range")
+                .unwrap()
+                .call1((10,))
+                .unwrap();
+            let range = Content::Py(range);
+            assert_eq!(range.contains(Some(Content::Int(5.into()))), Some(true));
+            assert_eq!(range.contains(Some(Content::Int(10.into()))), Some(false));
+            assert_eq!(range.contains(Some(Content::Float(5.0))), Some(true));
+            assert_eq!(range.contains(Some(Content::Float(5.5))), Some(false));
+            assert_eq!(range.contains(None), Some(false));
+
+            let dict = PyDict::new(py);
+            dict.set_item(1, "one").unwrap();
+            let dict = Content::Py(dict.into_any());
+            assert_eq!(dict.contains(Some(Content::Int(1.into()))), Some(true));
+            assert_eq!(dict.contains(Some(Content::Float(1.0))), Some(true));
+            assert_eq!(dict.contains(Some(string("one"))), Some(false));
+        })
+    }
+}
@@ -0,0 +1,170 @@
+//! Helpers for rendering dates and times the way Django does.
+//!
+//! Django formats aware datetimes in the currently activated timezone when
+//! `settings.USE_TZ` is enabled. The `now` tag and the date/time filters share
+//! this logic so they always agree on the zone being used.
+use pyo3::prelude::*;
+use pyo3::sync::PyOnceLock;
+use pyo3::types::IntoPyDict;
+
+use super::types::Context;
+
+static DATE: PyOnceLock<Py<PyAny>> = PyOnceLock::new();
+static DATE_FORMAT: PyOnceLock<Py<PyAny>> = PyOnceLock::new();
+static DATETIME: PyOnceLock<Py<PyAny>> = PyOnceLock::new();
+static GET_CURRENT_TIMEZONE: PyOnceLock<Py<PyAny>> = PyOnceLock::new();
+static GET_CURRENT_TIMEZONE_NAME: PyOnceLock<Py<PyAny>> = PyOnceLock::new();
+static GETTEXT: PyOnceLock<Py<PyAny>> = PyOnceLock::new();
+static HUMANIZE: PyOnceLock<Py<PyModule>> = PyOnceLock::new();
+static IS_AWARE: PyOnceLock<Py<PyAny>> = PyOnceLock::new();
+static NGETTEXT: PyOnceLock<Py<PyAny>> = PyOnceLock::new();
+static OVERRIDE: PyOnceLock<Py<PyAny>> = PyOnceLock::new();
+static SETTINGS: PyOnceLock<Py<PyAny>> = PyOnceLock::new();
+static TIMESINCE: PyOnceLock<Py<PyAny>> = PyOnceLock::new();
+static TIMEUNTIL: PyOnceLock<Py<PyAny>> = PyOnceLock::new();
+
+/// The timezone values should be displayed in, or `None` when
+/// `settings.USE_TZ` is disabled.
+///
+/// Inside a `{% timezone %}` tag this is the timezone it activated.
+pub fn current_timezone<'py>(
+    py: Python<'py>,
+    context: &Context,
+) -> PyResult<Option<Bound<'py, PyAny>>> {
+    let settings = SETTINGS.import(py, "django.conf", "settings")?;
+    if !settings.getattr("USE_TZ")?.is_truthy()? {
+        return Ok(None);
+    }
+    if let Some(timezone) = context.timezone(py) {
+        return Ok(Some(timezone.clone()));
+    }
+    let get_current_timezone =
+        GET_CURRENT_TIMEZONE.import(py, "django.utils.timezone", "get_current_timezone")?;
+    Ok(Some(get_current_timezone.call0()?))
+}
+
+/// The name of the active timezone, like Django's `get_current_timezone_name`.
+pub fn current_timezone_name<'py>(py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+    let get_current_timezone_name = GET_CURRENT_TIMEZONE_NAME.import(
+        py,
+        "django.utils.timezone",
+        "get_current_timezone_name",
+    )?;
+    get_current_timezone_name.call0()
+}
+
+/// Activate `timezone` with Django's `timezone.override`, returning the
+/// override, which must be exited afterwards, and the activated timezone.
+pub fn enter_override<'py>(
+    py: Python<'py>,
+    timezone: &Bound<'py, PyAny>,
+) -> PyResult<(Bound<'py, PyAny>, Bound<'py, PyAny>)> {
+    let timezone_override = OVERRIDE
+        .import(py, "django.utils.timezone", "override")?
+        .call1((timezone,))?;
+    timezone_override.call_method0("__enter__")?;
+    let get_current_timezone =
+        GET_CURRENT_TIMEZONE.import(py, "django.utils.timezone", "get_current_timezone")?;
+    match get_current_timezone.call0() {
+        Ok(timezone) => Ok((timezone_override, timezone)),
+        Err(error) => {
+            exit_override(py, &timezone_override)?;
+            Err(error)
+        }
+    }
+}
+
+/// Restore the timezone that was active before [`enter_override`].
+pub fn exit_override(py: Python<'_>, timezone_override: &Bound<'_, PyAny>) -> PyResult<()> {
+    timezone_override.call_method1("__exit__", (py.None(), py.None(), py.None()))?;
+    Ok(())
+}
+
+/// The current datetime in the timezone given by [`current_timezone`].
+pub fn now<'py>(py: Python<'py>, context: &Context) -> PyResult<Bound<'py, PyAny>> {
+    let datetime = DATETIME.import(py, "datetime", "datetime")?;
+    datetime.call_method1("now", (current_timezone(py, context)?,))
+}
+
+/// Format `value` like Django's `date` filter, accepting either format
+/// specifiers or the name of a format setting such as `SHORT_DATE_FORMAT`.
+pub fn format(py: Python<'_>, value: &Bound<'_, PyAny>, format: &str) -> PyResult<String> {
+    let date_format = DATE_FORMAT.import(py, "django.utils.formats", "date_format")?;
+    date_format.call1((value, format))?.extract()
+}
+
+/// Describe how far `value` is from now like Django's `naturaltime` filter,
+/// or `None` if `value` isn't a date.
+///
+/// The current time comes from the `datetime` the humanize library imported,
+/// so patching it there affects both template engines.
+pub fn naturaltime(py: Python<'_>, value: &Bound<'_, PyAny>) -> PyResult<Option<String>> {
+    if !value.is_instance(DATE.import(py, "datetime", "date")?)? {
+        return Ok(None);
+    }
+    let humanize = HUMANIZE.get_or_try_init(py, || {
+        PyModule::import(py, "django.contrib.humanize.templatetags.humanize").map(Bound::unbind)
+    })?;
+    let humanize = humanize.bind(py);
+    let is_aware = IS_AWARE.import(py, "django.utils.timezone", "is_aware")?;
+    let tz = match is_aware.call1((value,))?.is_truthy()? {
+        true => Some(humanize.getattr("timezone")?.getattr("utc")?),
+        false => None,
+    };
+    let now = humanize.getattr("datetime")?.call_method1("now", (tz,))?;
+
+    let past = value.lt(&now)?;
+    let delta = match past {
+        true => now.sub(value)?,
+        false => value.sub(&now)?,
+    };
+    let days: i64 = delta.getattr("days")?.extract()?;
+    let seconds: i64 = delta.getattr("seconds")?.extract()?;
+
+    let formatter = humanize.getattr("NaturalTimeFormatter")?;
+    let ngettext = NGETTEXT.import(py, "django.utils.translation", "ngettext")?;
+    let count = |singular: &str, plural: &str, count: i64| -> PyResult<String> {
+        let message: String = ngettext.call1((singular, plural, count))?.extract()?;
+        Ok(message.replace("%(count)s", &count.to_string()))
+    };
+    if days != 0 {
+        let gettext = GETTEXT.import(py, "django.utils.translation", "gettext")?;
+        let (function, substrings, message) = match past {
+            true => (
+                TIMESINCE.import(py, "django.utils.timesince", "timesince")?,
+                "past_substrings",
+                "%(delta)s ago",
+            ),
+            false => (
+                TIMEUNTIL.import(py, "django.utils.timesince", "timeuntil")?,
+                "future_substrings",
+                "%(delta)s from now",
+            ),
+        };
+        let kwargs = [("time_strings", formatter.getattr(substrings)?)].into_py_dict(py)?;
+        let delta: String = function.call((value, now), Some(&kwargs))?.extract()?;
+        let message: String = gettext.call1((message,))?.extract()?;
+        return Ok(Some(message.replace("%(delta)s", &delta)));
+    }
+    let natural = match (past, seconds) {
+        (_, 0) => {
+            let gettext = GETTEXT.import(py, "django.utils.translation", "gettext")?;
+            gettext.call1(("now",))?.extract()?
+        }
+        (true, 1..60) => count("a second ago", "%(count)s seconds ago", seconds)?,
+        (true, 60..3600) => count("a minute ago", "%(count)s minutes ago", seconds / 60)?,
+        (true, _) => count("an hour ago", "%(count)s hours ago", seconds / 3600)?,
+        (false, 1..60) => count("a second from now", "%(count)s seconds from now", seconds)?,
+        (false, 60..3600) => count(
+            "a minute from now",
+            "%(count)s minutes from now",
+            seconds / 60,
+        )?,
+        (false, _) => count(
+            "an hour from now",
+            "%(count)s hours from now",
+            seconds / 3600,
+        )?,
+    };
+    Ok(Some(natural))
+}
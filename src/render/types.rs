@@ -4,6 +4,7 @@ use std::collections::HashMap;
 use std::collections::HashSet;
 use std::iter::zip;
 use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
 use html_escape::encode_quoted_attribute;
 use num_bigint::{BigInt, ToBigInt};
@@ -11,16 +12,20 @@ use pyo3::exceptions::{PyAttributeError, PyKeyError, PyTypeError};
 use pyo3::intern;
 use pyo3::prelude::*;
 use pyo3::sync::MutexExt;
-use pyo3::types::{PyBool, PyDict, PyInt, PyString, PyType};
+use pyo3::types::{PyBool, PyDict, PyInt, PyIterator, PyString, PyType};
 
 use crate::error::{AnnotatePyErr, PyRenderError, RenderError};
+use crate::parse::Block;
+use crate::template::django_rusty_templates::EngineRef;
 use crate::types::TemplateString;
 use crate::utils::PyResultMethods;
 
 #[derive(Debug, Clone)]
 pub struct ForLoop {
     count: usize,
-    len: usize,
+    /// `None` when a generator is being consumed lazily.
+    len: Option<usize>,
+    last: bool,
 }
 
 impl ForLoop {
@@ -32,12 +37,17 @@ impl ForLoop {
         self.count + 1
     }
 
+    fn len(&self) -> usize {
+        self.len
+            .expect("Loops using their length are always materialized")
+    }
+
     pub fn rev_counter(&self) -> usize {
-        self.len - self.count
+        self.len() - self.count
     }
 
     pub fn rev_counter0(&self) -> usize {
-        self.len - self.count - 1
+        self.len() - self.count - 1
     }
 
     pub fn first(&self) -> bool {
@@ -45,17 +55,36 @@ impl ForLoop {
     }
 
     pub fn last(&self) -> bool {
-        self.count + 1 == self.len
+        self.last
     }
 }
 
+/// A version of a `{% block %}`, with the source of the template defining it.
+#[derive(Clone, Debug)]
+pub struct BlockSource {
+    pub source: Arc<str>,
+    pub block: Arc<Block>,
+}
+
 #[derive(Debug, Default)]
 pub struct Context {
     context: HashMap<String, Vec<Py<PyAny>>>,
     loops: Vec<ForLoop>,
     pub request: Option<Py<PyAny>>,
     pub autoescape: bool,
+    /// Whether undefined variables raise `VariableDoesNotExist` instead of
+    /// resolving to nothing.
+    pub strict_variables: bool,
     names: Vec<HashSet<String>>,
+    timings: Option<Vec<Duration>>,
+    /// For each block name, the versions collected by `{% extends %}`, with
+    /// the most derived last.
+    blocks: HashMap<String, Vec<BlockSource>>,
+    /// The timezone activated by the innermost `{% timezone %}` tag.
+    timezone: Option<Py<PyAny>>,
+    /// The engine of the template being rendered, for loading templates by
+    /// name.
+    pub engine: Option<EngineRef>,
 }
 
 impl Context {
@@ -69,8 +98,13 @@ impl Context {
             request,
             context,
             autoescape,
+            strict_variables: false,
             loops: Vec::new(),
             names: Vec::new(),
+            timings: None,
+            blocks: HashMap::new(),
+            timezone: None,
+            engine: None,
         }
     }
 
@@ -83,11 +117,117 @@ impl Context {
                 .map(|(k, v)| (k.clone(), v.iter().map(|v| v.clone_ref(py)).collect()))
                 .collect(),
             autoescape: self.autoescape,
+            strict_variables: self.strict_variables,
             loops: self.loops.clone(),
             names: self.names.clone(),
+            timings: self.timings.clone(),
+            blocks: self.blocks.clone(),
+            timezone: self.timezone.as_ref().map(|tz| tz.clone_ref(py)),
+            engine: self.engine.clone(),
+        }
+    }
+
+    /// Build a context containing only `variables`, keeping the request and
+    /// autoescape setting. Used by `{% include ... only %}`.
+    pub fn isolated(&self, py: Python<'_>, variables: Vec<(String, Bound<'_, PyAny>)>) -> Self {
+        let mut context = HashMap::from([
+            ("None".to_string(), py.None()),
+            ("True".to_string(), PyBool::new(py, true).to_owned().into()),
+            (
+                "False".to_string(),
+                PyBool::new(py, false).to_owned().into(),
+            ),
+        ]);
+        context.extend(
+            variables
+                .into_iter()
+                .map(|(name, value)| (name, value.unbind())),
+        );
+        let request = self.request.as_ref().map(|request| request.clone_ref(py));
+        let mut isolated = Self::new(context, request, self.autoescape);
+        isolated.strict_variables = self.strict_variables;
+        isolated.timezone = self.timezone.as_ref().map(|tz| tz.clone_ref(py));
+        isolated.engine = self.engine.clone();
+        isolated
+    }
+
+    /// Add a template's blocks beneath the versions already collected, like
+    /// Django's `BlockContext.add_blocks`.
+    pub fn add_blocks(&mut self, source: &Arc<str>, blocks: &HashMap<String, Arc<Block>>) {
+        for (name, block) in blocks {
+            let block = BlockSource {
+                source: source.clone(),
+                block: block.clone(),
+            };
+            self.blocks
+                .entry(name.clone())
+                .or_default()
+                .insert(0, block);
+        }
+    }
+
+    /// Take the most derived version of a block, to render in its place.
+    pub fn pop_block(&mut self, name: &str) -> Option<BlockSource> {
+        self.blocks.get_mut(name)?.pop()
+    }
+
+    pub fn push_block(&mut self, name: &str, block: BlockSource) {
+        self.blocks
+            .get_mut(name)
+            .expect("blocks are pushed back after being popped")
+            .push(block);
+    }
+
+    pub fn has_block(&self, name: &str) -> bool {
+        self.blocks
+            .get(name)
+            .is_some_and(|blocks| !blocks.is_empty())
+    }
+
+    /// Whether `{% extends %}` collected any blocks, like Django's
+    /// `BlockContext` being set.
+    pub fn in_extends(&self) -> bool {
+        !self.blocks.is_empty()
+    }
+
+    /// Swap out the collected blocks, so an included template only sees its
+    /// own.
+    pub fn replace_blocks(
+        &mut self,
+        blocks: HashMap<String, Vec<BlockSource>>,
+    ) -> HashMap<String, Vec<BlockSource>> {
+        std::mem::replace(&mut self.blocks, blocks)
+    }
+
+    pub fn timezone<'py>(&self, py: Python<'py>) -> Option<&Bound<'py, PyAny>> {
+        self.timezone.as_ref().map(|timezone| timezone.bind(py))
+    }
+
+    /// Swap out the timezone set by `{% timezone %}`, returning the previous
+    /// one so it can be restored at the end of the block.
+    pub fn replace_timezone(&mut self, timezone: Option<Py<PyAny>>) -> Option<Py<PyAny>> {
+        std::mem::replace(&mut self.timezone, timezone)
+    }
+
+    /// Start recording how long each top-level node takes to render.
+    pub fn enable_timings(&mut self) {
+        self.timings = Some(Vec::new());
+    }
+
+    pub fn timings_enabled(&self) -> bool {
+        self.timings.is_some()
+    }
+
+    pub fn record_timing(&mut self, duration: Duration) {
+        if let Some(timings) = &mut self.timings {
+            timings.push(duration);
         }
     }
 
+    pub fn take_timings(&mut self) -> Vec<Duration> {
+        self.timings.take().unwrap_or_default()
+    }
+
     pub fn get(&self, key: &str) -> Option<&Py<PyAny>> {
         self.context.get(key)?.last()
     }
@@ -141,11 +281,10 @@ impl Context {
         template: TemplateString<'_>,
     ) -> Result<(), PyRenderError> {
         let replace = index != 0;
-        if !replace {
-            let names_set = names.iter().cloned().collect();
-            self.names.push(names_set);
-        }
         if names.len() == 1 {
+            if !replace {
+                self.names.push(HashSet::from([names[0].clone()]));
+            }
             self._insert(names[0].clone(), values, replace);
         } else {
             let py = values.py();
@@ -172,6 +311,11 @@ impl Context {
                 }
             };
             if names.len() == values.len() {
+                // Only start a new scope once unpacking has succeeded, so an error
+                // never leaves a scope with missing variables behind.
+                if !replace {
+                    self.names.push(names.iter().cloned().collect());
+                }
                 for (name, value) in zip(names, values) {
                     self._insert(name.clone(), value, replace);
                 }
@@ -188,6 +332,16 @@ impl Context {
         Ok(())
     }
 
+    /// Push a new scope, shadowing any existing values of these variables
+    /// until it is popped.
+    pub fn push_scope(&mut self, variables: Vec<(String, Bound<'_, PyAny>)>) {
+        self.names
+            .push(variables.iter().map(|(name, _)| name.clone()).collect());
+        for (name, value) in variables {
+            self._insert(name, value, false);
+        }
+    }
+
     fn _pop_variable(&mut self, name: &str) {
         let values = self
             .context
@@ -204,8 +358,33 @@ impl Context {
         }
     }
 
-    pub fn push_for_loop(&mut self, len: usize) {
-        self.loops.push(ForLoop { count: 0, len })
+    pub fn scope_depth(&self) -> usize {
+        self.names.len()
+    }
+
+    /// Pop every scope pushed since `scope_depth` returned `depth`. Unlike
+    /// `pop_variables`, this is safe to call when no scope was pushed, such as
+    /// when rendering stops early with an error.
+    pub fn pop_variables_to(&mut self, depth: usize) {
+        while self.names.len() > depth {
+            self.pop_variables();
+        }
+    }
+
+    pub fn push_for_loop(&mut self, len: Option<usize>) {
+        self.loops.push(ForLoop {
+            count: 0,
+            len,
+            last: false,
+        })
+    }
+
+    pub fn set_for_loop_last(&mut self, last: bool) {
+        let for_loop = self
+            .loops
+            .last_mut()
+            .expect("Called within an active for loop");
+        for_loop.last = last
     }
 
     pub fn increment_for_loop(&mut self) {
@@ -229,7 +408,9 @@ impl Context {
 
     pub fn render_for_loop(&self, py: Python<'_>, depth: usize) -> String {
         let mut forloop_dict = PyDict::new(py);
-        for forloop in self.loops.iter().rev().take(self.loops.len() - depth) {
+        // Build from the outermost loop in, so each loop's dict becomes the
+        // `parentloop` of the next.
+        for forloop in self.loops.iter().take(self.loops.len() - depth) {
             let dict = PyDict::new(py);
             dict.set_item("parentloop", forloop_dict)
                 .expect("Can always set a str: dict key/value");
@@ -372,29 +553,69 @@ impl<'t, 'py> ContentString<'t> {
     }
 }
 
+/// Convert `value` to a Rust `String` like Python's `str()`.
+///
+/// Python strings can contain lone surrogates, which aren't valid UTF-8, so
+/// each of these is replaced with U+FFFD rather than raising.
+fn python_str(value: &Bound<'_, PyAny>) -> PyResult<String> {
+    let value = value.str()?;
+    if let Ok(value) = value.to_str() {
+        return Ok(value.to_string());
+    }
+    value
+        .call_method1(intern!(value.py(), "encode"), ("utf-16", "surrogatepass"))?
+        .call_method1(intern!(value.py(), "decode"), ("utf-16", "replace"))?
+        .extract()
+}
+
 fn resolve_python<'t>(value: Bound<'_, PyAny>, context: &Context) -> PyResult<ContentString<'t>> {
     if !context.autoescape {
-        return Ok(ContentString::String(
-            value.str()?.extract::<String>()?.into(),
-        ));
+        return Ok(ContentString::String(python_str(&value)?.into()));
     };
-    let py = value.py();
-
+    // Like Django, objects are converted with `str` first, so their own
+    // `__html__` is only used if `__str__` returns something with one.
     let value = match value.is_instance_of::<PyString>() {
         true => value,
         false => value.str()?.into_any(),
     };
+    conditional_escape(&value)
+}
+
+/// Convert `value` like Django's `conditional_escape`: objects with an
+/// `__html__` method are rendered with it and treated as safe, anything else
+/// is escaped.
+pub fn conditional_escape<'t>(value: &Bound<'_, PyAny>) -> PyResult<ContentString<'t>> {
+    let py = value.py();
     Ok(
         match value
             .getattr(intern!(py, "__html__"))
             .ok_or_isinstance_of::<PyAttributeError>(py)?
         {
-            Ok(html) => ContentString::HtmlSafe(html.call0()?.extract::<String>()?.into()),
-            Err(_) => ContentString::HtmlUnsafe(value.str()?.extract::<String>()?.into()),
+            Ok(html) => ContentString::HtmlSafe(python_str(&html.call0()?)?.into()),
+            Err(_) => ContentString::HtmlUnsafe(python_str(value)?.into()),
         },
     )
 }
 
+pub enum ContentIter<'py> {
+    Py(Bound<'py, PyIterator>),
+    Chars(Python<'py>, std::vec::IntoIter<char>),
+}
+
+impl<'py> Iterator for ContentIter<'py> {
+    type Item = PyResult<Bound<'py, PyAny>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            Self::Py(iterator) => iterator.next(),
+            Self::Chars(py, chars) => {
+                let c = chars.next()?;
+                Some(Ok(PyString::new(*py, &c.to_string()).into_any()))
+            }
+        }
+    }
+}
+
 #[derive(Debug, IntoPyObject)]
 pub enum Content<'t, 'py> {
     Py(Bound<'py, PyAny>),
@@ -406,14 +627,7 @@ pub enum Content<'t, 'py> {
 
 impl<'t, 'py> Content<'t, 'py> {
     pub fn render(self, context: &Context) -> PyResult<Cow<'t, str>> {
-        Ok(match self {
-            Self::Py(content) => resolve_python(content, context)?.content(),
-            Self::String(content) => content.content(),
-            Self::Float(content) => content.to_string().into(),
-            Self::Int(content) => content.to_string().into(),
-            Self::Bool(true) => "True".into(),
-            Self::Bool(false) => "False".into(),
-        })
+        Ok(self.resolve_string(context)?.content())
     }
 
     pub fn resolve_string(self, context: &Context) -> PyResult<ContentString<'t>> {
@@ -448,6 +662,19 @@ impl<'t, 'py> Content<'t, 'py> {
         }
     }
 
+    pub fn iter(&self, py: Python<'py>) -> PyResult<ContentIter<'py>> {
+        match self {
+            Self::Py(content) => Ok(ContentIter::Py(content.try_iter()?)),
+            Self::String(content) => {
+                let chars: Vec<_> = content.as_raw().chars().collect();
+                Ok(ContentIter::Chars(py, chars.into_iter()))
+            }
+            Self::Int(_) => Err(PyTypeError::new_err("'int' object is not iterable")),
+            Self::Float(_) => Err(PyTypeError::new_err("'float' object is not iterable")),
+            Self::Bool(_) => Err(PyTypeError::new_err("'bool' object is not iterable")),
+        }
+    }
+
     pub fn to_py(&self, py: Python<'py>) -> Bound<'py, PyAny> {
         match self {
             Self::Py(object) => object.clone(),
@@ -519,3 +746,93 @@ impl<'t, 'py> IntoOwnedContent<'t, 'py> for Cow<'t, str> {
         Content::String(ContentString::String(self))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use pyo3::types::PyList;
+
+    #[test]
+    fn test_content_render_bool() {
+        Python::initialize();
+
+        Python::attach(|py| {
+            let context = Context::new(HashMap::new(), None, false);
+            for (value, expected) in [(true, "True"), (false, "False")] {
+                let native = Content::Bool(value).render(&context).unwrap();
+                let object = PyBool::new(py, value).to_owned().into_any();
+                let python = Content::Py(object).render(&context).unwrap();
+                assert_eq!(native, expected);
+                assert_eq!(python, expected);
+            }
+        })
+    }
+
+    #[test]
+    fn test_content_iter_py() {
+        Python::initialize();
+
+        Python::attach(|py| {
+            let list = PyList::new(py, [1, 2, 3]).unwrap().into_any();
+            let content = Content::Py(list);
+            let items: Vec<i64> = content
+                .iter(py)
+                .unwrap()
+                .map(|item| item.unwrap().extract().unwrap())
+                .collect();
+            assert_eq!(items, [1, 2, 3]);
+        })
+    }
+
+    #[test]
+    fn test_content_iter_string() {
+        Python::initialize();
+
+        Python::attach(|py| {
+            let content = "añb".as_content();
+            let items: Vec<String> = content
+                .iter(py)
+                .unwrap()
+                .map(|item| item.unwrap().extract().unwrap())
+                .collect();
+            assert_eq!(items, ["a", "ñ", "b"]);
+        })
+    }
+
+    #[test]
+    fn test_content_iter_not_iterable() {
+        Python::initialize();
+
+        Python::attach(|py| {
+            for (content, expected) in [
+                (
+                    Content::Int(1.into()),
+                    "TypeError: 'int' object is not iterable",
+                ),
+                (
+                    Content::Float(1.5),
+                    "TypeError: 'float' object is not iterable",
+                ),
+                (
+                    Content::Bool(true),
+                    "TypeError: 'bool' object is not iterable",
+                ),
+            ] {
+                let error = content.iter(py).err().unwrap();
+                assert_eq!(error.to_string(), expected);
+            }
+        })
+    }
+
+    #[test]
+    fn test_content_iter_py_not_iterable() {
+        Python::initialize();
+
+        Python::attach(|py| {
+            let content = Content::Py(1i64.into_pyobject(py).unwrap().into_any());
+            let error = content.iter(py).err().unwrap();
+            assert_eq!(error.to_string(), "TypeError: 'int' object is not iterable");
+        })
+    }
+}
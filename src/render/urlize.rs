@@ -0,0 +1,328 @@
+//! A port of Django's `Urlizer` from `django.utils.html`, used by the `urlize`
+//! family of filters.
+//!
+//! The word scanning and punctuation trimming happen in Rust. URL quoting is
+//! delegated to Python so that the generated `href`s match Django exactly.
+use std::borrow::Cow;
+use std::iter::zip;
+use std::sync::LazyLock;
+
+use html_escape::encode_quoted_attribute;
+use pyo3::prelude::*;
+use pyo3::sync::PyOnceLock;
+use regex::Regex;
+
+static SMART_URLQUOTE: PyOnceLock<Py<PyAny>> = PyOnceLock::new();
+static UNESCAPE: PyOnceLock<Py<PyAny>> = PyOnceLock::new();
+static QUOTE: PyOnceLock<Py<PyAny>> = PyOnceLock::new();
+
+const MAX_URL_LENGTH: usize = 2048;
+const TRAILING_PUNCTUATION_CHARS: &[char] = &['.', ',', ':', ';', '!'];
+const TRAILING_PUNCTUATION_CHARS_NO_SEMICOLON: &[char] = &['.', ',', ':', '!'];
+const WRAPPING_PUNCTUATION: [(char, char); 2] = [('(', ')'), ('[', ']')];
+const WRAPPING_PUNCTUATION_OPENINGS: &[char] = &['(', '['];
+
+static WORD_SPLIT_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"[\s<>"']+"#).expect("Static string will never panic"));
+
+static SIMPLE_URL_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)^https?://\[?\w").expect("Static string will never panic"));
+
+// Django's version of this regex starts with a `(?!http)` lookahead, which the
+// `regex` crate doesn't support. We check for that prefix separately instead.
+static SIMPLE_URL_2_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)^\w[^@]+\.(com|edu|gov|int|mil|net|org)($|/.*)$")
+        .expect("Static string will never panic")
+});
+
+fn is_simple_url_2(middle: &str) -> bool {
+    let lower = middle.to_lowercase();
+    lower.starts_with("www.") || (!lower.starts_with("http") && SIMPLE_URL_2_RE.is_match(middle))
+}
+
+fn is_email_simple(value: &str) -> bool {
+    let Some((_, domain)) = value.split_once('@') else {
+        return false;
+    };
+    if value.starts_with('@') || value.ends_with('@') || domain.contains('@') {
+        return false;
+    }
+    // Max length for domain name labels is 63 characters per RFC 1034.
+    if domain.chars().count() > 63 {
+        return false;
+    }
+    domain.contains('.') && !domain.starts_with('.')
+}
+
+fn escape(content: &str) -> Cow<'_, str> {
+    encode_quoted_attribute(content)
+}
+
+fn unescape(py: Python<'_>, content: &str) -> PyResult<String> {
+    let unescape = UNESCAPE.import(py, "html", "unescape")?;
+    unescape.call1((content,))?.extract()
+}
+
+fn smart_urlquote(py: Python<'_>, url: &str) -> PyResult<String> {
+    let smart_urlquote = SMART_URLQUOTE.import(py, "django.utils.html", "smart_urlquote")?;
+    smart_urlquote.call1((url,))?.extract()
+}
+
+fn quote(py: Python<'_>, content: &str) -> PyResult<String> {
+    let quote = QUOTE.import(py, "urllib.parse", "quote")?;
+    quote.call1((content, ""))?.extract()
+}
+
+/// Split `word` into leading punctuation, the potential URL and trailing
+/// punctuation.
+fn trim_punctuation<'a>(py: Python<'_>, word: &'a str) -> PyResult<(&'a str, &'a str, String)> {
+    let mut middle = word.trim_start_matches(WRAPPING_PUNCTUATION_OPENINGS);
+    let lead = &word[..word.len() - middle.len()];
+    let mut trail = String::new();
+
+    let mut counts: Vec<_> = WRAPPING_PUNCTUATION
+        .iter()
+        .map(|(opening, closing)| {
+            (
+                middle.matches(*opening).count(),
+                middle.matches(*closing).count(),
+            )
+        })
+        .collect();
+    let mut trimmed_something = true;
+    while trimmed_something && !middle.is_empty() {
+        trimmed_something = false;
+        for ((_, closing), (openings, closings)) in zip(WRAPPING_PUNCTUATION, counts.iter_mut()) {
+            if openings < closings && middle.ends_with(closing) {
+                let strip = *closings - *openings;
+                // Like Django, slice off `strip` characters, even if they aren't
+                // all closing punctuation.
+                let split = middle
+                    .char_indices()
+                    .rev()
+                    .nth(strip - 1)
+                    .map_or(0, |(index, _)| index);
+                trail = format!("{}{trail}", &middle[split..]);
+                middle = &middle[..split];
+                trimmed_something = true;
+                *closings -= strip;
+            }
+        }
+
+        let amp = middle.rfind('&');
+        let rstripped = match amp {
+            None => middle.trim_end_matches(TRAILING_PUNCTUATION_CHARS),
+            Some(_) => middle.trim_end_matches(TRAILING_PUNCTUATION_CHARS_NO_SEMICOLON),
+        };
+        if rstripped != middle {
+            trail = format!("{}{trail}", &middle[rstripped.len()..]);
+            middle = rstripped;
+            trimmed_something = true;
+        }
+
+        if middle.ends_with(';') {
+            // Only strip if not part of an HTML entity.
+            let potential_entity = match amp {
+                Some(amp) => &middle[amp..],
+                None => &middle[middle.len() - 1..],
+            };
+            let escaped = unescape(py, potential_entity)?;
+            if escaped == potential_entity || escaped.ends_with(';') {
+                let rstripped = middle.trim_end_matches(TRAILING_PUNCTUATION_CHARS);
+                let trail_start = rstripped.len();
+                let trailing_semicolons = middle.len() - middle.trim_end_matches(';').len();
+                if amp.is_some() && trailing_semicolons > 1 {
+                    // Leave up to most recent semicolon as might be an entity.
+                    let recent_semicolon = middle[trail_start..]
+                        .find(';')
+                        .expect("middle ends with a semicolon");
+                    let semicolon_index = recent_semicolon + trail_start + 1;
+                    trail = format!("{}{trail}", &middle[semicolon_index..]);
+                    middle = &middle[..semicolon_index];
+                } else {
+                    trail = format!("{}{trail}", &middle[trail_start..]);
+                    middle = rstripped;
+                }
+                trimmed_something = true;
+            }
+        }
+    }
+    Ok((lead, middle, trail))
+}
+
+fn trim_url(url: &str, limit: Option<usize>) -> Cow<'_, str> {
+    match limit {
+        Some(limit) if url.chars().count() > limit => {
+            let truncated: String = url.chars().take(limit.saturating_sub(1)).collect();
+            Cow::Owned(format!("{truncated}…"))
+        }
+        _ => Cow::Borrowed(url),
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Urlizer {
+    pub trim_url_limit: Option<usize>,
+    pub nofollow: bool,
+    pub autoescape: bool,
+}
+
+impl Urlizer {
+    /// Convert any URLs or email addresses in `text` into links.
+    ///
+    /// `safe_input` should be true when `text` was already marked safe, in
+    /// which case it won't be escaped.
+    pub fn urlize(&self, py: Python<'_>, text: &str, safe_input: bool) -> PyResult<String> {
+        let mut urlized = String::with_capacity(text.len());
+        let mut last = 0;
+        for separator in WORD_SPLIT_RE.find_iter(text) {
+            urlized.push_str(&self.handle_word(py, &text[last..separator.start()], safe_input)?);
+            urlized.push_str(&self.handle_word(py, separator.as_str(), safe_input)?);
+            last = separator.end();
+        }
+        urlized.push_str(&self.handle_word(py, &text[last..], safe_input)?);
+        Ok(urlized)
+    }
+
+    fn handle_word<'a>(
+        &self,
+        py: Python<'_>,
+        word: &'a str,
+        safe_input: bool,
+    ) -> PyResult<Cow<'a, str>> {
+        if word.contains(['.', '@', ':']) {
+            let (lead, middle, trail) = trim_punctuation(py, word)?;
+            let mut nofollow_attr = if self.nofollow {
+                " rel=\"nofollow\""
+            } else {
+                ""
+            };
+            let url = if middle.len() <= MAX_URL_LENGTH && SIMPLE_URL_RE.is_match(middle) {
+                Some(smart_urlquote(py, &unescape(py, middle)?)?)
+            } else if middle.len() <= MAX_URL_LENGTH && is_simple_url_2(middle) {
+                let url = format!("http://{}", unescape(py, middle)?);
+                Some(smart_urlquote(py, &url)?)
+            } else if !middle.contains(':') && is_email_simple(middle) {
+                let (local, domain) = middle
+                    .rsplit_once('@')
+                    .expect("is_email_simple checks for an @");
+                nofollow_attr = "";
+                Some(format!(
+                    "mailto:{}@{}",
+                    quote(py, local)?,
+                    quote(py, domain)?
+                ))
+            } else {
+                None
+            };
+            if let Some(url) = url {
+                let trimmed = trim_url(middle, self.trim_url_limit);
+                let (lead, trimmed, trail) = if self.autoescape && !safe_input {
+                    (escape(lead), escape(&trimmed).into_owned(), escape(&trail))
+                } else {
+                    (
+                        Cow::Borrowed(lead),
+                        trimmed.into_owned(),
+                        Cow::Borrowed(trail.as_str()),
+                    )
+                };
+                let href = escape(&url);
+                return Ok(Cow::Owned(format!(
+                    "{lead}<a href=\"{href}\"{nofollow_attr}>{trimmed}</a>{trail}"
+                )));
+            }
+        }
+        if !safe_input && self.autoescape {
+            return Ok(escape(word));
+        }
+        Ok(Cow::Borrowed(word))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_email_simple() {
+        assert!(is_email_simple("lily@example.com"));
+        assert!(!is_email_simple("@example.com"));
+        assert!(!is_email_simple("lily@"));
+        assert!(!is_email_simple("lily@example"));
+        assert!(!is_email_simple("lily@.example.com"));
+        assert!(!is_email_simple("lily@bryony@example.com"));
+    }
+
+    #[test]
+    fn test_is_simple_url_2() {
+        assert!(is_simple_url_2("www.example.com"));
+        assert!(is_simple_url_2("example.com"));
+        assert!(is_simple_url_2("example.org/path"));
+        assert!(!is_simple_url_2("example.co.uk"));
+        assert!(!is_simple_url_2("http.example.com"));
+    }
+
+    #[test]
+    fn test_trim_punctuation() {
+        Python::initialize();
+
+        Python::attach(|py| {
+            assert_eq!(
+                trim_punctuation(py, "example.com.").unwrap(),
+                ("", "example.com", ".".to_string())
+            );
+            assert_eq!(
+                trim_punctuation(py, "(example.com)").unwrap(),
+                ("(", "example.com", ")".to_string())
+            );
+            assert_eq!(
+                trim_punctuation(py, "example.com/a_(b)").unwrap(),
+                ("", "example.com/a_(b)", "".to_string())
+            );
+            assert_eq!(
+                trim_punctuation(py, "example.com?a=&lt;").unwrap(),
+                ("", "example.com?a=&lt;", "".to_string())
+            );
+            assert_eq!(
+                trim_punctuation(py, "example.com;").unwrap(),
+                ("", "example.com", ";".to_string())
+            );
+            assert_eq!(
+                trim_punctuation(py, "example.com/a).").unwrap(),
+                ("", "example.com/a", ").".to_string())
+            );
+            assert_eq!(
+                trim_punctuation(py, "(example.com/a)).!").unwrap(),
+                ("(", "example.com/a", ")).!".to_string())
+            );
+        })
+    }
+
+    #[test]
+    fn test_trim_url() {
+        assert_eq!(trim_url("example.com", None), "example.com");
+        assert_eq!(trim_url("example.com", Some(11)), "example.com");
+        assert_eq!(trim_url("example.com", Some(8)), "example…");
+        assert_eq!(trim_url("example.com", Some(0)), "…");
+    }
+
+    #[test]
+    fn test_urlize_email() {
+        Python::initialize();
+
+        Python::attach(|py| {
+            let urlizer = Urlizer {
+                trim_url_limit: None,
+                nofollow: true,
+                autoescape: true,
+            };
+            let urlized = urlizer
+                .urlize(py, "Email <lily@example.com>.", false)
+                .unwrap();
+            assert_eq!(
+                urlized,
+                "Email &lt;<a href=\"mailto:lily@example.com\">lily@example.com</a>&gt;."
+            );
+        })
+    }
+}
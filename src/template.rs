@@ -4,22 +4,29 @@ use pyo3::prelude::*;
 pub mod django_rusty_templates {
     use std::collections::HashMap;
     use std::path::PathBuf;
+    use std::sync::Arc;
+    use std::time::Instant;
 
     use encoding_rs::Encoding;
     use pyo3::exceptions::{PyAttributeError, PyImportError, PyOverflowError, PyValueError};
     use pyo3::import_exception;
     use pyo3::intern;
     use pyo3::prelude::*;
-    use pyo3::types::{PyBool, PyDict, PyIterator, PyString};
+    use pyo3::sync::PyOnceLock;
+    use pyo3::types::{PyBool, PyDict, PyIterator, PyList, PyString};
 
-    use crate::error::RenderError;
+    use crate::display::TemplateDisplay;
+    use crate::error::{PyRenderError, RenderError};
     use crate::loaders::{AppDirsLoader, CachedLoader, FileSystemLoader, Loader, LocMemLoader};
-    use crate::parse::{Parser, TokenTree};
-    use crate::render::Render;
+    use crate::parse::{Block, DEFAULT_MAX_DEPTH, Extends, Parser, Tag, TokenTree};
     use crate::render::types::Context;
+    use crate::render::{Render, RenderResult, RenderSink};
     use crate::types::TemplateString;
     use crate::utils::PyResultMethods;
 
+    static CSRF: PyOnceLock<Py<PyAny>> = PyOnceLock::new();
+
+    import_exception!(django.core.cache.backends.base, InvalidCacheBackendError);
     import_exception!(django.core.exceptions, ImproperlyConfigured);
     import_exception!(django.template.base, VariableDoesNotExist);
     import_exception!(django.template.exceptions, TemplateDoesNotExist);
@@ -80,6 +87,10 @@ pub mod django_rusty_templates {
     pub struct EngineData {
         autoescape: bool,
         libraries: HashMap<String, Py<PyAny>>,
+        legacy_tags: bool,
+        unclosed_tag_errors: bool,
+        max_parse_depth: usize,
+        strict_variables: bool,
     }
 
     impl EngineData {
@@ -88,6 +99,10 @@ pub mod django_rusty_templates {
             Self {
                 autoescape: false,
                 libraries: HashMap::new(),
+                legacy_tags: false,
+                unclosed_tag_errors: false,
+                max_parse_depth: DEFAULT_MAX_DEPTH,
+                strict_variables: false,
             }
         }
     }
@@ -248,8 +263,10 @@ pub mod django_rusty_templates {
     #[pymethods]
     impl Engine {
         #[new]
-        #[pyo3(signature = (dirs=None, app_dirs=false, context_processors=None, debug=false, loaders=None, string_if_invalid="".to_string(), file_charset="utf-8".to_string(), libraries=None, builtins=None, autoescape=true))]
-        #[allow(clippy::too_many_arguments)] // We're matching Django's Engine __init__ signature
+        #[pyo3(signature = (dirs=None, app_dirs=false, context_processors=None, debug=false, loaders=None, string_if_invalid="".to_string(), file_charset="utf-8".to_string(), libraries=None, builtins=None, autoescape=true, legacy_tags=false, unclosed_tag_errors=false, max_parse_depth=DEFAULT_MAX_DEPTH, strict_variables=false))]
+        // We're matching Django's Engine __init__ signature, plus our extensions:
+        // legacy_tags, unclosed_tag_errors, max_parse_depth and strict_variables.
+        #[allow(clippy::too_many_arguments)]
         pub fn new(
             _py: Python<'_>,
             dirs: Option<Bound<'_, PyAny>>,
@@ -262,6 +279,10 @@ pub mod django_rusty_templates {
             libraries: Option<Bound<'_, PyAny>>,
             #[allow(unused_variables)] builtins: Option<Bound<'_, PyAny>>,
             autoescape: bool,
+            legacy_tags: bool,
+            unclosed_tag_errors: bool,
+            max_parse_depth: usize,
+            strict_variables: bool,
         ) -> PyResult<Self> {
             let dirs = match dirs {
                 Some(dirs) => dirs.extract()?,
@@ -304,6 +325,10 @@ pub mod django_rusty_templates {
             let data = EngineData {
                 autoescape,
                 libraries,
+                legacy_tags,
+                unclosed_tag_errors,
+                max_parse_depth,
+                strict_variables,
             };
             Ok(Self {
                 dirs,
@@ -322,48 +347,35 @@ pub mod django_rusty_templates {
         /// handling template inheritance recursively.
         ///
         /// See https://docs.djangoproject.com/en/stable/ref/templates/api/#django.template.Engine.get_template
-        pub fn get_template(
-            &mut self,
-            py: Python<'_>,
-            template_name: String,
-        ) -> PyResult<Template> {
-            let mut tried = Vec::new();
-            for loader in &mut self.template_loaders {
-                match loader.get_template(py, &template_name, &self.data) {
-                    Ok(template) => return template,
-                    Err(e) => tried.push(e.tried),
-                }
-            }
-            Err(TemplateDoesNotExist::new_err((template_name, tried)))
+        #[pyo3(name = "get_template")]
+        fn py_get_template(slf: &Bound<'_, Self>, template_name: String) -> PyResult<Template> {
+            EngineRef::new(slf).get_template(slf.py(), template_name)
         }
 
         /// Given a list of template names, return the first that can be loaded.
         ///
         /// See https://docs.djangoproject.com/en/stable/ref/templates/api/#django.template.Engine.select_template
-        pub fn select_template(
-            &mut self,
-            py: Python<'_>,
+        #[pyo3(name = "select_template")]
+        fn py_select_template(
+            slf: &Bound<'_, Self>,
             template_name_list: Vec<String>,
         ) -> PyResult<Template> {
-            if template_name_list.is_empty() {
-                return Err(TemplateDoesNotExist::new_err("No template names provided"));
-            }
-            let mut not_found = Vec::new();
-            for template_name in template_name_list {
-                match self.get_template(py, template_name) {
-                    Ok(template) => return Ok(template),
-                    Err(e) if e.is_instance_of::<TemplateDoesNotExist>(py) => {
-                        not_found.push(e.value(py).to_string())
-                    }
-                    Err(e) => return Err(e),
-                }
-            }
-            Err(TemplateDoesNotExist::new_err(not_found.join(", ")))
+            EngineRef::new(slf).select_template(slf.py(), template_name_list)
         }
 
-        #[allow(clippy::wrong_self_convention)] // We're implementing a Django interface
-        pub fn from_string(&self, template_code: Bound<'_, PyString>) -> PyResult<Template> {
-            Template::new_from_string(template_code.py(), template_code.extract()?, &self.data)
+        /// Compile a template from a string.
+        ///
+        /// `autoescape` overrides the engine's `autoescape` option for this
+        /// template only.
+        #[pyo3(name = "from_string", signature = (template_code, autoescape=None))]
+        fn py_from_string(
+            slf: &Bound<'_, Self>,
+            template_code: Bound<'_, PyString>,
+            autoescape: Option<bool>,
+        ) -> PyResult<Template> {
+            let mut template = slf.borrow().from_string(template_code, autoescape)?;
+            template.engine = Some(EngineRef::new(slf));
+            Ok(template)
         }
 
         // TODO render_to_string needs implementation.
@@ -395,13 +407,148 @@ pub mod django_rusty_templates {
         }
     }
 
+    impl Engine {
+        pub fn get_template(&self, py: Python<'_>, template_name: String) -> PyResult<Template> {
+            let mut tried = Vec::new();
+            for loader in &self.template_loaders {
+                match loader.get_template(py, &template_name, &self.data) {
+                    Ok(template) => return template,
+                    Err(e) => tried.extend(e.tried),
+                }
+            }
+            Err(TemplateDoesNotExist::new_err((template_name, tried)))
+        }
+
+        /// Like Django, the error when none of the templates can be loaded
+        /// lists each missing name once, along with every source tried.
+        pub fn select_template(
+            &self,
+            py: Python<'_>,
+            template_name_list: Vec<String>,
+        ) -> PyResult<Template> {
+            if template_name_list.is_empty() {
+                return Err(TemplateDoesNotExist::new_err("No template names provided"));
+            }
+            let mut not_found = Vec::new();
+            let tried = PyList::empty(py);
+            for template_name in template_name_list {
+                match self.get_template(py, template_name) {
+                    Ok(template) => return Ok(template),
+                    Err(e) if e.is_instance_of::<TemplateDoesNotExist>(py) => {
+                        let error = e.value(py);
+                        let name = error.str()?.to_string();
+                        if !not_found.contains(&name) {
+                            not_found.push(name);
+                        }
+                        if let Ok(error_tried) = error.getattr(intern!(py, "tried"))
+                            && !error_tried.is_none()
+                        {
+                            for entry in error_tried.try_iter()? {
+                                tried.append(entry?)?;
+                            }
+                        }
+                    }
+                    Err(e) => return Err(e),
+                }
+            }
+            Err(TemplateDoesNotExist::new_err((
+                not_found.join(", "),
+                tried.unbind(),
+            )))
+        }
+
+        #[allow(clippy::wrong_self_convention)] // We're implementing a Django interface
+        pub fn from_string(
+            &self,
+            template_code: Bound<'_, PyString>,
+            autoescape: Option<bool>,
+        ) -> PyResult<Template> {
+            let mut template = Template::new_from_string(
+                template_code.py(),
+                template_code.extract()?,
+                &self.data,
+            )?;
+            if let Some(autoescape) = autoescape {
+                template.autoescape = autoescape;
+            }
+            Ok(template)
+        }
+    }
+
+    /// The engine a template was created by, used to load other templates
+    /// by name while rendering it, like Django's `Template.engine`.
+    #[derive(Debug, Clone)]
+    pub struct EngineRef(Arc<Py<Engine>>);
+
+    impl EngineRef {
+        fn new(engine: &Bound<'_, Engine>) -> Self {
+            Self(Arc::new(engine.clone().unbind()))
+        }
+
+        pub fn get_template(&self, py: Python<'_>, template_name: String) -> PyResult<Template> {
+            let mut template = self.0.bind(py).borrow().get_template(py, template_name)?;
+            template.engine = Some(self.clone());
+            Ok(template)
+        }
+
+        pub fn select_template(
+            &self,
+            py: Python<'_>,
+            template_name_list: Vec<String>,
+        ) -> PyResult<Template> {
+            let mut template = self
+                .0
+                .bind(py)
+                .borrow()
+                .select_template(py, template_name_list)?;
+            template.engine = Some(self.clone());
+            Ok(template)
+        }
+    }
+
+    impl PartialEq for EngineRef {
+        fn eq(&self, other: &Self) -> bool {
+            self.0.is(&*other.0)
+        }
+    }
+
     #[derive(Debug, Clone, PartialEq)]
     #[pyclass]
     pub struct Template {
         pub filename: Option<PathBuf>,
-        pub template: String,
+        pub template: Arc<str>,
         pub nodes: Vec<TokenTree>,
+        /// The template's `{% block %}` tags, by name.
+        pub blocks: HashMap<String, Arc<Block>>,
         pub autoescape: bool,
+        /// Whether undefined variables raise instead of rendering as empty.
+        pub strict_variables: bool,
+        /// Whether the template only contains text and variables, so it can
+        /// be rendered without dispatching on tags.
+        pub fast_path: bool,
+        pub engine: Option<EngineRef>,
+    }
+
+    /// Writes rendered output to a Python file-like object.
+    struct PyWriter<'py>(Bound<'py, PyAny>);
+
+    impl RenderSink for PyWriter<'_> {
+        fn write(&mut self, content: &str) -> PyResult<()> {
+            if !content.is_empty() {
+                let py = self.0.py();
+                self.0.call_method1(intern!(py, "write"), (content,))?;
+            }
+            Ok(())
+        }
+    }
+
+    fn only_text_and_variables(nodes: &[TokenTree]) -> bool {
+        nodes.iter().all(|node| {
+            matches!(
+                node,
+                TokenTree::Text(_) | TokenTree::Variable(_) | TokenTree::Filter(_)
+            )
+        })
     }
 
     impl Template {
@@ -411,7 +558,10 @@ pub mod django_rusty_templates {
             filename: PathBuf,
             engine_data: &EngineData,
         ) -> PyResult<Self> {
-            let mut parser = Parser::new(py, TemplateString(template), &engine_data.libraries);
+            let mut parser = Parser::new(py, TemplateString(template), &engine_data.libraries)
+                .with_legacy_tags(engine_data.legacy_tags)
+                .with_unclosed_errors(engine_data.unclosed_tag_errors)
+                .with_max_depth(engine_data.max_parse_depth);
             let nodes = match parser.parse() {
                 Ok(nodes) => nodes,
                 Err(err) => {
@@ -421,11 +571,16 @@ pub mod django_rusty_templates {
                     return Err(TemplateSyntaxError::with_source_code(err.into(), source));
                 }
             };
+            let blocks = parser.take_blocks();
             Ok(Self {
-                template: template.to_string(),
+                template: Arc::from(template),
+                blocks,
                 filename: Some(filename),
+                fast_path: only_text_and_variables(&nodes),
                 nodes,
                 autoescape: engine_data.autoescape,
+                strict_variables: engine_data.strict_variables,
+                engine: None,
             })
         }
 
@@ -434,7 +589,10 @@ pub mod django_rusty_templates {
             template: String,
             engine_data: &EngineData,
         ) -> PyResult<Self> {
-            let mut parser = Parser::new(py, TemplateString(&template), &engine_data.libraries);
+            let mut parser = Parser::new(py, TemplateString(&template), &engine_data.libraries)
+                .with_legacy_tags(engine_data.legacy_tags)
+                .with_unclosed_errors(engine_data.unclosed_tag_errors)
+                .with_max_depth(engine_data.max_parse_depth);
             let nodes = match parser.parse() {
                 Ok(nodes) => nodes,
                 Err(err) => {
@@ -442,54 +600,185 @@ pub mod django_rusty_templates {
                     return Err(TemplateSyntaxError::with_source_code(err.into(), template));
                 }
             };
+            let blocks = parser.take_blocks();
+            drop(parser);
             Ok(Self {
-                template,
+                template: Arc::from(template),
+                blocks,
                 filename: None,
+                fast_path: only_text_and_variables(&nodes),
                 nodes,
                 autoescape: engine_data.autoescape,
+                strict_variables: engine_data.strict_variables,
+                engine: None,
             })
         }
 
+        fn build_context(
+            &self,
+            py: Python<'_>,
+            context: Option<Bound<'_, PyDict>>,
+            request: Option<Bound<'_, PyAny>>,
+        ) -> PyResult<Context> {
+            let mut base_context = HashMap::from([
+                ("None".to_string(), py.None()),
+                ("True".to_string(), PyBool::new(py, true).to_owned().into()),
+                (
+                    "False".to_string(),
+                    PyBool::new(py, false).to_owned().into(),
+                ),
+            ]);
+            if let Some(request) = &request {
+                // Like Django's `request` and `csrf` context processors.
+                let csrf = CSRF.import(py, "django.template.context_processors", "csrf")?;
+                let processed: HashMap<_, _> = csrf.call1((request,))?.extract()?;
+                base_context.insert("request".to_string(), request.clone().unbind());
+                base_context.extend(processed);
+            }
+            if let Some(context) = context {
+                let new_context: HashMap<_, _> = context.extract()?;
+                base_context.extend(new_context);
+            };
+            let request = request.map(|request| request.unbind());
+            let mut context = Context::new(base_context, request, self.autoescape);
+            context.strict_variables = self.strict_variables;
+            context.engine = self.engine.clone();
+            Ok(context)
+        }
+
         fn _render(&self, py: Python<'_>, context: &mut Context) -> PyResult<String> {
             let mut rendered = String::with_capacity(self.template.len());
+            self.render_into(py, context, &mut rendered)?;
+            Ok(rendered)
+        }
+
+        /// Render the template, writing the output of each top-level node to
+        /// `out` as soon as it's rendered.
+        fn render_into(
+            &self,
+            py: Python<'_>,
+            context: &mut Context,
+            out: &mut impl RenderSink,
+        ) -> PyResult<()> {
             let template = TemplateString(&self.template);
-            for node in &self.nodes {
-                match node.render(py, template, context) {
-                    Ok(content) => rendered.push_str(&content),
-                    Err(err) => {
-                        let err = err.try_into_render_error()?;
-                        match err {
-                            RenderError::VariableDoesNotExist { .. }
-                            | RenderError::ArgumentDoesNotExist { .. } => {
-                                return Err(VariableDoesNotExist::with_source_code(
-                                    err.into(),
-                                    self.template.clone(),
-                                ));
-                            }
-                            RenderError::InvalidArgumentInteger { .. } => {
-                                return Err(PyValueError::with_source_code(
-                                    err.into(),
-                                    self.template.clone(),
-                                ));
-                            }
-                            RenderError::OverflowError { .. }
-                            | RenderError::InvalidArgumentFloat { .. } => {
-                                return Err(PyOverflowError::with_source_code(
-                                    err.into(),
-                                    self.template.clone(),
-                                ));
-                            }
-                            RenderError::TupleUnpackError { .. } => {
-                                return Err(PyValueError::with_source_code(
-                                    err.into(),
-                                    self.template.clone(),
-                                ));
-                            }
+            if self.fast_path && !context.timings_enabled() {
+                for node in &self.nodes {
+                    let content = match node {
+                        TokenTree::Text(text) => {
+                            out.write(template.content(text.at))?;
+                            continue;
                         }
+                        TokenTree::Variable(variable) => variable.render(py, template, context),
+                        TokenTree::Filter(filter) => filter.render(py, template, context),
+                        _ => unreachable!("fast path templates only contain text and variables"),
+                    };
+                    match content {
+                        Ok(content) => out.write(&content)?,
+                        Err(err) => return Err(self.render_error(err)),
                     }
                 }
+                return Ok(());
+            }
+            for node in &self.nodes {
+                let start = context.timings_enabled().then(Instant::now);
+                let content = self.render_node(py, node, context);
+                if let Some(start) = start {
+                    context.record_timing(start.elapsed());
+                }
+                match content {
+                    Ok(content) => out.write(&content)?,
+                    Err(err) => return Err(self.render_error(err)),
+                }
+            }
+            Ok(())
+        }
+
+        pub(crate) fn render_error(&self, err: PyRenderError) -> PyErr {
+            annotate_render_error(err, &self.template)
+        }
+
+        fn render_node<'t>(
+            &'t self,
+            py: Python<'_>,
+            node: &'t TokenTree,
+            context: &mut Context,
+        ) -> RenderResult<'t> {
+            match node {
+                // `{% extends %}` needs this template's blocks, so it is
+                // rendered here rather than through `Render`.
+                TokenTree::Tag(Tag::Extends(extends)) => {
+                    Ok(self.render_extends(py, extends, context)?.into())
+                }
+                node => node.render(py, TemplateString(&self.template), context),
+            }
+        }
+
+        /// Render the nodes of this template inside another template, as
+        /// `{% include %}` does.
+        pub(crate) fn render_nodes(
+            &self,
+            py: Python<'_>,
+            context: &mut Context,
+        ) -> RenderResult<'_> {
+            let mut rendered = String::with_capacity(self.template.len());
+            for node in &self.nodes {
+                rendered.push_str(&self.render_node(py, node, context)?);
+            }
+            Ok(rendered.into())
+        }
+
+        fn extends(&self) -> Option<&Extends> {
+            self.nodes.iter().find_map(|node| match node {
+                TokenTree::Tag(Tag::Extends(extends)) => Some(extends),
+                _ => None,
+            })
+        }
+
+        /// Render the parent template, with this template's blocks
+        /// overriding the parent's, like Django's `ExtendsNode`.
+        fn render_extends(
+            &self,
+            py: Python<'_>,
+            extends: &Extends,
+            context: &mut Context,
+        ) -> PyResult<String> {
+            let parent = extends
+                .parent_template(py, TemplateString(&self.template), context)
+                .map_err(|err| self.render_error(err))?;
+            let parent = parent.borrow();
+            context.add_blocks(&self.template, &self.blocks);
+            // The root template's own blocks are the last version of each.
+            if parent.extends().is_none() {
+                context.add_blocks(&parent.template, &parent.blocks);
+            }
+            match parent.render_nodes(py, context) {
+                Ok(rendered) => Ok(rendered.into_owned()),
+                Err(err) => Err(parent.render_error(err)),
+            }
+        }
+    }
+
+    /// Convert a render error into a Python exception, attaching the source
+    /// of the template it came from.
+    pub(crate) fn annotate_render_error(err: PyRenderError, source: &Arc<str>) -> PyErr {
+        let err = match err.try_into_render_error() {
+            Ok(err) => err,
+            Err(err) => return err,
+        };
+        let source = source.clone();
+        match err {
+            RenderError::VariableDoesNotExist { .. } | RenderError::ArgumentDoesNotExist { .. } => {
+                VariableDoesNotExist::with_source_code(err.into(), source)
+            }
+            RenderError::InvalidArgumentInteger { .. } => {
+                PyValueError::with_source_code(err.into(), source)
+            }
+            RenderError::OverflowError { .. } | RenderError::InvalidArgumentFloat { .. } => {
+                PyOverflowError::with_source_code(err.into(), source)
+            }
+            RenderError::TupleUnpackError { .. } => {
+                PyValueError::with_source_code(err.into(), source)
             }
-            Ok(rendered)
         }
     }
 
@@ -502,22 +791,65 @@ pub mod django_rusty_templates {
             context: Option<Bound<'_, PyDict>>,
             request: Option<Bound<'_, PyAny>>,
         ) -> PyResult<String> {
-            let mut base_context = HashMap::from([
-                ("None".to_string(), py.None()),
-                ("True".to_string(), PyBool::new(py, true).to_owned().into()),
-                (
-                    "False".to_string(),
-                    PyBool::new(py, false).to_owned().into(),
-                ),
-            ]);
-            if let Some(context) = context {
-                let new_context: HashMap<_, _> = context.extract()?;
-                base_context.extend(new_context);
-            };
-            let request = request.map(|request| request.unbind());
-            let mut context = Context::new(base_context, request, self.autoescape);
+            let mut context = self.build_context(py, context, request)?;
             self._render(py, &mut context)
         }
+
+        /// Render the template into the file-like object `out`, calling
+        /// `out.write` with the output of each top-level node instead of
+        /// building the whole result in memory.
+        #[pyo3(signature = (context, out, request=None))]
+        pub fn stream(
+            &self,
+            py: Python<'_>,
+            context: Option<Bound<'_, PyDict>>,
+            out: Bound<'_, PyAny>,
+            request: Option<Bound<'_, PyAny>>,
+        ) -> PyResult<()> {
+            let mut context = self.build_context(py, context, request)?;
+            self.render_into(py, &mut context, &mut PyWriter(out))
+        }
+
+        /// Render the template, also returning a dict mapping the index of
+        /// each top-level node to the number of seconds it took to render.
+        #[pyo3(signature = (context=None, request=None))]
+        pub fn render_with_timings<'py>(
+            &self,
+            py: Python<'py>,
+            context: Option<Bound<'_, PyDict>>,
+            request: Option<Bound<'_, PyAny>>,
+        ) -> PyResult<(String, Bound<'py, PyDict>)> {
+            let mut context = self.build_context(py, context, request)?;
+            context.enable_timings();
+            let rendered = self._render(py, &mut context)?;
+            let timings = PyDict::new(py);
+            for (index, duration) in context.take_timings().into_iter().enumerate() {
+                timings.set_item(index, duration.as_secs_f64())?;
+            }
+            Ok((rendered, timings))
+        }
+
+        /// Reconstruct the template source from the parsed nodes.
+        ///
+        /// This is useful for checking how a template was parsed.
+        pub fn debug_source(&self) -> String {
+            self.nodes
+                .display(TemplateString(&self.template))
+                .to_string()
+        }
+
+        #[getter]
+        pub fn source(&self) -> &str {
+            &self.template
+        }
+
+        #[getter]
+        pub fn origin(&self) -> String {
+            match &self.filename {
+                Some(filename) => filename.to_string_lossy().to_string(),
+                None => "<unknown source>".to_string(),
+            }
+        }
     }
 }
 
@@ -525,8 +857,10 @@ pub mod django_rusty_templates {
 mod tests {
     use super::django_rusty_templates::*;
 
-    use pyo3::Python;
-    use pyo3::types::{PyDict, PyDictMethods, PyString};
+    use pyo3::exceptions::PyRuntimeError;
+    use pyo3::prelude::PyAnyMethods;
+    use pyo3::types::{PyDict, PyDictMethods, PyList, PyString};
+    use pyo3::{Bound, Py, PyAny, Python};
 
     #[test]
     fn test_syntax_error() {
@@ -617,93 +951,1108 @@ mod tests {
     }
 
     #[test]
-    fn test_render_template_unknown_variable() {
+    fn test_render_include_template_object() {
         Python::initialize();
 
         Python::attach(|py| {
             let engine = EngineData::empty();
-            let template_string = "Hello {{ user }}!".to_string();
+            let included = "{{ greeting }} {{ user }}".to_string();
+            let included = Template::new_from_string(py, included, &engine).unwrap();
+            let template_string =
+                "{% include tmpl %}|{% include tmpl with greeting='Hi' %}|{% include tmpl with user='Bryony' only %}".to_string();
             let template = Template::new_from_string(py, template_string, &engine).unwrap();
             let context = PyDict::new(py);
+            context
+                .set_item("tmpl", Py::new(py, included).unwrap())
+                .unwrap();
+            context.set_item("greeting", "Hello").unwrap();
+            context.set_item("user", "Lily").unwrap();
 
-            assert_eq!(template.render(py, Some(context), None).unwrap(), "Hello !");
+            assert_eq!(
+                template.render(py, Some(context), None).unwrap(),
+                "Hello Lily|Hi Lily| Bryony"
+            );
         })
     }
 
     #[test]
-    fn test_render_template_variable_nested() {
+    fn test_render_extends_template_object() {
         Python::initialize();
 
         Python::attach(|py| {
             let engine = EngineData::empty();
-            let template_string = "Hello {{ user.profile.names.0 }}!".to_string();
+            let base = "<{% block title %}Base{% endblock %}|{% block body %}{% endblock body %}>";
+            let base = Template::new_from_string(py, base.to_string(), &engine).unwrap();
+            let parent = "{% extends base %}{% block title %}Parent{% endblock %}";
+            let parent = Template::new_from_string(py, parent.to_string(), &engine).unwrap();
+            let template_string =
+                "{% extends parent %}ignored{% block body %}{{ user }}{% endblock %}".to_string();
             let template = Template::new_from_string(py, template_string, &engine).unwrap();
-            let locals = PyDict::new(py);
-            py.run(
-                cr#"
-class User:
-    def __init__(self, names):
-        self.profile = {"names": names}
-
-user = User(["Lily"])
-"#,
-                None,
-                Some(&locals),
-            )
-            .unwrap();
-            let user = locals.get_item("user").unwrap().unwrap();
             let context = PyDict::new(py);
-            context.set_item("user", user.into_any()).unwrap();
+            context
+                .set_item("base", Py::new(py, base).unwrap())
+                .unwrap();
+            context
+                .set_item("parent", Py::new(py, parent).unwrap())
+                .unwrap();
+            context.set_item("user", "Lily").unwrap();
 
             assert_eq!(
                 template.render(py, Some(context), None).unwrap(),
-                "Hello Lily!"
+                "<Parent|Lily>"
             );
         })
     }
 
     #[test]
-    fn test_engine_from_string() {
+    fn test_stream() {
         Python::initialize();
 
         Python::attach(|py| {
-            let engine = Engine::new(
-                py,
-                None,
-                false,
-                None,
-                false,
-                None,
-                "".to_string(),
-                "utf-8".to_string(),
-                None,
-                None,
-                false,
-            )
-            .unwrap();
-            let template_string = PyString::new(py, "Hello {{ user }}!");
-            let template = engine.from_string(template_string).unwrap();
+            let engine = EngineData::empty();
+            let template_string =
+                "{% for x in xs %}{{ x }},{% endfor %}|{{ user|lower }}!".to_string();
+            let template = Template::new_from_string(py, template_string, &engine).unwrap();
             let context = PyDict::new(py);
+            context
+                .set_item("xs", PyList::new(py, [1, 2]).unwrap())
+                .unwrap();
+            context.set_item("user", "Lily").unwrap();
+            let out = py.import("io").unwrap().call_method0("StringIO").unwrap();
 
-            assert_eq!(template.render(py, Some(context), None).unwrap(), "Hello !");
+            template
+                .stream(py, Some(context.clone()), out.clone(), None)
+                .unwrap();
+            let streamed: String = out.call_method0("getvalue").unwrap().extract().unwrap();
+            assert_eq!(streamed, "1,2,|lily!");
+            assert_eq!(template.render(py, Some(context), None).unwrap(), streamed);
         })
     }
 
     #[test]
-    fn test_clone_template() {
-        use std::collections::HashMap;
-
-        use pyo3::IntoPyObject;
-        use pyo3::types::{PyAnyMethods, PyListMethods};
-
+    fn test_render_csrf_token() {
         Python::initialize();
 
         Python::attach(|py| {
-            let cwd = std::env::current_dir().unwrap();
-            let sys_path = py.import("sys").unwrap().getattr("path").unwrap();
-            let sys_path = sys_path.cast().unwrap();
-            sys_path.append(cwd.to_string_lossy()).unwrap();
-            let mut engine = Engine::new(
+            let engine = EngineData::empty();
+            let template_string = "{% csrf_token %}".to_string();
+            let template = Template::new_from_string(py, template_string, &engine).unwrap();
+
+            assert_eq!(template.render(py, None, None).unwrap(), "");
+
+            let context = PyDict::new(py);
+            context.set_item("csrf_token", "NOTPROVIDED").unwrap();
+            assert_eq!(template.render(py, Some(context), None).unwrap(), "");
+
+            let context = PyDict::new(py);
+            context.set_item("csrf_token", "a\"b").unwrap();
+            assert_eq!(
+                template.render(py, Some(context), None).unwrap(),
+                "<input type=\"hidden\" name=\"csrfmiddlewaretoken\" value=\"a&quot;b\">"
+            );
+        })
+    }
+
+    #[test]
+    fn test_render_for_dict_items() {
+        Python::initialize();
+
+        Python::attach(|py| {
+            let engine = EngineData::empty();
+            let template_string =
+                "{% for k, v in d.items %}{{ k }}={{ v }};{% endfor %}".to_string();
+            let template = Template::new_from_string(py, template_string, &engine).unwrap();
+            let d = PyDict::new(py);
+            d.set_item("a", 1).unwrap();
+            d.set_item("b", 2).unwrap();
+            let context = PyDict::new(py);
+            context.set_item("d", d).unwrap();
+
+            assert_eq!(
+                template.render(py, Some(context), None).unwrap(),
+                "a=1;b=2;"
+            );
+        })
+    }
+
+    #[test]
+    fn test_render_for_filter_arguments() {
+        Python::initialize();
+
+        Python::attach(|py| {
+            let engine = EngineData::empty();
+            let template_string =
+                "{% for x in l %}{{ missing|default:forloop.counter }}{{ missing|default:x }};{% endfor %}"
+                    .to_string();
+            let template = Template::new_from_string(py, template_string, &engine).unwrap();
+            let context = PyDict::new(py);
+            context.set_item("l", vec!["a", "b"]).unwrap();
+
+            assert_eq!(template.render(py, Some(context), None).unwrap(), "1a;2b;");
+        })
+    }
+
+    #[test]
+    fn test_render_lone_surrogate() {
+        Python::initialize();
+
+        Python::attach(|py| {
+            let engine = EngineData::empty();
+            let template_string = "{{ value }}{% autoescape off %}{{ value }}{% endautoescape %}";
+            let template =
+                Template::new_from_string(py, template_string.to_string(), &engine).unwrap();
+            let value = py.eval(c"'a' + chr(0xD800) + 'b'", None, None).unwrap();
+            let context = PyDict::new(py);
+            context.set_item("value", value).unwrap();
+
+            assert_eq!(
+                template.render(py, Some(context), None).unwrap(),
+                "a\u{FFFD}ba\u{FFFD}b"
+            );
+        })
+    }
+
+    #[test]
+    fn test_render_empty_and_text_if_branches() {
+        Python::initialize();
+
+        Python::attach(|py| {
+            let engine = EngineData::empty();
+            let template_string = "a{% if x %}{% endif %}b{% if x %}yes{% else %}no{% endif %}{% if x %}{% else %}{% endif %}c{% if x %}{{ x }}{% endif %}";
+            let template =
+                Template::new_from_string(py, template_string.to_string(), &engine).unwrap();
+
+            for (x, expected) in [(true, "abyescTrue"), (false, "abnoc")] {
+                let context = PyDict::new(py);
+                context.set_item("x", x).unwrap();
+                assert_eq!(template.render(py, Some(context), None).unwrap(), expected);
+            }
+        })
+    }
+
+    #[test]
+    fn test_render_is_variables() {
+        Python::initialize();
+
+        Python::attach(|py| {
+            let engine = EngineData::empty();
+            let template_string = "{% if a is b %}yes{% else %}no{% endif %}{% if b is a %}yes{% else %}no{% endif %}";
+            let template =
+                Template::new_from_string(py, template_string.to_string(), &engine).unwrap();
+
+            let shared = PyList::empty(py);
+            let cases = [
+                (Some(shared.clone()), Some(shared), "yesyes"),
+                (Some(PyList::empty(py)), Some(PyList::empty(py)), "nono"),
+                (Some(PyList::empty(py)), None, "nono"),
+                (None, Some(PyList::empty(py)), "nono"),
+            ];
+            for (a, b, expected) in cases {
+                let context = PyDict::new(py);
+                if let Some(a) = a {
+                    context.set_item("a", a).unwrap();
+                }
+                if let Some(b) = b {
+                    context.set_item("b", b).unwrap();
+                }
+                assert_eq!(template.render(py, Some(context), None).unwrap(), expected);
+            }
+        })
+    }
+
+    #[test]
+    fn test_render_for_reversed_counters() {
+        Python::initialize();
+
+        Python::attach(|py| {
+            let engine = EngineData::empty();
+            let template_string = "{% for c in 'abc' reversed %}{{ c }}{{ forloop.counter }}{{ forloop.revcounter0 }}{% if forloop.first %}F{% endif %}{% if forloop.last %}L{% endif %} {% endfor %}";
+            let template =
+                Template::new_from_string(py, template_string.to_string(), &engine).unwrap();
+
+            assert_eq!(template.render(py, None, None).unwrap(), "c12F b21 a30L ");
+        })
+    }
+
+    #[test]
+    fn test_render_forloop_object() {
+        Python::initialize();
+
+        Python::attach(|py| {
+            let engine = EngineData::empty();
+            let template_string = "{% for x in 'ab' %}{{ forloop }};{% endfor %}\
+                {% for x in 'ab' %}{% for y in 'c' %}{{ forloop.parentloop.counter }}\
+                {{ forloop.parentloop.last }};{% endfor %}{% endfor %}"
+                .to_string();
+            let template = Template::new_from_string(py, template_string, &engine).unwrap();
+
+            assert_eq!(
+                template.render(py, None, None).unwrap(),
+                "{'parentloop': {}, 'counter0': 0, 'counter': 1, 'revcounter': 2, \
+                'revcounter0': 1, 'first': True, 'last': False};\
+                {'parentloop': {}, 'counter0': 1, 'counter': 2, 'revcounter': 1, \
+                'revcounter0': 0, 'first': False, 'last': True};1False;2True;"
+            );
+
+            let template_string =
+                "{% for x in 'a' %}{% for y in 'bc' %}{{ forloop }}{% endfor %}{% endfor %}"
+                    .to_string();
+            let template = Template::new_from_string(py, template_string, &engine).unwrap();
+            let outer = "'parentloop': {'parentloop': {}, 'counter0': 0, 'counter': 1, \
+                'revcounter': 1, 'revcounter0': 0, 'first': True, 'last': True}";
+            assert_eq!(
+                template.render(py, None, None).unwrap(),
+                format!(
+                    "{{{outer}, 'counter0': 0, 'counter': 1, 'revcounter': 2, \
+                    'revcounter0': 1, 'first': True, 'last': False}}\
+                    {{{outer}, 'counter0': 1, 'counter': 2, 'revcounter': 1, \
+                    'revcounter0': 0, 'first': False, 'last': True}}"
+                )
+            );
+        })
+    }
+
+    #[test]
+    fn test_render_for_nested_restores_outer_forloop() {
+        Python::initialize();
+
+        Python::attach(|py| {
+            let engine = EngineData::empty();
+            let template_string = "{% for a in 'ab' %}{{ forloop.counter }}[\
+                {% for b in 'xyz' %}{{ forloop.counter }}{{ forloop.parentloop.counter }}{% endfor %}\
+                ]{{ forloop.counter }}{{ forloop.revcounter }}{{ forloop.last }} {% endfor %}\
+                [{{ forloop.counter }}]";
+            let template =
+                Template::new_from_string(py, template_string.to_string(), &engine).unwrap();
+
+            assert_eq!(
+                template.render(py, None, None).unwrap(),
+                "1[112131]12False 2[122232]21True []"
+            );
+        })
+    }
+
+    #[test]
+    fn test_render_spaceless_variable() {
+        Python::initialize();
+
+        Python::attach(|py| {
+            let engine = EngineData::empty();
+            let template_string = "{% autoescape off %}{% spaceless %} <div>{{ html }}</div> {% endspaceless %}{% endautoescape %}";
+            let template =
+                Template::new_from_string(py, template_string.to_string(), &engine).unwrap();
+            let context = PyDict::new(py);
+            context
+                .set_item("html", "<p>\n  <span>x</span>  </p>")
+                .unwrap();
+
+            assert_eq!(
+                template.render(py, Some(context), None).unwrap(),
+                "<div><p><span>x</span></p></div>"
+            );
+        })
+    }
+
+    #[test]
+    fn test_render_fast_path() {
+        Python::initialize();
+
+        Python::attach(|py| {
+            let engine = EngineData::empty();
+            let template_string = "Hello {{ user|upper }}, from {{ sender }}.".to_string();
+            let template = Template::new_from_string(py, template_string, &engine).unwrap();
+            assert!(template.fast_path);
+
+            let context = PyDict::new(py);
+            context.set_item("user", "lily").unwrap();
+            context.set_item("sender", "Bryony").unwrap();
+            let fast = template.render(py, Some(context.clone()), None).unwrap();
+
+            let mut slow_template = template.clone();
+            slow_template.fast_path = false;
+            let slow = slow_template.render(py, Some(context), None).unwrap();
+
+            assert_eq!(fast, "Hello LILY, from Bryony.");
+            assert_eq!(fast, slow);
+        })
+    }
+
+    #[test]
+    fn test_no_fast_path_with_tags() {
+        Python::initialize();
+
+        Python::attach(|py| {
+            let engine = EngineData::empty();
+            let template_string = "{% if user %}Hello {{ user }}{% endif %}".to_string();
+            let template = Template::new_from_string(py, template_string, &engine).unwrap();
+            assert!(!template.fast_path);
+        })
+    }
+
+    #[test]
+    fn test_render_template_unknown_variable() {
+        Python::initialize();
+
+        Python::attach(|py| {
+            let engine = EngineData::empty();
+            let template_string = "Hello {{ user }}!".to_string();
+            let template = Template::new_from_string(py, template_string, &engine).unwrap();
+            let context = PyDict::new(py);
+
+            assert_eq!(template.render(py, Some(context), None).unwrap(), "Hello !");
+        })
+    }
+
+    fn counting_iterator(py: Python<'_>) -> Bound<'_, PyAny> {
+        let locals = PyDict::new(py);
+        py.run(
+            cr#"
+class Counting:
+    """Count the items taken from a large iterator."""
+
+    produced = 0
+
+    def __iter__(self):
+        return self
+
+    def __next__(self):
+        if self.produced == 100_000:
+            raise StopIteration
+        self.produced += 1
+        return self.produced
+
+items = Counting()
+"#,
+            None,
+            Some(&locals),
+        )
+        .unwrap();
+        locals.get_item("items").unwrap().unwrap()
+    }
+
+    #[test]
+    fn test_render_for_streams_iterators() {
+        Python::initialize();
+
+        Python::attach(|py| {
+            let engine = EngineData::empty();
+            let template_string = "{% for x in items %}\
+                {% if x == 5 %}{{ items.produced }}{% endif %}\
+                {% if forloop.last %} last={{ x }}{% endif %}\
+                {% endfor %}"
+                .to_string();
+            let template = Template::new_from_string(py, template_string, &engine).unwrap();
+            let context = PyDict::new(py);
+            context.set_item("items", counting_iterator(py)).unwrap();
+
+            // Only one item is read ahead, to find the last one.
+            assert_eq!(
+                template.render(py, Some(context), None).unwrap(),
+                "6 last=100000"
+            );
+        })
+    }
+
+    #[test]
+    fn test_render_for_materializes_for_length() {
+        Python::initialize();
+
+        Python::attach(|py| {
+            let engine = EngineData::empty();
+            let template_string = "{% for x in items %}\
+                {% if x == 5 %}{{ items.produced }} {{ forloop.revcounter }}{% endif %}\
+                {% endfor %}"
+                .to_string();
+            let template = Template::new_from_string(py, template_string, &engine).unwrap();
+            let context = PyDict::new(py);
+            context.set_item("items", counting_iterator(py)).unwrap();
+
+            assert_eq!(
+                template.render(py, Some(context), None).unwrap(),
+                "100000 99996"
+            );
+        })
+    }
+
+    #[test]
+    fn test_render_for_empty_iterable() {
+        Python::initialize();
+
+        Python::attach(|py| {
+            let engine = EngineData::empty();
+            let template_string =
+                "{% for x in items %}{{ x }}{% empty %}none{% endfor %}".to_string();
+            let template = Template::new_from_string(py, template_string, &engine).unwrap();
+            let context = PyDict::new(py);
+            context.set_item("items", PyList::empty(py)).unwrap();
+
+            assert_eq!(template.render(py, Some(context), None).unwrap(), "none");
+        })
+    }
+
+    #[test]
+    fn test_render_if_falsey_values() {
+        Python::initialize();
+
+        Python::attach(|py| {
+            let engine = EngineData::empty();
+            let template_string = "{% if x %}truthy{% else %}falsey{% endif %}".to_string();
+            let template = Template::new_from_string(py, template_string, &engine).unwrap();
+            let locals = PyDict::new(py);
+            py.run(
+                cr#"
+class Empty:
+    def __len__(self):
+        return 0
+
+values = [[], {}, 0, 0.0, "", Empty(), None, False]
+"#,
+                None,
+                Some(&locals),
+            )
+            .unwrap();
+            let values = locals.get_item("values").unwrap().unwrap();
+            for value in values.try_iter().unwrap() {
+                let value = value.unwrap();
+                let context = PyDict::new(py);
+                context.set_item("x", &value).unwrap();
+                assert_eq!(
+                    template.render(py, Some(context), None).unwrap(),
+                    "falsey",
+                    "{value}"
+                );
+            }
+        })
+    }
+
+    #[test]
+    fn test_render_if_bool_error() {
+        Python::initialize();
+
+        Python::attach(|py| {
+            let engine = EngineData::empty();
+            let locals = PyDict::new(py);
+            py.run(
+                cr#"
+class Broken:
+    def __bool__(self):
+        raise RuntimeError("broken")
+
+x = Broken()
+"#,
+                None,
+                Some(&locals),
+            )
+            .unwrap();
+            let x = locals.get_item("x").unwrap().unwrap();
+            let context = PyDict::new(py);
+            context.set_item("x", x).unwrap();
+
+            let template_string = "{% if x %}truthy{% endif %}".to_string();
+            let template = Template::new_from_string(py, template_string, &engine).unwrap();
+            let error = template
+                .render(py, Some(context.clone()), None)
+                .unwrap_err();
+            assert!(error.is_instance_of::<PyRuntimeError>(py));
+
+            // Operators treat the error as false, like Django.
+            let template_string =
+                "{% if not x %}a{% endif %}{% if x or 1 %}b{% endif %}".to_string();
+            let template = Template::new_from_string(py, template_string, &engine).unwrap();
+            assert_eq!(template.render(py, Some(context), None).unwrap(), "");
+        })
+    }
+
+    #[test]
+    fn test_render_length_after_property_lookup() {
+        Python::initialize();
+
+        Python::attach(|py| {
+            let engine = EngineData::empty();
+            let template_string =
+                "{% if items %}{{ items|length }}{{ items.popped }}{{ items|length }}{% endif %}"
+                    .to_string();
+            let template = Template::new_from_string(py, template_string, &engine).unwrap();
+            let locals = PyDict::new(py);
+            py.run(
+                cr#"
+class Items:
+    def __init__(self, values):
+        self.values = values
+
+    def __len__(self):
+        return len(self.values)
+
+    @property
+    def popped(self):
+        return self.values.pop()
+
+items = Items([1, 2, 3])
+"#,
+                None,
+                Some(&locals),
+            )
+            .unwrap();
+            let context = PyDict::new(py);
+            context
+                .set_item("items", locals.get_item("items").unwrap().unwrap())
+                .unwrap();
+
+            assert_eq!(template.render(py, Some(context), None).unwrap(), "332");
+        })
+    }
+
+    #[test]
+    fn test_render_for_single_pass_iterable_length() {
+        Python::initialize();
+
+        Python::attach(|py| {
+            let engine = EngineData::empty();
+            let template_string = "{{ stream|length }}|{% for x in stream %}{{ x }}{% endfor %}|\
+                {{ stream|length }}|{% for x in stream %}{{ x }}{% empty %}empty{% endfor %}"
+                .to_string();
+            let template = Template::new_from_string(py, template_string, &engine).unwrap();
+            let locals = PyDict::new(py);
+            py.run(
+                cr#"
+class Stream:
+    def __init__(self, values):
+        self.values = values
+
+    def __len__(self):
+        return len(self.values)
+
+    def __iter__(self):
+        while self.values:
+            yield self.values.pop(0)
+
+stream = Stream(["a", "b", "c"])
+"#,
+                None,
+                Some(&locals),
+            )
+            .unwrap();
+            let context = PyDict::new(py);
+            context
+                .set_item("stream", locals.get_item("stream").unwrap().unwrap())
+                .unwrap();
+
+            // The loop consumes the stream, so its length is 0 afterwards.
+            assert_eq!(
+                template.render(py, Some(context), None).unwrap(),
+                "3|abc|0|empty"
+            );
+        })
+    }
+
+    #[test]
+    fn test_render_template_variable_nested() {
+        Python::initialize();
+
+        Python::attach(|py| {
+            let engine = EngineData::empty();
+            let template_string = "Hello {{ user.profile.names.0 }}!".to_string();
+            let template = Template::new_from_string(py, template_string, &engine).unwrap();
+            let locals = PyDict::new(py);
+            py.run(
+                cr#"
+class User:
+    def __init__(self, names):
+        self.profile = {"names": names}
+
+user = User(["Lily"])
+"#,
+                None,
+                Some(&locals),
+            )
+            .unwrap();
+            let user = locals.get_item("user").unwrap().unwrap();
+            let context = PyDict::new(py);
+            context.set_item("user", user.into_any()).unwrap();
+
+            assert_eq!(
+                template.render(py, Some(context), None).unwrap(),
+                "Hello Lily!"
+            );
+        })
+    }
+
+    #[test]
+    fn test_render_if_callable_attributes() {
+        Python::initialize();
+
+        Python::attach(|py| {
+            let engine = EngineData::empty();
+            let template_string = "{% if user.is_authenticated %}a{% else %}b{% endif %}\
+                {% if user.is_staff %}c{% else %}d{% endif %}\
+                {% if user.is_active %}e{% else %}f{% endif %}"
+                .to_string();
+            let template = Template::new_from_string(py, template_string, &engine).unwrap();
+            let locals = PyDict::new(py);
+            py.run(
+                cr#"
+class User:
+    is_active = False
+
+    @property
+    def is_authenticated(self):
+        return True
+
+    def is_staff(self):
+        return False
+
+user = User()
+"#,
+                None,
+                Some(&locals),
+            )
+            .unwrap();
+            let user = locals.get_item("user").unwrap().unwrap();
+            let context = PyDict::new(py);
+            context.set_item("user", user.into_any()).unwrap();
+
+            assert_eq!(template.render(py, Some(context), None).unwrap(), "adf");
+        })
+    }
+
+    #[test]
+    fn test_render_if_in_dict_views() {
+        Python::initialize();
+
+        Python::attach(|py| {
+            let engine = EngineData::empty();
+            let template_string = "{% if 'b' in data.values %}a{% else %}b{% endif %}\
+                {% if 'a' in data.values %}c{% else %}d{% endif %}\
+                {% if 'a' in data.keys %}e{% else %}f{% endif %}\
+                {% if 2 not in data.keys %}g{% else %}h{% endif %}\
+                {% if pair in data.items %}i{% else %}j{% endif %}\
+                {% if missing in data.values %}k{% else %}l{% endif %}"
+                .to_string();
+            let template = Template::new_from_string(py, template_string, &engine).unwrap();
+            let data = PyDict::new(py);
+            data.set_item("a", "b").unwrap();
+            data.set_item(1, 2).unwrap();
+            let context = PyDict::new(py);
+            context.set_item("data", data).unwrap();
+            context.set_item("pair", ("a", "b")).unwrap();
+
+            assert_eq!(template.render(py, Some(context), None).unwrap(), "adegil");
+        })
+    }
+
+    #[test]
+    fn test_render_if_in_generator() {
+        Python::initialize();
+
+        Python::attach(|py| {
+            let engine = EngineData::empty();
+            let template_string = "{% if 5 not in gen %}a{% endif %}\
+                {% for x in gen %}{{ x }}{% endfor %}|\
+                {% if 2 not in other %}b{% else %}c{% endif %}\
+                {% if 2 in other %}d{% else %}e{% endif %}\
+                {% for x in other %}{{ x }}{% endfor %}|\
+                {% if 1 not in opaque %}f{% else %}g{% endif %}"
+                .to_string();
+            let template = Template::new_from_string(py, template_string, &engine).unwrap();
+            let context = PyDict::new(py);
+            py.run(
+                c"gen = (x for x in [1, 2, 3]); other = (x for x in [1, 2, 3]); opaque = object()",
+                None,
+                Some(&context),
+            )
+            .unwrap();
+
+            assert_eq!(template.render(py, Some(context), None).unwrap(), "a|ce|g");
+        })
+    }
+
+    #[test]
+    fn test_render_widthratio() {
+        Python::initialize();
+
+        Python::attach(|py| {
+            let engine = EngineData::empty();
+            let template_string = "{% widthratio 175 200 100 %} {% widthratio 1 2 1 %} \
+                {% widthratio 3 2 1 %} {% widthratio a 0 100 %} \
+                {% widthratio a b 100 as ratio %}[{{ ratio }}]"
+                .to_string();
+            let template = Template::new_from_string(py, template_string, &engine).unwrap();
+            let context = PyDict::new(py);
+            context.set_item("a", 50).unwrap();
+            context.set_item("b", 80).unwrap();
+
+            assert_eq!(
+                template.render(py, Some(context), None).unwrap(),
+                "88 0 2 0 [62]"
+            );
+        })
+    }
+
+    #[test]
+    fn test_render_widthratio_overflow() {
+        Python::initialize();
+
+        Python::attach(|py| {
+            let engine = EngineData::empty();
+            let template_string = "[{% widthratio big 1 100 %}][{% widthratio 1 big 100 %}]\
+                [{% widthratio 1 2 big %}]"
+                .to_string();
+            let template = Template::new_from_string(py, template_string, &engine).unwrap();
+            let context = PyDict::new(py);
+            let big = py.eval(c"10 ** 400", None, None).unwrap();
+            context.set_item("big", big).unwrap();
+
+            // Like Django, integers too large for a float render nothing.
+            assert_eq!(template.render(py, Some(context), None).unwrap(), "[][][]");
+        })
+    }
+
+    #[test]
+    fn test_render_if_equals_none() {
+        Python::initialize();
+
+        Python::attach(|py| {
+            let engine = EngineData::empty();
+            let template_string = "{% if x == None %}yes{% else %}no{% endif %}".to_string();
+            let template = Template::new_from_string(py, template_string, &engine).unwrap();
+
+            assert_eq!(template.render(py, None, None).unwrap(), "yes");
+
+            let context = PyDict::new(py);
+            context.set_item("x", py.None()).unwrap();
+            assert_eq!(template.render(py, Some(context), None).unwrap(), "yes");
+
+            let context = PyDict::new(py);
+            context.set_item("x", 0).unwrap();
+            assert_eq!(template.render(py, Some(context), None).unwrap(), "no");
+        })
+    }
+
+    #[test]
+    fn test_render_if_bool_int_equality() {
+        Python::initialize();
+
+        Python::attach(|py| {
+            let engine = EngineData::empty();
+            let template_string = "{% if count == True %}a{% endif %}\
+                {% if flag == 1 %}b{% endif %}\
+                {% if True == count %}c{% endif %}\
+                {% if 1 == flag %}d{% endif %}\
+                {% if zero == False %}e{% endif %}\
+                {% if False != 0 %}f{% endif %}\
+                {% if two == True %}g{% endif %}\
+                {% for x in 'x' %}{% if forloop.first == 1 %}h{% endif %}\
+                {% if forloop.last == count %}i{% endif %}{% endfor %}\
+                {% if flag == 1.0 %}j{% endif %}"
+                .to_string();
+            let template = Template::new_from_string(py, template_string, &engine).unwrap();
+            let context = PyDict::new(py);
+            context.set_item("count", 1).unwrap();
+            context.set_item("flag", true).unwrap();
+            context.set_item("zero", 0).unwrap();
+            context.set_item("two", 2).unwrap();
+
+            assert_eq!(
+                template.render(py, Some(context), None).unwrap(),
+                "abcdehij"
+            );
+        })
+    }
+
+    #[test]
+    fn test_render_if_numeric_literal() {
+        Python::initialize();
+
+        Python::attach(|py| {
+            let engine = EngineData::empty();
+            let template_string = "{% if 1 %}a{% endif %}{% if 0 %}b{% endif %}\
+                {% if 0.0 %}c{% endif %}{% if -1 %}d{% endif %}\
+                {% if 1.5 %}e{% endif %}{% if not 0 %}f{% endif %}\
+                {% if 0 or -0.0 %}g{% else %}h{% endif %}"
+                .to_string();
+            let template = Template::new_from_string(py, template_string, &engine).unwrap();
+
+            assert_eq!(template.render(py, None, None).unwrap(), "adefh");
+        })
+    }
+
+    #[test]
+    fn test_render_long_elif_chain() {
+        Python::initialize();
+
+        Python::attach(|py| {
+            let engine = EngineData::empty();
+            let template_string = format!(
+                "{{% if 0 %}}{}{{% elif 1 %}}last{{% else %}}else{{% endif %}}",
+                "{% elif 0 %}".repeat(1000)
+            );
+            let template = Template::new_from_string(py, template_string, &engine).unwrap();
+
+            assert_eq!(template.render(py, None, None).unwrap(), "last");
+        })
+    }
+
+    #[test]
+    fn test_engine_from_string() {
+        Python::initialize();
+
+        Python::attach(|py| {
+            let engine = Engine::new(
+                py,
+                None,
+                false,
+                None,
+                false,
+                None,
+                "".to_string(),
+                "utf-8".to_string(),
+                None,
+                None,
+                false,
+                false,
+                false,
+                crate::parse::DEFAULT_MAX_DEPTH,
+                false,
+            )
+            .unwrap();
+            let template_string = PyString::new(py, "Hello {{ user }}!");
+            let template = engine.from_string(template_string, None).unwrap();
+            let context = PyDict::new(py);
+
+            assert_eq!(template.render(py, Some(context), None).unwrap(), "Hello !");
+        })
+    }
+
+    #[test]
+    fn test_render_assign_reserved_names() {
+        Python::initialize();
+
+        Python::attach(|py| {
+            let engine = EngineData::empty();
+            let template_string = "{% for True in items %}{{ True }}{% endfor %}|\
+                {% with None=1 %}{{ None }}{% endwith %}|{{ True }}{{ None }}"
+                .to_string();
+            let template = Template::new_from_string(py, template_string, &engine).unwrap();
+            let context = PyDict::new(py);
+            context.set_item("items", vec![1, 2]).unwrap();
+
+            // Like Django, the names shadow the literals in the context.
+            assert_eq!(
+                template.render(py, Some(context), None).unwrap(),
+                "12|1|TrueNone"
+            );
+        })
+    }
+
+    #[test]
+    fn test_include_template_name_list() {
+        use pyo3::IntoPyObject;
+
+        Python::initialize();
+
+        Python::attach(|py| {
+            let engine = Engine::new(
+                py,
+                Some(vec!["tests/templates"].into_pyobject(py).unwrap()),
+                false,
+                None,
+                false,
+                None,
+                "".to_string(),
+                "utf-8".to_string(),
+                None,
+                None,
+                false,
+                false,
+                false,
+                crate::parse::DEFAULT_MAX_DEPTH,
+                false,
+            )
+            .unwrap();
+            let engine = Bound::new(py, engine).unwrap();
+            let template = engine
+                .call_method1("from_string", ("{% include names %}|{% include name %}",))
+                .unwrap();
+            let context = PyDict::new(py);
+            context
+                .set_item("names", ("basic.txt", "missing.html"))
+                .unwrap();
+            context.set_item("name", "basic.txt").unwrap();
+            context.set_item("user", "Lily").unwrap();
+
+            let rendered: String = template
+                .call_method1("render", (context,))
+                .unwrap()
+                .extract()
+                .unwrap();
+            assert_eq!(rendered, "Hello Lily!\n|Hello Lily!\n");
+        })
+    }
+
+    #[test]
+    fn test_render_from_threads() {
+        use pyo3::IntoPyObject;
+
+        Python::initialize();
+
+        let template = Python::attach(|py| {
+            let engine = Engine::new(
+                py,
+                Some(vec!["tests/templates"].into_pyobject(py).unwrap()),
+                false,
+                None,
+                false,
+                None,
+                "".to_string(),
+                "utf-8".to_string(),
+                None,
+                None,
+                false,
+                false,
+                false,
+                crate::parse::DEFAULT_MAX_DEPTH,
+                false,
+            )
+            .unwrap();
+            let engine = Bound::new(py, engine).unwrap();
+            engine
+                .call_method1(
+                    "from_string",
+                    ("{% for x in items %}{% for y in items %}\
+                    {{ forloop.parentloop.counter }}{{ y }}{% endfor %},{% endfor %}\
+                    {% include name %}",),
+                )
+                .unwrap()
+                .unbind()
+        });
+
+        // Each render gets its own context, so threads sharing the parsed
+        // template and the engine's template cache don't see each other.
+        std::thread::scope(|scope| {
+            for thread in 0..8 {
+                let template = &template;
+                scope.spawn(move || {
+                    Python::attach(|py| {
+                        for _ in 0..20 {
+                            let context = PyDict::new(py);
+                            let items = vec![thread; thread % 3 + 1];
+                            context.set_item("items", &items).unwrap();
+                            context.set_item("name", "basic.txt").unwrap();
+                            context.set_item("user", thread).unwrap();
+
+                            let rendered: String = template
+                                .call_method1(py, "render", (context,))
+                                .unwrap()
+                                .extract(py)
+                                .unwrap();
+                            let loops: String = (1..=items.len())
+                                .map(|i| format!("{i}{thread}").repeat(items.len()) + ",")
+                                .collect();
+                            assert_eq!(rendered, format!("{loops}Hello {thread}!\n"));
+                        }
+                    })
+                });
+            }
+        });
+    }
+
+    #[test]
+    fn test_from_string_autoescape_override() {
+        Python::initialize();
+
+        Python::attach(|py| {
+            let engine = Engine::new(
+                py,
+                None,
+                false,
+                None,
+                false,
+                None,
+                "".to_string(),
+                "utf-8".to_string(),
+                None,
+                None,
+                false,
+                false,
+                false,
+                crate::parse::DEFAULT_MAX_DEPTH,
+                false,
+            )
+            .unwrap();
+            assert!(!engine.autoescape());
+
+            let template_code = PyString::new(py, "{{ html }}");
+            let context = PyDict::new(py);
+            context.set_item("html", "<p>").unwrap();
+
+            let template = engine.from_string(template_code.clone(), None).unwrap();
+            let rendered = template.render(py, Some(context.clone()), None).unwrap();
+            assert_eq!(rendered, "<p>");
+
+            let template = engine.from_string(template_code, Some(true)).unwrap();
+            let rendered = template.render(py, Some(context), None).unwrap();
+            assert_eq!(rendered, "&lt;p&gt;");
+        })
+    }
+
+    #[test]
+    fn test_render_with_timings() {
+        use pyo3::types::PyAnyMethods;
+
+        Python::initialize();
+
+        Python::attach(|py| {
+            let engine = EngineData::empty();
+            let template_string = "Hello {{ user }}!".to_string();
+            let template = Template::new_from_string(py, template_string, &engine).unwrap();
+            let context = PyDict::new(py);
+            context.set_item("user", "Lily").unwrap();
+
+            let (rendered, timings) = template
+                .render_with_timings(py, Some(context), None)
+                .unwrap();
+            assert_eq!(rendered, "Hello Lily!");
+            assert_eq!(timings.len(), 3);
+            for index in 0..3 {
+                let seconds: f64 = timings.get_item(index).unwrap().unwrap().extract().unwrap();
+                assert!(seconds >= 0.0);
+            }
+        })
+    }
+
+    #[test]
+    fn test_template_source_and_origin() {
+        Python::initialize();
+
+        Python::attach(|py| {
+            let engine = EngineData::empty();
+            let template_string = "Hello {{ user }}!".to_string();
+            let template = Template::new_from_string(py, template_string, &engine).unwrap();
+            assert_eq!(template.source(), "Hello {{ user }}!");
+            assert_eq!(template.origin(), "<unknown source>");
+
+            let filename = std::path::PathBuf::from("templates/basic.txt");
+            let template = Template::new(py, "Hello!", filename, &engine).unwrap();
+            assert_eq!(template.source(), "Hello!");
+            assert_eq!(template.origin(), "templates/basic.txt");
+        })
+    }
+
+    #[test]
+    fn test_clone_template() {
+        use std::collections::HashMap;
+
+        use pyo3::IntoPyObject;
+        use pyo3::types::{PyAnyMethods, PyListMethods};
+
+        Python::initialize();
+
+        Python::attach(|py| {
+            let cwd = std::env::current_dir().unwrap();
+            let sys_path = py.import("sys").unwrap().getattr("path").unwrap();
+            let sys_path = sys_path.cast().unwrap();
+            sys_path.append(cwd.to_string_lossy()).unwrap();
+            let engine = Engine::new(
                 py,
                 Some(vec!["tests/templates"].into_pyobject(py).unwrap()),
                 false,
@@ -720,6 +2069,10 @@ user = User(["Lily"])
                 ),
                 None,
                 false,
+                false,
+                false,
+                crate::parse::DEFAULT_MAX_DEPTH,
+                false,
             )
             .unwrap();
             let template = engine
@@ -770,6 +2123,10 @@ user = User(["Lily"])
                 ),
                 None,
                 false,
+                false,
+                false,
+                crate::parse::DEFAULT_MAX_DEPTH,
+                false,
             )
             .unwrap();
 
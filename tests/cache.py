@@ -0,0 +1,20 @@
+from django.core.cache.backends.base import DEFAULT_TIMEOUT
+from django.core.cache.backends.locmem import LocMemCache
+
+
+class StubCache(LocMemCache):
+    """A local memory cache which records how it was used."""
+
+    def __init__(self, name, params):
+        super().__init__(name, params)
+        self.gets = []
+        self.sets = []
+
+    def get(self, key, default=None, version=None):
+        value = super().get(key, default, version)
+        self.gets.append((key, value))
+        return value
+
+    def set(self, key, value, timeout=DEFAULT_TIMEOUT, version=None):
+        self.sets.append((key, timeout))
+        super().set(key, value, timeout, version)
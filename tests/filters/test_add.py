@@ -1,3 +1,4 @@
+import pytest
 from django.template.base import VariableDoesNotExist
 
 
@@ -53,6 +54,21 @@ def test_add_incompatible(assert_render):
     assert_render(template=template, context={"foo": [1], "bar": 2}, expected="")
 
 
+@pytest.mark.parametrize(
+    "template,context",
+    [
+        ('{{ "item-"|add:5 }}', {}),
+        ("{{ prefix|add:id }}", {"prefix": "item-", "id": 5}),
+        ('{{ 5|add:"-item" }}', {}),
+        ("{{ id|add:suffix }}", {"id": 5, "suffix": "-item"}),
+    ],
+)
+def test_add_string_and_integer(assert_render, template, context):
+    # Django tries integer addition, then `+`, which raises for mixed types,
+    # so it doesn't concatenate.
+    assert_render(template=template, context=context, expected="")
+
+
 def test_add_float(assert_render):
     template = "{{ foo|add:bar}}"
     assert_render(template=template, context={"foo": 1.2, "bar": 2.9}, expected="3")
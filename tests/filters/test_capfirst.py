@@ -34,6 +34,18 @@ from django.utils.safestring import mark_safe
             "[&#x27;hello&#x27;]",
             id="capfirst_for_list",
         ),
+        pytest.param(
+            "{{ safe_html|capfirst }}",
+            {"safe_html": mark_safe("<em>hello</em> & goodbye")},
+            "<em>hello</em> & goodbye",
+            id="capfirst_safe_input_stays_safe",
+        ),
+        pytest.param(
+            "{{ safe_html|capfirst }}",
+            {"safe_html": mark_safe("hello <em>world</em>")},
+            "Hello <em>world</em>",
+            id="capfirst_safe_input_capitalised",
+        ),
     ],
 )
 def test_capfirst(assert_render, template, context, expected):
@@ -33,6 +33,19 @@ def test_center_with_odd_width(assert_render):
     assert_render(template, context, expected)
 
 
+@pytest.mark.parametrize(
+    "width,expected", [(7, "  test "), ("7", "  test "), (7.5, "  test ")]
+)
+def test_center_variable_width(assert_render, width, expected):
+    template = "{{ var|center:width }}"
+    assert_render(template, {"var": "test", "width": width}, expected)
+
+
+def test_center_unicode(assert_render):
+    template = "{{ var|center:9 }}"
+    assert_render(template, {"var": "héllo"}, "  héllo  ")
+
+
 def test_add_no_argument(assert_parse_error):
     template = "{{ foo|center }}"
     django_message = "center requires 2 arguments, 1 provided"
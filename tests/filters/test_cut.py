@@ -0,0 +1,85 @@
+"""
+Adapted from
+https://github.com/django/django/blob/5.1/tests/template_tests/filter_tests/test_cut.py
+"""
+
+import pytest
+from django.utils.safestring import mark_safe
+
+
+def test_cut01(assert_render):
+    template = '{% autoescape off %}{{ a|cut:"x" }} {{ b|cut:"x" }}{% endautoescape %}'
+    context = {"a": "x&y", "b": mark_safe("x&amp;y")}
+    assert_render(template, context, "&y &amp;y")
+
+
+def test_cut02(assert_render):
+    template = '{{ a|cut:"x" }} {{ b|cut:"x" }}'
+    context = {"a": "x&y", "b": mark_safe("x&amp;y")}
+    assert_render(template, context, "&amp;y &amp;y")
+
+
+def test_cut03(assert_render):
+    template = '{% autoescape off %}{{ a|cut:"&" }} {{ b|cut:"&" }}{% endautoescape %}'
+    context = {"a": "x&y", "b": mark_safe("x&amp;y")}
+    assert_render(template, context, "xy xamp;y")
+
+
+def test_cut04(assert_render):
+    template = '{{ a|cut:"&" }} {{ b|cut:"&" }}'
+    context = {"a": "x&y", "b": mark_safe("x&amp;y")}
+    assert_render(template, context, "xy xamp;y")
+
+
+def test_cut05(assert_render):
+    # Passing ';' to cut can break existing HTML entities, so those strings
+    # are auto-escaped.
+    template = '{% autoescape off %}{{ a|cut:";" }} {{ b|cut:";" }}{% endautoescape %}'
+    context = {"a": "x&y", "b": mark_safe("x&amp;y")}
+    assert_render(template, context, "x&y x&ampy")
+
+
+def test_cut06(assert_render):
+    template = '{{ a|cut:";" }} {{ b|cut:";" }}'
+    context = {"a": "x&y", "b": mark_safe("x&amp;y")}
+    assert_render(template, context, "x&amp;y x&amp;ampy")
+
+
+def test_cut_space_keeps_safe(assert_render):
+    template = '{{ a|cut:" " }}'
+    context = {"a": mark_safe("<b>a b</b>")}
+    assert_render(template, context, "<b>ab</b>")
+
+
+def test_cut_integer(assert_render):
+    template = '{{ a|cut:"2" }}'
+    assert_render(template, {"a": 123}, "13")
+
+
+def test_cut_missing_value(assert_render):
+    template = '{{ a|cut:"2" }}'
+    assert_render(template, {}, "")
+
+
+def test_cut_non_string_argument(template_engine):
+    template = template_engine.from_string("{{ a|cut:2 }}")
+    with pytest.raises(TypeError) as exc_info:
+        template.render({"a": "123"})
+
+    assert str(exc_info.value) == "replace() argument 1 must be str, not int"
+
+
+def test_cut_missing_argument(assert_parse_error):
+    template = "{{ foo|cut }}"
+    django_message = "cut requires 2 arguments, 1 provided"
+    rusty_message = """\
+  × Expected an argument
+   ╭────
+ 1 │ {{ foo|cut }}
+   ·        ─┬─
+   ·         ╰── here
+   ╰────
+"""
+    assert_parse_error(
+        template=template, django_message=django_message, rusty_message=rusty_message
+    )
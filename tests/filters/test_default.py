@@ -0,0 +1,99 @@
+import pytest
+from django.template.base import VariableDoesNotExist
+from django.utils.safestring import mark_safe
+
+MISSING = object()
+
+CASES = [
+    # (filter, value, autoescaped output, output without autoescape)
+    ("default:'<b>'", "<i>", "&lt;i&gt;", "<i>"),
+    ("default:'<b>'", mark_safe("<i>"), "<i>", "<i>"),
+    ("default:'<b>'", MISSING, "<b>", "<b>"),
+    ("default:'<b>'", None, "<b>", "<b>"),
+    ("default:'<b>'", "", "<b>", "<b>"),
+    ("default:'<b>'", 0, "<b>", "<b>"),
+    ("default:'<b>'", [], "<b>", "<b>"),
+    ("default:'<b>'", False, "<b>", "<b>"),
+    ("default:'<b>'", [0], "[0]", "[0]"),
+    ("default:fallback", MISSING, "&lt;b&gt;", "<b>"),
+    ("default_if_none:'<b>'", "<i>", "&lt;i&gt;", "<i>"),
+    ("default_if_none:'<b>'", mark_safe("<i>"), "<i>", "<i>"),
+    ("default_if_none:'<b>'", MISSING, "", ""),
+    ("default_if_none:'<b>'", None, "<b>", "<b>"),
+    ("default_if_none:'<b>'", "", "", ""),
+    ("default_if_none:'<b>'", 0, "0", "0"),
+    ("default_if_none:fallback", None, "&lt;b&gt;", "<b>"),
+]
+
+
+def build_context(value):
+    context = {"fallback": "<b>"}
+    if value is not MISSING:
+        context["value"] = value
+    return context
+
+
+@pytest.mark.parametrize("filter,value,escaped,unescaped", CASES)
+def test_default_autoescape(assert_render, filter, value, escaped, unescaped):
+    template = f"{{{{ value|{filter} }}}}"
+    assert_render(template=template, context=build_context(value), expected=escaped)
+
+
+@pytest.mark.parametrize("filter,value,escaped,unescaped", CASES)
+def test_default_autoescape_off(assert_render, filter, value, escaped, unescaped):
+    template = f"{{% autoescape off %}}{{{{ value|{filter} }}}}{{% endautoescape %}}"
+    assert_render(template=template, context=build_context(value), expected=unescaped)
+
+
+def test_default_if_none_missing_argument(assert_parse_error):
+    template = "{{ foo|default_if_none }}"
+    django_message = "default_if_none requires 2 arguments, 1 provided"
+    rusty_message = """\
+  × Expected an argument
+   ╭────
+ 1 │ {{ foo|default_if_none }}
+   ·        ───────┬───────
+   ·               ╰── here
+   ╰────
+"""
+    assert_parse_error(
+        template=template, django_message=django_message, rusty_message=rusty_message
+    )
+
+
+def test_default_bool_error(template_engine):
+    class Broken:
+        def __bool__(self):
+            raise ValueError("no truth")
+
+    template = template_engine.from_string("{{ value|default:'x' }}")
+    with pytest.raises(ValueError, match="no truth"):
+        template.render({"value": Broken()})
+
+
+def test_default_argument_with_colons(assert_render):
+    template = '{{ t|default:"Y-m-d H:i:s" }}|{{ t|default:\'a:b\'|upper }}'
+    assert_render(template=template, context={}, expected="Y-m-d H:i:s|A:B")
+
+
+@pytest.mark.parametrize("filter", ["default", "default_if_none"])
+def test_default_missing_argument_variable(assert_render_error, filter):
+    template = "{{ x|%s:missing }}" % filter
+    django_message = "Failed lookup for key [missing] in [{'True': True, 'False': False, 'None': None}, {'x': 'a'}]"
+    padding = " " * len(filter)
+    rusty_message = f"""\
+  × Failed lookup for key [missing] in {{"False": False, "None": None, "True":
+  │ True, "x": 'a'}}
+   ╭────
+ 1 │ {template}
+   ·      {padding} ───┬───
+   ·      {padding}    ╰── key
+   ╰────
+"""
+    assert_render_error(
+        template=template,
+        context={"x": "a"},
+        exception=VariableDoesNotExist,
+        django_message=django_message,
+        rusty_message=rusty_message,
+    )
@@ -0,0 +1,98 @@
+import pytest
+
+
+class Person:
+    def __init__(self, name, age):
+        self.name = name
+        self.age = age
+
+    def __repr__(self):
+        return self.name
+
+
+def test_dictsort_by_index(assert_render):
+    template = "{{ pairs|dictsort:0 }}"
+    pairs = [(2, "b"), (1, "c"), (3, "a")]
+    expected = "[(1, &#x27;c&#x27;), (2, &#x27;b&#x27;), (3, &#x27;a&#x27;)]"
+    assert_render(template, {"pairs": pairs}, expected)
+
+
+def test_dictsort_by_second_index(assert_render):
+    template = "{% for x, y in pairs|dictsort:1 %}{{ x }}{{ y }} {% endfor %}"
+    pairs = [(2, "b"), (1, "c"), (3, "a")]
+    assert_render(template, {"pairs": pairs}, "3a 2b 1c ")
+
+
+def test_dictsort_index_of_lists(assert_render):
+    template = "{% for x in items|dictsort:1 %}{{ x.0 }}{% endfor %}"
+    items = [["a", 3], ["b", 1], ["c", 2]]
+    assert_render(template, {"items": items}, "bca")
+
+
+def test_dictsort_string_index(assert_render):
+    template = '{{ pairs|dictsort:"0" }}'
+    assert_render(template, {"pairs": [(2, "b"), (1, "c")]}, "")
+
+
+def test_dictsort_by_key(assert_render):
+    template = "{% for p in people|dictsort:'age' %}{{ p.name }} {% endfor %}"
+    people = [{"name": "b", "age": 30}, {"name": "a", "age": 20}]
+    assert_render(template, {"people": people}, "a b ")
+
+
+def test_dictsort_by_attribute_path(assert_render):
+    template = "{% for p in people|dictsort:'friend.age' %}{{ p.name }} {% endfor %}"
+    people = [
+        {"name": "b", "friend": Person("x", 9)},
+        {"name": "a", "friend": Person("y", 3)},
+    ]
+    assert_render(template, {"people": people}, "a b ")
+
+
+def test_dictsort_private_attribute(assert_render):
+    template = "{{ people|dictsort:'_age' }}"
+    assert_render(template, {"people": [Person("a", 1)]}, "")
+
+
+def test_dictsort_not_sortable(assert_render):
+    template = "{{ value|dictsort:'age' }}"
+    assert_render(template, {"value": 5}, "")
+
+
+def test_dictsort_mixed_types(assert_render):
+    template = "{{ pairs|dictsort:0 }}"
+    assert_render(template, {"pairs": [(1, "a"), ("b", 2)]}, "")
+
+
+@pytest.mark.parametrize(
+    "pairs",
+    [
+        [(float("nan"), "a"), (2.0, "b"), (float("nan"), "c"), (1.0, "d")],
+        [({1, 2}, "a"), ({3}, "b"), ({1}, "c"), (set(), "d")],
+    ],
+)
+def test_dictsort_partial_order(assert_render, pairs):
+    template = "{% autoescape off %}{{ pairs|dictsort:0 }}{% endautoescape %}"
+    expected = str(sorted(pairs, key=lambda pair: pair[0]))
+    assert_render(template, {"pairs": pairs}, expected)
+
+
+def test_dictsort_missing_value(assert_render):
+    template = "{{ value|dictsort:0 }}"
+    assert_render(template, {}, "[]")
+
+
+def test_dictsort_missing_argument(assert_parse_error):
+    template = "{{ foo|dictsort }}"
+    django_message = "dictsort requires 2 arguments, 1 provided"
+    rusty_message = """\
+  × Expected an argument
+   ╭────
+ 1 │ {{ foo|dictsort }}
+   ·        ────┬───
+   ·            ╰── here
+   ╰────
+"""
+    assert_parse_error(
+        template=template, django_message=django_message, rusty_message=rusty_message
+    )
@@ -1,3 +1,7 @@
+import pytest
+from django.utils.safestring import mark_safe
+
+
 def test_escape(assert_render):
     template = "{{ html|escape }}"
     html = "<p>Hello World!</p>"
@@ -60,3 +64,21 @@ def test_escape_autoescape_off_lower(assert_render):
     html = "<p>Hello World!</p>"
     escaped = "&lt;p&gt;hello world!&lt;/p&gt;"
     assert_render(template=template, context={"html": html}, expected=escaped)
+
+
+@pytest.mark.parametrize(
+    "template,expected",
+    [
+        ("{{ html|escape|safe }}", "&lt;b&gt;&amp;amp;&lt;/b&gt;"),
+        ("{{ html|safe|escape }}", "<b>&amp;</b>"),
+        ("{{ html|escape|escape }}", "&lt;b&gt;&amp;amp;&lt;/b&gt;"),
+        ("{{ html|escape|safe|escape }}", "&lt;b&gt;&amp;amp;&lt;/b&gt;"),
+        ("{{ safe_html|escape }}", "<b>&amp;</b>"),
+        ("{{ safe_html|escape|escape }}", "<b>&amp;</b>"),
+    ],
+)
+@pytest.mark.parametrize("autoescape", ["on", "off"])
+def test_escape_safe_boundary(assert_render, template, expected, autoescape):
+    template = f"{{% autoescape {autoescape} %}}{template}{{% endautoescape %}}"
+    context = {"html": "<b>&amp;</b>", "safe_html": mark_safe("<b>&amp;</b>")}
+    assert_render(template=template, context=context, expected=expected)
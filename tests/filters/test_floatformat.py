@@ -0,0 +1,165 @@
+"""
+Adapted from
+https://github.com/django/django/blob/5.1/tests/template_tests/filter_tests/test_floatformat.py
+"""
+
+from decimal import Decimal
+
+import pytest
+from django.test import override_settings
+from django.utils import translation
+from django.utils.safestring import mark_safe
+
+
+def test_floatformat01(assert_render):
+    template = (
+        "{% autoescape off %}{{ a|floatformat }} {{ b|floatformat }}{% endautoescape %}"
+    )
+    context = {"a": "1.42", "b": mark_safe("1.42")}
+    assert_render(template, context, "1.4 1.4")
+
+
+def test_floatformat02(assert_render):
+    template = "{{ a|floatformat }} {{ b|floatformat }}"
+    context = {"a": "1.42", "b": mark_safe("1.42")}
+    assert_render(template, context, "1.4 1.4")
+
+
+@pytest.mark.parametrize(
+    "value,argument,expected",
+    [
+        (7.7, None, "7.7"),
+        (7.0, None, "7"),
+        (0.7, None, "0.7"),
+        (-0.7, None, "-0.7"),
+        (0.07, None, "0.1"),
+        (-0.07, None, "-0.1"),
+        (0.007, None, "0.0"),
+        (0.0, None, "0"),
+        (7.7, 0, "8"),
+        (7.7, 3, "7.700"),
+        (6.000000, 3, "6.000"),
+        (6.200000, 3, "6.200"),
+        (6.200000, -3, "6.200"),
+        (13.1031, -3, "13.103"),
+        (11.1197, -2, "11.12"),
+        (11.0000, -2, "11"),
+        (11.000001, -2, "11.00"),
+        (8.2798, 3, "8.280"),
+        (5555.555, 2, "5555.56"),
+        (001.3000, 2, "1.30"),
+        (0.12345, 2, "0.12"),
+        (Decimal("555.555"), 2, "555.56"),
+        (Decimal("09.000"), None, "9"),
+        ("foo", None, ""),
+        (13.1031, "'bar'", "13.1031"),
+        (18.125, 2, "18.13"),
+        ("foo", "'bar'", ""),
+        ("¿Cómo esta usted?", None, ""),
+        (None, None, ""),
+        (-1.323297138040798e35, 2, "-132329713804079800000000000000000000.00"),
+        (-1.323297138040798e35, -2, "-132329713804079800000000000000000000"),
+        (1.5e-15, 20, "0.00000000000000150000"),
+        (1.5e-15, -20, "0.00000000000000150000"),
+        (1.00000000000000015, 16, "1.0000000000000002"),
+    ],
+)
+def test_inputs(assert_render, value, argument, expected):
+    if argument is None:
+        template = "{{ value|floatformat }}"
+    else:
+        template = f"{{{{ value|floatformat:{argument} }}}}"
+    assert_render(template, {"value": value}, expected)
+
+
+def test_missing_value(assert_render):
+    assert_render("{{ value|floatformat }}", {}, "")
+
+
+def test_zero_values(assert_render):
+    assert_render("{{ value|floatformat:7 }}", {"value": 0}, "0.0000000")
+    assert_render("{{ value|floatformat:10 }}", {"value": 0.0}, "0.0000000000")
+    assert_render(
+        "{{ value|floatformat:2 }}", {"value": Decimal("0.000000000")}, "0.00"
+    )
+
+
+def test_negative_zero_values(assert_render):
+    assert_render("{{ value|floatformat }}", {"value": -0.01}, "0.0")
+    assert_render("{{ value|floatformat:2 }}", {"value": -0.0001}, "0.00")
+    assert_render("{{ value|floatformat }}", {"value": -0.0}, "0")
+
+
+def test_infinity(assert_render):
+    assert_render("{{ value|floatformat }}", {"value": float("inf")}, "inf")
+    assert_render("{{ value|floatformat }}", {"value": float("nan")}, "nan")
+
+
+@override_settings(USE_THOUSAND_SEPARATOR=False)
+def test_grouping_off(assert_render):
+    context = {"value": 1234567.89}
+    with translation.override("en"):
+        assert_render("{{ value|floatformat:2 }}", context, "1234567.89")
+        assert_render('{{ value|floatformat:"2g" }}', context, "1,234,567.89")
+
+
+@override_settings(USE_THOUSAND_SEPARATOR=True)
+def test_grouping_on(assert_render):
+    context = {"value": 1234567.89}
+    with translation.override("en"):
+        assert_render("{{ value|floatformat:2 }}", context, "1,234,567.89")
+        assert_render('{{ value|floatformat:"2u" }}', context, "1234567.89")
+    with translation.override("de"):
+        assert_render("{{ value|floatformat:2 }}", context, "1.234.567,89")
+        assert_render('{{ value|floatformat:"2u" }}', context, "1234567.89")
+
+
+@override_settings(
+    USE_THOUSAND_SEPARATOR=True, THOUSAND_SEPARATOR="'", NUMBER_GROUPING=(3, 2, 0)
+)
+def test_grouping_unlocalized_settings(assert_render):
+    context = {"value": 1234567.89}
+    assert_render('{{ value|floatformat:"2gu" }}', context, "12'34'567.89")
+
+
+def test_grouping_forced_integer(assert_render):
+    with translation.override("en"):
+        assert_render('{{ value|floatformat:"g" }}', {"value": 1234567}, "1,234,567")
+
+
+@pytest.mark.parametrize(
+    "value,argument,expected",
+    [
+        (-0.0, -1, "0"),
+        (-0.0, 0, "0"),
+        (-0.0, 2, "0.00"),
+        (-0.001, 2, "0.00"),
+        (-0.4, 0, "0"),
+        (-2.5, 0, "-3"),
+        (2.675, 2, "2.68"),
+        (1.005, 2, "1.01"),
+        (Decimal("1E+30"), 2, "1000000000000000000000000000000.00"),
+        (1e-300, 3, "0.000"),
+    ],
+)
+def test_floatformat_rounding_edge_cases(assert_render, value, argument, expected):
+    template = "{{ value|floatformat:argument }}"
+    context = {"value": value, "argument": argument}
+    assert_render(template, context, expected)
+
+
+@pytest.mark.parametrize(
+    "value,expected",
+    [
+        (float("inf"), "inf"),
+        (float("-inf"), "-inf"),
+        (float("nan"), "nan"),
+        (Decimal("Infinity"), "Infinity"),
+        (Decimal("NaN"), "NaN"),
+    ],
+)
+@pytest.mark.parametrize("argument", [-1, 0, 2])
+def test_floatformat_special_values(assert_render, value, argument, expected):
+    template = "{{ value|floatformat:argument }}"
+    context = {"value": value, "argument": argument}
+    assert_render(template, context, expected)
@@ -0,0 +1,180 @@
+"""
+Adapted from
+https://github.com/django/django/blob/5.1/tests/humanize_tests/tests.py
+"""
+
+from datetime import datetime, timedelta
+from datetime import timezone as dt_timezone
+from decimal import Decimal
+from unittest.mock import patch
+
+import pytest
+from django.template import engines
+from django.template.exceptions import TemplateSyntaxError
+from django.test import override_settings
+
+
+@pytest.mark.parametrize(
+    "value,expected",
+    [
+        (100, "100"),
+        (1000, "1,000"),
+        (10123, "10,123"),
+        (-10123, "-10,123"),
+        (1000000, "1,000,000"),
+        (1234567.25, "1,234,567.25"),
+        (Decimal("1234567.1234567"), "1,234,567.1234567"),
+        ("1234567", "1,234,567"),
+        ("-1234567.1234567", "-1,234,567.1234567"),
+        ("the quick brown fox", "the quick brown fox"),
+        (None, "None"),
+        (True, "1"),
+    ],
+)
+def test_intcomma(assert_render, value, expected):
+    template = "{% load humanize %}{{ value|intcomma }}"
+    assert_render(template, {"value": value}, expected)
+
+
+@pytest.mark.parametrize(
+    "value,expected",
+    [
+        (1000000, "1,000,000"),
+        ("1234567.1234567", "1,234,567.1234567"),
+        (Decimal("-1234567.25"), "-1,234,567.25"),
+    ],
+)
+def test_intcomma_without_l10n(assert_render, value, expected):
+    template = "{% load humanize %}{{ value|intcomma:False }}"
+    assert_render(template, {"value": value}, expected)
+
+
+def test_intcomma_missing(assert_render):
+    template = "{% load humanize %}{{ missing|intcomma }}"
+    assert_render(template, {}, "")
+
+
+@pytest.mark.parametrize(
+    "value,expected",
+    [
+        (1, "1st"),
+        (2, "2nd"),
+        (3, "3rd"),
+        (4, "4th"),
+        (11, "11th"),
+        (12, "12th"),
+        (13, "13th"),
+        (22, "22nd"),
+        (101, "101st"),
+        (111, "111th"),
+        ("23", "23rd"),
+        (-1, "-1"),
+        ("-12", "-12"),
+        ("something", "something"),
+        (None, "None"),
+    ],
+)
+def test_ordinal(assert_render, value, expected):
+    template = "{% load humanize %}{{ value|ordinal }}"
+    assert_render(template, {"value": value}, expected)
+
+
+def test_ordinal_missing(assert_render):
+    template = "{% load humanize %}{{ missing|ordinal }}"
+    assert_render(template, {}, "")
+
+
+def test_ordinal_unexpected_argument():
+    template = "{% load humanize %}{{ 1|ordinal:'x' }}"
+    with pytest.raises(TemplateSyntaxError) as exc_info:
+        engines["rusty"].from_string(template)
+
+    assert "ordinal filter does not take an argument" in str(exc_info.value)
+
+
+@pytest.mark.parametrize(
+    "value,expected",
+    [
+        (100, "100"),
+        (999999, "999999"),
+        ("999999", "999999"),
+        (1000000, "1.0 million"),
+        (1200000, "1.2 million"),
+        (1290000, "1.3 million"),
+        (-1200000, "-1.2 million"),
+        ("1234567", "1.2 million"),
+        (1000000000, "1.0 billion"),
+        (2000000000, "2.0 billion"),
+        (6000000000000, "6.0 trillion"),
+        (1300000000000000, "1.3 quadrillion"),
+        (3500000000000000000000, "3.5 sextillion"),
+        (8100000000000000000000000000000000, "8.1 decillion"),
+        (10**101, "10.0 googol"),
+        (10**103, str(10**103)),
+        ("the quick brown fox", "the quick brown fox"),
+        (None, "None"),
+    ],
+)
+def test_intword(assert_render, value, expected):
+    template = "{% load humanize %}{{ value|intword }}"
+    assert_render(template, {"value": value}, expected)
+
+
+def test_intword_missing(assert_render):
+    template = "{% load humanize %}{{ missing|intword }}"
+    assert_render(template, {}, "")
+
+
+NOW = datetime(2012, 3, 9, 22, 30)
+
+
+class MockDateTime(datetime):
+    @classmethod
+    def now(cls, tz=None):
+        if tz is None or tz.utcoffset(NOW) is None:
+            return NOW
+        return NOW.replace(tzinfo=tz) + tz.utcoffset(NOW)
+
+
+@pytest.fixture
+def mock_now():
+    with patch("django.contrib.humanize.templatetags.humanize.datetime", MockDateTime):
+        yield
+
+
+@pytest.mark.parametrize(
+    "value,expected",
+    [
+        (NOW, "now"),
+        (NOW - timedelta(seconds=1), "a second ago"),
+        (NOW - timedelta(seconds=30), "30 seconds ago"),
+        (NOW - timedelta(minutes=1, seconds=30), "a minute ago"),
+        (NOW - timedelta(minutes=4), "4 minutes ago"),
+        (NOW - timedelta(hours=1, minutes=30, seconds=30), "an hour ago"),
+        (NOW - timedelta(hours=23, minutes=50, seconds=50), "23 hours ago"),
+        (NOW - timedelta(days=1), "1\xa0day ago"),
+        (NOW - timedelta(days=500), "1\xa0year, 4\xa0months ago"),
+        (NOW + timedelta(seconds=1), "a second from now"),
+        (NOW + timedelta(seconds=30), "30 seconds from now"),
+        (NOW + timedelta(minutes=1, seconds=30), "a minute from now"),
+        (NOW + timedelta(hours=2), "2 hours from now"),
+        (NOW + timedelta(days=1), "1\xa0day from now"),
+        (NOW + timedelta(days=2, hours=6), "2\xa0days, 6\xa0hours from now"),
+        ("not a date", "not a date"),
+    ],
+)
+def test_naturaltime(assert_render, mock_now, value, expected):
+    template = "{% load humanize %}{{ value|naturaltime }}"
+    assert_render(template, {"value": value}, expected)
+
+
+@override_settings(USE_TZ=True)
+def test_naturaltime_aware(assert_render, mock_now):
+    value = NOW.replace(tzinfo=dt_timezone.utc) - timedelta(minutes=4)
+    template = "{% load humanize %}{{ value|naturaltime }}"
+    assert_render(template, {"value": value}, "4 minutes ago")
+
+
+def test_naturaltime_missing(assert_render):
+    template = "{% load humanize %}{{ missing|naturaltime }}"
+    assert_render(template, {}, "")
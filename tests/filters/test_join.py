@@ -0,0 +1,100 @@
+"""
+Adapted from
+https://github.com/django/django/blob/5.1/tests/template_tests/filter_tests/test_join.py
+"""
+
+from django.utils.safestring import mark_safe
+
+
+def test_join01(assert_render):
+    template = '{{ a|join:", " }}'
+    assert_render(template, {"a": ["alpha", "beta & me"]}, "alpha, beta &amp; me")
+
+
+def test_join02(assert_render):
+    template = '{% autoescape off %}{{ a|join:", " }}{% endautoescape %}'
+    assert_render(template, {"a": ["alpha", "beta & me"]}, "alpha, beta & me")
+
+
+def test_join03(assert_render):
+    template = '{{ a|join:" &amp; " }}'
+    assert_render(template, {"a": ["alpha", "beta & me"]}, "alpha &amp; beta &amp; me")
+
+
+def test_join04(assert_render):
+    template = '{% autoescape off %}{{ a|join:" &amp; " }}{% endautoescape %}'
+    assert_render(template, {"a": ["alpha", "beta & me"]}, "alpha &amp; beta & me")
+
+
+def test_join05(assert_render):
+    template = "{{ a|join:var }}"
+    context = {"a": ["alpha", "beta & me"], "var": " & "}
+    assert_render(template, context, "alpha &amp; beta &amp; me")
+
+
+def test_join06(assert_render):
+    template = "{% autoescape off %}{{ a|join:var }}{% endautoescape %}"
+    context = {"a": ["alpha", "beta & me"], "var": " & "}
+    assert_render(template, context, "alpha & beta & me")
+
+
+def test_join07(assert_render):
+    template = "{{ a|join:var|lower }}"
+    context = {"a": ["Alpha", "Beta & me"], "var": mark_safe(" & ")}
+    assert_render(template, context, "alpha & beta &amp; me")
+
+
+def test_join08(assert_render):
+    template = "{% autoescape off %}{{ a|join:var|lower }}{% endautoescape %}"
+    context = {"a": ["Alpha", "Beta & me"], "var": mark_safe(" & ")}
+    assert_render(template, context, "alpha & beta & me")
+
+
+def test_join_autoescape_off(assert_render):
+    template = "{% autoescape off %}{{ var_list|join:var_joiner }}{% endautoescape %}"
+    context = {
+        "var_list": ["<p>Hello World!</p>", "beta & me", "<script>Hi!</script>"],
+        "var_joiner": "<br/>",
+    }
+    expected = "<p>Hello World!</p><br/>beta & me<br/><script>Hi!</script>"
+    assert_render(template, context, expected)
+
+
+def test_join_integers(assert_render):
+    template = '{{ a|join:"glue" }}'
+    assert_render(template, {"a": [0, 1, 2]}, "0glue1glue2")
+
+
+def test_join_integers_autoescape_off(assert_render):
+    template = '{% autoescape off %}{{ a|join:"glue" }}{% endautoescape %}'
+    assert_render(template, {"a": [0, 1, 2]}, "[0, 1, 2]")
+
+
+def test_join_string(assert_render):
+    template = '{{ a|join:"-" }}|{{ a|safe|join:"" }}'
+    assert_render(template, {"a": "a&b"}, "a-&amp;-b|a&amp;b")
+
+
+def test_join_non_iterable(assert_render):
+    template = '{{ a|join:"<br>" }}|{{ 123|join:"-" }}'
+    assert_render(template, {"a": 123}, "123|123")
+
+
+def test_join_missing_variable(assert_render):
+    assert_render('{{ missing|join:"-" }}', {}, "")
+
+
+def test_join_missing_argument(assert_parse_error):
+    template = "{{ a|join }}"
+    django_message = "join requires 2 arguments, 1 provided"
+    rusty_message = """\
+  × Expected an argument
+   ╭────
+ 1 │ {{ a|join }}
+   ·      ──┬─
+   ·        ╰── here
+   ╰────
+"""
+    assert_parse_error(
+        template=template, django_message=django_message, rusty_message=rusty_message
+    )
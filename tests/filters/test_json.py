@@ -0,0 +1,71 @@
+from decimal import Decimal
+
+import pytest
+from django.template import engines
+from django.template.exceptions import TemplateSyntaxError
+
+
+def render(template, context):
+    return engines["rusty"].from_string(template).render(context)
+
+
+def test_json_dict():
+    template = "{{ data|json }}"
+    context = {"data": {"a": "<script>alert('x')</script>", "b": [1, 2]}}
+    expected = (
+        '{"a": "\\u003Cscript\\u003Ealert(\'x\')\\u003C/script\\u003E", "b": [1, 2]}'
+    )
+    assert render(template, context) == expected
+
+
+def test_json_list():
+    template = "{{ data|json }}"
+    context = {"data": ["a&b", 1, None, True]}
+    assert render(template, context) == '["a\\u0026b", 1, null, true]'
+
+
+def test_json_decimal():
+    template = "{{ data|json }}"
+    context = {"data": {"price": Decimal("1.10")}}
+    assert render(template, context) == '{"price": "1.10"}'
+
+
+def test_json_string():
+    template = "{{ data|json }}"
+    assert render(template, {"data": "<b>"}) == '"\\u003Cb\\u003E"'
+
+
+def test_json_literal_argument():
+    template = "{{ data|default:5|json }}"
+    assert render(template, {}) == "5"
+
+
+def test_json_missing():
+    template = "{{ data|json }}"
+    assert render(template, {}) == '""'
+
+
+def test_json_is_safe():
+    template = "<script>var data = {{ data|json }};</script>"
+    context = {"data": {"quote": '"'}}
+    assert render(template, context) == '<script>var data = {"quote": "\\""};</script>'
+
+
+def test_json_with_argument():
+    template = "{{ data|json:1 }}"
+    with pytest.raises(TemplateSyntaxError) as exc_info:
+        engines["rusty"].from_string(template)
+
+    assert str(exc_info.value) == """\
+  × json filter does not take an argument
+   ╭────
+ 1 │ {{ data|json:1 }}
+   ·              ┬
+   ·              ╰── unexpected argument
+   ╰────
+"""
+
+
+def test_json_loaded_filter_takes_precedence():
+    template = "{{ data|json }}{% load override_tags %}{{ data|json }}"
+    assert render(template, {"data": "a"}) == '"a"custom:a'
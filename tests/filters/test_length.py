@@ -0,0 +1,62 @@
+import pytest
+
+
+def test_length_list(assert_render):
+    template = "{{ items|length }}"
+    assert_render(template=template, context={"items": [1, 2, 3]}, expected="3")
+
+
+def test_length_string(assert_render):
+    template = "{{ name|length }}"
+    assert_render(template=template, context={"name": "Lily"}, expected="4")
+
+
+def test_length_missing(assert_render):
+    template = "{{ items|length }}"
+    assert_render(template=template, context={}, expected="0")
+
+
+def test_length_integer(assert_render):
+    template = "{{ foo|default:3|length }}"
+    assert_render(template=template, context={}, expected="0")
+
+
+def test_length_no_len(assert_render):
+    template = "{{ items|length }}"
+    assert_render(template=template, context={"items": object()}, expected="0")
+
+
+@pytest.mark.parametrize(
+    "template,context,expected",
+    [
+        ('{{ value|default_if_none:"abc"|length }}', {"value": None}, "3"),
+        ('{{ value|default_if_none:"abc"|length }}', {"value": [1, 2]}, "2"),
+        ('{{ value|default_if_none:"abc"|length }}', {"value": ""}, "0"),
+        ('{{ value|default_if_none:"abc"|length }}', {}, "0"),
+        ('{{ value|default:"abc"|length }}', {"value": None}, "3"),
+        ('{{ value|default:"abc"|length }}', {"value": "xy"}, "2"),
+        (
+            "{{ value|default_if_none:fallback|length }}",
+            {"value": None, "fallback": "abcd"},
+            "4",
+        ),
+    ],
+)
+def test_length_after_default(assert_render, template, context, expected):
+    assert_render(template=template, context=context, expected=expected)
+
+
+def test_length_with_argument(assert_parse_error):
+    template = "{{ items|length:1 }}"
+    django_message = "length requires 1 arguments, 2 provided"
+    rusty_message = """\
+  × length filter does not take an argument
+   ╭────
+ 1 │ {{ items|length:1 }}
+   ·                 ┬
+   ·                 ╰── unexpected argument
+   ╰────
+"""
+    assert_parse_error(
+        template=template, django_message=django_message, rusty_message=rusty_message
+    )
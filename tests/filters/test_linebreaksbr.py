@@ -0,0 +1,60 @@
+"""
+Adapted from
+https://github.com/django/django/blob/5.1/tests/template_tests/filter_tests/test_linebreaksbr.py
+"""
+
+from django.utils.safestring import mark_safe
+
+
+def test_linebreaksbr01(assert_render):
+    template = "{{ a|linebreaksbr }} {{ b|linebreaksbr }}"
+    context = {"a": "x&\ny", "b": mark_safe("x&\ny")}
+    assert_render(template, context, "x&amp;<br>y x&<br>y")
+
+
+def test_linebreaksbr02(assert_render):
+    template = "{% autoescape off %}{{ a|linebreaksbr }} {{ b|linebreaksbr }}{% endautoescape %}"
+    context = {"a": "x&\ny", "b": mark_safe("x&\ny")}
+    assert_render(template, context, "x&<br>y x&<br>y")
+
+
+def test_linebreaksbr_escapes_only_text(assert_render):
+    template = "{{ text|linebreaksbr }}"
+    context = {"text": "<b>bold</b>\nnext"}
+    assert_render(template, context, "&lt;b&gt;bold&lt;/b&gt;<br>next")
+
+
+def test_linebreaksbr_safe_not_reescaped(assert_render):
+    template = "{{ text|linebreaksbr }}"
+    context = {"text": mark_safe("<b>bold</b> &amp;\nnext")}
+    assert_render(template, context, "<b>bold</b> &amp;<br>next")
+
+
+def test_linebreaksbr_newlines(assert_render):
+    template = "{{ text|linebreaksbr }}"
+    context = {"text": "line 1\r\nline 2\rline 3\nline 4"}
+    assert_render(template, context, "line 1<br>line 2<br>line 3<br>line 4")
+
+
+def test_linebreaksbr_non_string_input(assert_render):
+    assert_render("{{ number|linebreaksbr }}", {"number": 123}, "123")
+
+
+def test_linebreaksbr_missing_variable(assert_render):
+    assert_render("{{ missing|linebreaksbr }}", {}, "")
+
+
+def test_linebreaksbr_argument(assert_parse_error):
+    template = "{{ text|linebreaksbr:1 }}"
+    django_message = "linebreaksbr requires 1 arguments, 2 provided"
+    rusty_message = """\
+  × linebreaksbr filter does not take an argument
+   ╭────
+ 1 │ {{ text|linebreaksbr:1 }}
+   ·                      ┬
+   ·                      ╰── unexpected argument
+   ╰────
+"""
+    assert_parse_error(
+        template=template, django_message=django_message, rusty_message=rusty_message
+    )
@@ -0,0 +1,85 @@
+"""
+Adapted from
+https://github.com/django/django/blob/5.1/tests/template_tests/filter_tests/test_ljust.py
+"""
+
+import pytest
+from django.utils.safestring import mark_safe
+
+
+def test_ljust01(assert_render):
+    template = '{% autoescape off %}.{{ a|ljust:"5" }}. .{{ b|ljust:"5" }}.{% endautoescape %}'
+    context = {"a": "a&b", "b": mark_safe("a&b")}
+    assert_render(template, context, ".a&b  . .a&b  .")
+
+
+def test_ljust02(assert_render):
+    template = '.{{ a|ljust:"5" }}. .{{ b|ljust:"5" }}.'
+    context = {"a": "a&b", "b": mark_safe("a&b")}
+    assert_render(template, context, ".a&amp;b  . .a&b  .")
+
+
+@pytest.mark.parametrize(
+    "width,expected",
+    [
+        (10, "test      "),
+        (3, "test"),
+        (-5, "test"),
+        ("6", "test  "),
+        (6.5, "test  "),
+        (True, "test"),
+    ],
+)
+def test_ljust_variable_width(assert_render, width, expected):
+    template = "{{ value|ljust:width }}"
+    assert_render(template, {"value": "test", "width": width}, expected)
+
+
+def test_ljust_unicode(assert_render):
+    template = "{{ value|ljust:7 }}"
+    assert_render(template, {"value": "héllo"}, "héllo  ")
+
+
+def test_ljust_non_string_input(assert_render):
+    template = "{{ value|ljust:4 }}"
+    assert_render(template, {"value": 123}, "123 ")
+
+
+def test_ljust_missing_variable(assert_render):
+    template = "[{{ missing|ljust:3 }}]"
+    assert_render(template, {}, "[   ]")
+
+
+def test_ljust_no_argument(assert_parse_error):
+    template = "{{ foo|ljust }}"
+    django_message = "ljust requires 2 arguments, 1 provided"
+    rusty_message = """\
+  × Expected an argument
+   ╭────
+ 1 │ {{ foo|ljust }}
+   ·        ──┬──
+   ·          ╰── here
+   ╰────
+"""
+    assert_parse_error(
+        template=template, django_message=django_message, rusty_message=rusty_message
+    )
+
+
+def test_ljust_argument_not_integer(assert_render_error):
+    django_message = "invalid literal for int() with base 10: 'wide'"
+    rusty_message = """\
+  × Couldn't convert argument (wide) to integer
+   ╭────
+ 1 │ {{ foo|ljust:width }}
+   ·              ──┬──
+   ·                ╰── argument
+   ╰────
+"""
+    assert_render_error(
+        template="{{ foo|ljust:width }}",
+        context={"foo": "test", "width": "wide"},
+        exception=ValueError,
+        django_message=django_message,
+        rusty_message=rusty_message,
+    )
@@ -0,0 +1,63 @@
+"""
+Adapted from
+https://github.com/django/django/blob/5.1/tests/template_tests/filter_tests/test_make_list.py
+"""
+
+import pytest
+from django.utils.safestring import mark_safe
+
+
+def test_make_list01(assert_render):
+    template = "{% autoescape off %}{{ a|make_list }}{% endautoescape %}"
+    assert_render(template, {"a": mark_safe("&")}, "['&']")
+
+
+def test_make_list02(assert_render):
+    template = "{{ a|make_list }}"
+    assert_render(template, {"a": mark_safe("&")}, "[&#x27;&amp;&#x27;]")
+
+
+@pytest.mark.parametrize(
+    "value,expected",
+    [
+        ("abc", "a-b-c"),
+        (1234, "1-2-3-4"),
+        (1.5, "1-.-5"),
+        (1.0, "1-.-0"),
+        (True, "T-r-u-e"),
+        ("", ""),
+    ],
+)
+def test_make_list_join(assert_render, value, expected):
+    template = '{{ value|make_list|join:"-" }}'
+    assert_render(template, {"value": value}, expected)
+
+
+def test_make_list_literals(assert_render):
+    template = '{{ 123|make_list|join:"-" }}|{{ 1.5|make_list|join:"-" }}'
+    assert_render(template, {}, "1-2-3|1-.-5")
+
+
+def test_make_list_iterates_characters(assert_render):
+    template = "{% for c in value|make_list %}{{ c }},{% endfor %}"
+    assert_render(template, {"value": 123}, "1,2,3,")
+
+
+def test_make_list_missing_variable(assert_render):
+    assert_render("{{ missing|make_list }}", {}, "[]")
+
+
+def test_make_list_argument(assert_parse_error):
+    template = "{{ value|make_list:1 }}"
+    django_message = "make_list requires 1 arguments, 2 provided"
+    rusty_message = """\
+  × make_list filter does not take an argument
+   ╭────
+ 1 │ {{ value|make_list:1 }}
+   ·                    ┬
+   ·                    ╰── unexpected argument
+   ╰────
+"""
+    assert_parse_error(
+        template=template, django_message=django_message, rusty_message=rusty_message
+    )
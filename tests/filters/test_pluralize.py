@@ -0,0 +1,97 @@
+"""
+Adapted from
+https://github.com/django/django/blob/5.1/tests/template_tests/filter_tests/test_pluralize.py
+"""
+
+from decimal import Decimal
+
+import pytest
+
+
+@pytest.mark.parametrize(
+    "value,expected", [("0", "votes"), ("1", "vote"), ("2", "votes")]
+)
+def test_no_arguments(assert_render, value, expected):
+    template = "vote{{ value|pluralize }}"
+    assert_render(template, {"value": value}, expected)
+
+
+@pytest.mark.parametrize(
+    "value,expected", [("0", "classes"), ("1", "class"), ("2", "classes")]
+)
+def test_suffix(assert_render, value, expected):
+    template = 'class{{ value|pluralize:"es" }}'
+    assert_render(template, {"value": value}, expected)
+
+
+@pytest.mark.parametrize(
+    "value,expected", [("0", "candies"), ("1", "candy"), ("2", "candies")]
+)
+def test_singular_and_plural_suffix(assert_render, value, expected):
+    template = 'cand{{ value|pluralize:"y,ies" }}'
+    assert_render(template, {"value": value}, expected)
+
+
+@pytest.mark.parametrize(
+    "value,expected",
+    [
+        (1, ""),
+        (0, "s"),
+        (2, "s"),
+        (0.5, "s"),
+        (1.5, "s"),
+        (1.0, ""),
+        (True, ""),
+        (False, "s"),
+        (Decimal(1), ""),
+        (Decimal(0), "s"),
+        (Decimal(2), "s"),
+        ([1], ""),
+        ([], "s"),
+        ([1, 2, 3], "s"),
+        ({"a": 1}, ""),
+        ("", ""),
+        (None, ""),
+        (object(), ""),
+    ],
+)
+def test_values(assert_render, value, expected):
+    assert_render("{{ value|pluralize }}", {"value": value}, expected)
+
+
+def test_literals(assert_render):
+    template = "{{ 1|pluralize }}|{{ 2|pluralize }}|{{ 1.0|pluralize }}"
+    assert_render(template, {}, "|s|")
+
+
+def test_count_variable(assert_render):
+    template = "{{ count }} item{{ count|pluralize }}"
+    assert_render(template, {"count": 1}, "1 item")
+    assert_render(template, {"count": 3}, "3 items")
+
+
+@pytest.mark.parametrize(
+    "argument,value,expected",
+    [
+        ("es", 1, ""),
+        ("es", 0, "es"),
+        ("y,ies", 1, "y"),
+        ("y,ies", 2, "ies"),
+        ("y,ies,error", 0, ""),
+        ("y,es", object(), ""),
+        ("es", object(), ""),
+        ("y,es", "", ""),
+    ],
+)
+def test_suffixes(assert_render, argument, value, expected):
+    template = "{{ value|pluralize:argument }}"
+    assert_render(template, {"value": value, "argument": argument}, expected)
+
+
+def test_missing_variable(assert_render):
+    assert_render('{{ missing|pluralize:"y,ies" }}', {}, "")
+
+
+def test_suffix_is_escaped(assert_render):
+    template = '{{ 2|pluralize:"<b>" }}'
+    assert_render(template, {}, "&lt;b&gt;")
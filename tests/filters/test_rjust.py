@@ -0,0 +1,85 @@
+"""
+Adapted from
+https://github.com/django/django/blob/5.1/tests/template_tests/filter_tests/test_rjust.py
+"""
+
+import pytest
+from django.utils.safestring import mark_safe
+
+
+def test_rjust01(assert_render):
+    template = '{% autoescape off %}.{{ a|rjust:"5" }}. .{{ b|rjust:"5" }}.{% endautoescape %}'
+    context = {"a": "a&b", "b": mark_safe("a&b")}
+    assert_render(template, context, ".  a&b. .  a&b.")
+
+
+def test_rjust02(assert_render):
+    template = '.{{ a|rjust:"5" }}. .{{ b|rjust:"5" }}.'
+    context = {"a": "a&b", "b": mark_safe("a&b")}
+    assert_render(template, context, ".  a&amp;b. .  a&b.")
+
+
+@pytest.mark.parametrize(
+    "width,expected",
+    [
+        (10, "      test"),
+        (3, "test"),
+        (-5, "test"),
+        ("6", "  test"),
+        (6.5, "  test"),
+        (True, "test"),
+    ],
+)
+def test_rjust_variable_width(assert_render, width, expected):
+    template = "{{ value|rjust:width }}"
+    assert_render(template, {"value": "test", "width": width}, expected)
+
+
+def test_rjust_unicode(assert_render):
+    template = "{{ value|rjust:7 }}"
+    assert_render(template, {"value": "héllo"}, "  héllo")
+
+
+def test_rjust_non_string_input(assert_render):
+    template = "{{ value|rjust:4 }}"
+    assert_render(template, {"value": 123}, " 123")
+
+
+def test_rjust_missing_variable(assert_render):
+    template = "[{{ missing|rjust:3 }}]"
+    assert_render(template, {}, "[   ]")
+
+
+def test_rjust_no_argument(assert_parse_error):
+    template = "{{ foo|rjust }}"
+    django_message = "rjust requires 2 arguments, 1 provided"
+    rusty_message = """\
+  × Expected an argument
+   ╭────
+ 1 │ {{ foo|rjust }}
+   ·        ──┬──
+   ·          ╰── here
+   ╰────
+"""
+    assert_parse_error(
+        template=template, django_message=django_message, rusty_message=rusty_message
+    )
+
+
+def test_rjust_argument_not_integer(assert_render_error):
+    django_message = "invalid literal for int() with base 10: 'wide'"
+    rusty_message = """\
+  × Couldn't convert argument (wide) to integer
+   ╭────
+ 1 │ {{ foo|rjust:width }}
+   ·              ──┬──
+   ·                ╰── argument
+   ╰────
+"""
+    assert_render_error(
+        template="{{ foo|rjust:width }}",
+        context={"foo": "test", "width": "wide"},
+        exception=ValueError,
+        django_message=django_message,
+        rusty_message=rusty_message,
+    )
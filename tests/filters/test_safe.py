@@ -63,3 +63,20 @@ def test_safe_autoescape_off_lower(assert_render):
     template = "{% autoescape off %}{{ html|lower|safe }}{% endautoescape %}"
     html = "<p>Hello World!</p>"
     assert_render(template=template, context={"html": html}, expected=html.lower())
+
+
+def test_safe_integer_then_add(assert_render):
+    template = "{{ count|safe|add:1 }}"
+    assert_render(template=template, context={"count": 5}, expected="6")
+
+
+def test_safe_float_then_add(assert_render):
+    template = "{{ count|safe|add:1 }}"
+    assert_render(template=template, context={"count": 1.5}, expected="")
+
+
+def test_safe_then_add_string(assert_render):
+    template = "{{ a|safe|add:b }}"
+    assert_render(
+        template=template, context={"a": "<b>", "b": "<i>"}, expected="&lt;b&gt;&lt;i&gt;"
+    )
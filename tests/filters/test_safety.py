@@ -0,0 +1,35 @@
+"""
+String filters keep or drop the safe flag of their input according to the
+`is_safe` flag Django registers them with.
+"""
+
+import pytest
+from django.utils.safestring import mark_safe
+
+
+@pytest.mark.parametrize(
+    "filter,value,expected",
+    [
+        ("lower", mark_safe("<B>"), "<b>"),
+        ("lower", "<B>", "&lt;b&gt;"),
+        ("upper", mark_safe("<b>&amp;</b>"), "&lt;B&gt;&amp;AMP;&lt;/B&gt;"),
+        ("upper", "<b>", "&lt;B&gt;"),
+        ("capfirst", mark_safe("<b>x</b>"), "<b>x</b>"),
+        ("capfirst", "<b>", "&lt;b&gt;"),
+        ("slugify", mark_safe("<b>A b</b>"), "ba-bb"),
+        ("slugify", "<b>A b</b>", "ba-bb"),
+        ("addslashes", mark_safe("<b>'</b>"), "<b>\\'</b>"),
+        ("addslashes", "<b>'</b>", "&lt;b&gt;\\&#x27;&lt;/b&gt;"),
+        ("center:7", mark_safe("<b>"), "  <b>  "),
+        ("center:7", "<b>", "  &lt;b&gt;  "),
+    ],
+)
+def test_string_filter_safety(assert_render, filter, value, expected):
+    template = f"{{{{ value|{filter} }}}}"
+    assert_render(template=template, context={"value": value}, expected=expected)
+
+
+@pytest.mark.parametrize("filter", ["capfirst", "center:2"])
+def test_string_filter_escapes_after_filtering(assert_render, filter):
+    template = f"{{{{ value|{filter}|length }}}}"
+    assert_render(template=template, context={"value": "<b>"}, expected="3")
@@ -0,0 +1,71 @@
+"""
+Adapted from
+https://github.com/django/django/blob/5.1/tests/template_tests/filter_tests/test_slice.py
+"""
+
+import pytest
+from django.utils.safestring import mark_safe
+
+
+def test_slice01(assert_render):
+    template = '{{ a|slice:"1:3" }} {{ b|slice:"1:3" }}'
+    context = {"a": "a&b", "b": mark_safe("a&b")}
+    assert_render(template, context, "&amp;b &b")
+
+
+def test_slice02(assert_render):
+    template = '{% autoescape off %}{{ a|slice:"1:3" }} {{ b|slice:"1:3" }}{% endautoescape %}'
+    context = {"a": "a&b", "b": mark_safe("a&b")}
+    assert_render(template, context, "&b &b")
+
+
+@pytest.mark.parametrize(
+    "argument,expected",
+    [
+        ("0", ""),
+        ("1", "a"),
+        (1, "a"),
+        ("-1", "abcdef"),
+        ("1:2", "b"),
+        ("1:3", "bc"),
+        ("0::2", "aceg"),
+        ("::-1", "gfedcba"),
+        ("::0", "abcdefg"),
+        ("1:2:3:4", "abcdefg"),
+        ("x", "abcdefg"),
+    ],
+)
+def test_slice_string(assert_render, argument, expected):
+    template = "{{ value|slice:argument }}"
+    context = {"value": "abcdefg", "argument": argument}
+    assert_render(template, context, expected)
+
+
+def test_slice_string_stays_a_string(assert_render):
+    template = '{{ value|slice:"::-1"|upper }}'
+    assert_render(template, {"value": "abc"}, "CBA")
+
+
+def test_slice_list(assert_render):
+    template = '{{ items|slice:":3" }}|{{ items|slice:"::-1" }}'
+    context = {"items": [1, 2, 3, 4]}
+    assert_render(template, context, "[1, 2, 3]|[4, 3, 2, 1]")
+
+
+def test_slice_list_in_for(assert_render):
+    template = '{% for x in items|slice:"::-1" %}{{ x }},{% endfor %}'
+    assert_render(template, {"items": [1, 2, 3]}, "3,2,1,")
+
+
+def test_slice_fail_silently(assert_render):
+    class Unsliceable:
+        def __str__(self):
+            return "unsliceable"
+
+    template = '{{ value|slice:"0::2" }}|{{ dict|slice:"1" }}|{{ number|slice:"1" }}'
+    context = {"value": Unsliceable(), "dict": {}, "number": 5}
+    assert_render(template, context, "unsliceable|{}|5")
+
+
+def test_slice_missing_variable(assert_render):
+    assert_render('{{ missing|slice:"1" }}', {}, "")
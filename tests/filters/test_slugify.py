@@ -3,8 +3,12 @@ Adapted from
 https://github.com/django/django/blob/5.1/tests/template_tests/filter_tests/test_slugify.py
 """
 
+import pytest
+from django.template import engines
+from django.template.exceptions import TemplateSyntaxError
 from django.utils.functional import lazy
 from django.utils.safestring import mark_safe
+from django.utils.text import slugify
 
 
 def test_slugify01(assert_render):
@@ -86,3 +90,46 @@ def test_dictionary(assert_render):
     expected = "key-value"
 
     assert_render(template, context, expected)
+
+
+@pytest.mark.parametrize(
+    "value,expected",
+    [
+        ("Héllo", "héllo"),
+        ("Lærke Sørensen", "lærke-sørensen"),
+        ("Un \xe9l\xe9phant \xe0 l'or\xe9e du bois", "un-éléphant-à-lorée-du-bois"),
+        ("\ufb01le ①", "file-1"),
+    ],
+)
+def test_unicode_slugify(value, expected):
+    template = engines["rusty"].from_string(
+        "{{ test|slugify }}|{{ test|unicode_slugify }}"
+    )
+    django_template = engines["django"].from_string("{{ test|slugify }}")
+    ascii_slug = django_template.render({"test": value})
+    assert template.render({"test": value}) == f"{ascii_slug}|{expected}"
+
+
+def test_unicode_slugify_matches_django_function():
+    value = "Un \xe9l\xe9phant \xe0 l'or\xe9e du bois"
+    template = engines["rusty"].from_string("{{ test|unicode_slugify }}")
+    assert template.render({"test": value}) == slugify(value, allow_unicode=True)
+
+
+def test_slugify_argument():
+    with pytest.raises(TemplateSyntaxError):
+        engines["rusty"].from_string("{{ test|slugify:True }}")
+    with pytest.raises(TemplateSyntaxError):
+        engines["django"].from_string("{{ test|slugify:True }}")
+
+
+def test_unicode_slugify_argument():
+    with pytest.raises(TemplateSyntaxError):
+        engines["rusty"].from_string("{{ test|unicode_slugify:True }}")
+
+
+def test_unicode_slugify_loaded_filter_takes_precedence():
+    template = engines["rusty"].from_string(
+        "{{ test|unicode_slugify }}{% load override_tags %}{{ test|unicode_slugify }}"
+    )
+    assert template.render({"test": "Héllo"}) == "héllocustom:Héllo"
@@ -0,0 +1,64 @@
+"""
+Adapted from
+https://github.com/django/django/blob/5.1/tests/template_tests/filter_tests/test_truncatewords.py
+"""
+
+import pytest
+from django.utils.safestring import mark_safe
+
+
+def test_truncatewords01(assert_render):
+    template = '{% autoescape off %}{{ a|truncatewords:"2" }} {{ b|truncatewords:"2"}}{% endautoescape %}'
+    context = {"a": "alpha & bravo", "b": mark_safe("alpha &amp; bravo")}
+    assert_render(template, context, "alpha & … alpha &amp; …")
+
+
+def test_truncatewords02(assert_render):
+    template = '{{ a|truncatewords:"2" }} {{ b|truncatewords:"2"}}'
+    context = {"a": "alpha & bravo", "b": mark_safe("alpha &amp; bravo")}
+    assert_render(template, context, "alpha &amp; … alpha &amp; …")
+
+
+@pytest.mark.parametrize(
+    "length,expected",
+    [
+        (1, "A …"),
+        (5, "A sentence with a few …"),
+        (8, "A sentence with a few words in it"),
+        (100, "A sentence with a few words in it"),
+        (0, ""),
+        (-1, ""),
+    ],
+)
+def test_truncatewords(assert_render, length, expected):
+    template = "{{ text|truncatewords:length }}"
+    context = {"text": "A sentence with a few words in it", "length": length}
+    assert_render(template, context, expected)
+
+
+def test_truncatewords_punctuation(assert_render):
+    template = "{{ text|truncatewords:4 }}"
+    context = {"text": "Joel is a slug, really."}
+    assert_render(template, context, "Joel is a slug, …")
+
+
+def test_truncatewords_collapses_whitespace(assert_render):
+    template = "{{ text|truncatewords:10 }}"
+    context = {"text": " Joel\n is\ta   slug "}
+    assert_render(template, context, "Joel is a slug")
+
+
+def test_truncatewords_invalid_number(assert_render):
+    template = "{{ text|truncatewords:'not a number' }}"
+    context = {"text": "A sentence with a few words in it"}
+    assert_render(template, context, "A sentence with a few words in it")
+
+
+def test_truncatewords_non_string_input(assert_render):
+    template = "{{ number|truncatewords:2 }}"
+    assert_render(template, {"number": 123}, "123")
+
+
+def test_truncatewords_missing_variable(assert_render):
+    template = "{{ missing|truncatewords:2 }}"
+    assert_render(template, {}, "")
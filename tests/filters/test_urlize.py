@@ -0,0 +1,108 @@
+"""
+Adapted from
+https://github.com/django/django/blob/5.1/tests/template_tests/filter_tests/test_urlize.py
+"""
+
+from django.utils.safestring import mark_safe
+
+
+def test_urlize01(assert_render):
+    template = "{% autoescape off %}{{ a|urlize }} {{ b|urlize }}{% endautoescape %}"
+    context = {
+        "a": "http://example.com/?x=&y=",
+        "b": mark_safe("http://example.com?x=&amp;y=&lt;2&gt;"),
+    }
+    expected = (
+        '<a href="http://example.com/?x=&amp;y=" rel="nofollow">'
+        "http://example.com/?x=&y=</a> "
+        '<a href="http://example.com?x=&amp;y=%3C2%3E" rel="nofollow">'
+        "http://example.com?x=&amp;y=&lt;2&gt;</a>"
+    )
+    assert_render(template, context, expected)
+
+
+def test_urlize02(assert_render):
+    template = "{{ a|urlize }} {{ b|urlize }}"
+    context = {
+        "a": "http://example.com/?x=&y=",
+        "b": mark_safe("http://example.com?x=&amp;y="),
+    }
+    expected = (
+        '<a href="http://example.com/?x=&amp;y=" rel="nofollow">'
+        "http://example.com/?x=&amp;y=</a> "
+        '<a href="http://example.com?x=&amp;y=" rel="nofollow">'
+        "http://example.com?x=&amp;y=</a>"
+    )
+    assert_render(template, context, expected)
+
+
+def test_urlize_trailing_period(assert_render):
+    template = "{{ a|urlize }}"
+    context = {"a": "Go to www.example.com."}
+    expected = (
+        'Go to <a href="http://www.example.com" rel="nofollow">www.example.com</a>.'
+    )
+    assert_render(template, context, expected)
+
+
+def test_urlize_email(assert_render):
+    template = "{{ a|urlize }}"
+    context = {"a": "Email lily@example.com"}
+    expected = 'Email <a href="mailto:lily@example.com">lily@example.com</a>'
+    assert_render(template, context, expected)
+
+
+def test_urlize_escapes_surrounding_text(assert_render):
+    template = "{{ a|urlize }}"
+    context = {"a": "<b>https://example.com</b>"}
+    expected = (
+        '&lt;b&gt;<a href="https://example.com" rel="nofollow">'
+        "https://example.com</a>&lt;/b&gt;"
+    )
+    assert_render(template, context, expected)
+
+
+def test_urlize_wrapping_punctuation(assert_render):
+    template = "{{ a|urlize }}"
+    context = {"a": "(see https://example.com)"}
+    expected = (
+        '(see <a href="https://example.com" rel="nofollow">https://example.com</a>)'
+    )
+    assert_render(template, context, expected)
+
+
+def test_urlize_chained_trailing_punctuation(assert_render):
+    template = "{{ a|urlize }}"
+    context = {"a": "(see https://example.com/a))."}
+    expected = (
+        '(see <a href="https://example.com/a" rel="nofollow">'
+        "https://example.com/a</a>))."
+    )
+    assert_render(template, context, expected)
+
+
+def test_urlize_no_url(assert_render):
+    template = "{{ a|urlize }}"
+    context = {"a": "nothing to see & here"}
+    assert_render(template, context, "nothing to see &amp; here")
+
+
+def test_urlize_missing_value(assert_render):
+    template = "{{ a|urlize }}"
+    assert_render(template, {}, "")
+
+
+def test_urlize_with_argument(assert_parse_error):
+    template = "{{ a|urlize:invalid }}"
+    django_message = "urlize requires 1 arguments, 2 provided"
+    rusty_message = """\
+  × urlize filter does not take an argument
+   ╭────
+ 1 │ {{ a|urlize:invalid }}
+   ·             ───┬───
+   ·                ╰── unexpected argument
+   ╰────
+"""
+    assert_parse_error(
+        template=template, django_message=django_message, rusty_message=rusty_message
+    )
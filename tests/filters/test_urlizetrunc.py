@@ -0,0 +1,96 @@
+"""
+Adapted from
+https://github.com/django/django/blob/5.1/tests/template_tests/filter_tests/test_urlizetrunc.py
+"""
+
+from django.utils.safestring import mark_safe
+
+
+def test_urlizetrunc01(assert_render):
+    template = '{% autoescape off %}{{ a|urlizetrunc:"8" }} {{ b|urlizetrunc:"8" }}{% endautoescape %}'
+    context = {
+        "a": '"Unsafe" http://example.com/x=&y=',
+        "b": mark_safe("&quot;Safe&quot; http://example.com?x=&amp;y="),
+    }
+    expected = (
+        '"Unsafe" <a href="http://example.com/x=&amp;y=" rel="nofollow">http://…</a> '
+        '&quot;Safe&quot; <a href="http://example.com?x=&amp;y=" rel="nofollow">http://…</a>'
+    )
+    assert_render(template, context, expected)
+
+
+def test_urlizetrunc02(assert_render):
+    template = '{{ a|urlizetrunc:"8" }} {{ b|urlizetrunc:"8" }}'
+    context = {
+        "a": '"Unsafe" http://example.com/x=&y=',
+        "b": mark_safe("&quot;Safe&quot; http://example.com?x=&amp;y="),
+    }
+    expected = (
+        '&quot;Unsafe&quot; <a href="http://example.com/x=&amp;y=" rel="nofollow">http://…</a> '
+        '&quot;Safe&quot; <a href="http://example.com?x=&amp;y=" rel="nofollow">http://…</a>'
+    )
+    assert_render(template, context, expected)
+
+
+def test_urlizetrunc_long_url(assert_render):
+    template = "{{ a|urlizetrunc:limit }}"
+    url = "https://www.example.com/a/very/long/path/to/some/page/"
+    context = {"a": url, "limit": 20}
+    expected = f'<a href="{url}" rel="nofollow">https://www.example…</a>'
+    assert_render(template, context, expected)
+
+
+def test_urlizetrunc_non_url(assert_render):
+    template = "{{ a|urlizetrunc:2 }}"
+    context = {"a": "hello world"}
+    assert_render(template, context, "hello world")
+
+
+def test_urlizetrunc_missing_argument(assert_parse_error):
+    template = "{{ a|urlizetrunc }}"
+    django_message = "urlizetrunc requires 2 arguments, 1 provided"
+    rusty_message = """\
+  × Expected an argument
+   ╭────
+ 1 │ {{ a|urlizetrunc }}
+   ·      ─────┬─────
+   ·           ╰── here
+   ╰────
+"""
+    assert_parse_error(
+        template=template, django_message=django_message, rusty_message=rusty_message
+    )
+
+
+def test_urlizetrunc_invalid_argument(assert_render_error):
+    template = "{{ a|urlizetrunc:'foo' }}"
+    django_message = "invalid literal for int() with base 10: 'foo'"
+    rusty_message = """\
+  × Couldn't convert argument ('foo') to integer
+   ╭────
+ 1 │ {{ a|urlizetrunc:'foo' }}
+   ·                  ──┬──
+   ·                    ╰── argument
+   ╰────
+"""
+    assert_render_error(
+        template=template,
+        context={"a": "https://example.com"},
+        exception=ValueError,
+        django_message=django_message,
+        rusty_message=rusty_message,
+    )
+
+
+def test_urlizetrunc_huge_limit(assert_render):
+    template = "{{ a|urlizetrunc:99999999999999999999 }}"
+    context = {"a": "https://example.com"}
+    expected = '<a href="https://example.com" rel="nofollow">https://example.com</a>'
+    assert_render(template=template, context=context, expected=expected)
+
+
+def test_urlizetrunc_huge_negative_limit(assert_render):
+    template = "{{ a|urlizetrunc:-99999999999999999999 }}"
+    context = {"a": "https://example.com"}
+    expected = '<a href="https://example.com" rel="nofollow">…</a>'
+    assert_render(template=template, context=context, expected=expected)
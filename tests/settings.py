@@ -3,6 +3,7 @@ import os
 BASE_DIR = os.path.dirname(os.path.abspath(__file__))
 
 INSTALLED_APPS = [
+    "django.contrib.humanize",
     "tests.apps.DummyAppConfig",
 ]
 
@@ -11,12 +12,14 @@ TEMPLATES = [
         "BACKEND": "django.template.backends.django.DjangoTemplates",
         "DIRS": ["tests/templates"],
         "OPTIONS": {
+            "context_processors": ["django.template.context_processors.request"],
             "libraries": {
                 "custom_filters": "tests.templatetags.custom_filters",
                 "custom_tags": "tests.templatetags.custom_tags",
                 "more_filters": "tests.templatetags.more_filters",
                 "no_filters": "tests.templatetags.no_filters",
                 "no_tags": "tests.templatetags.no_tags",
+                "override_tags": "tests.templatetags.override_tags",
             },
         },
     },
@@ -31,6 +34,7 @@ TEMPLATES = [
                 "more_filters": "tests.templatetags.more_filters",
                 "no_filters": "tests.templatetags.no_filters",
                 "no_tags": "tests.templatetags.no_tags",
+                "override_tags": "tests.templatetags.override_tags",
             },
         },
     },
@@ -36,6 +36,55 @@ def test_invalid_argument(assert_parse_error):
     )
 
 
+def test_keyword_argument(assert_parse_error):
+    template = "{% autoescape yes %}{{ html }}"
+    django_message = "'autoescape' argument should be 'on' or 'off'"
+    rusty_message = """\
+  × 'autoescape' argument should be 'on' or 'off'.
+   ╭────
+ 1 │ {% autoescape yes %}{{ html }}
+   ·               ─┬─
+   ·                ╰── here
+   ╰────
+"""
+    assert_parse_error(
+        template=template, django_message=django_message, rusty_message=rusty_message
+    )
+
+
+def test_variable_argument(assert_parse_error):
+    """Like Django, the argument is never resolved as a variable."""
+    template = "{% autoescape somevar %}{{ html }}"
+    django_message = "'autoescape' argument should be 'on' or 'off'"
+    rusty_message = """\
+  × 'autoescape' argument should be 'on' or 'off'.
+   ╭────
+ 1 │ {% autoescape somevar %}{{ html }}
+   ·               ───┬───
+   ·                  ╰── here
+   ╰────
+"""
+    assert_parse_error(
+        template=template, django_message=django_message, rusty_message=rusty_message
+    )
+
+
+def test_quoted_argument(assert_parse_error):
+    template = '{% autoescape "on" %}{{ html }}'
+    django_message = "'autoescape' argument should be 'on' or 'off'"
+    rusty_message = """\
+  × 'autoescape' argument should be 'on' or 'off'.
+   ╭────
+ 1 │ {% autoescape "on" %}{{ html }}
+   ·               ──┬─
+   ·                 ╰── here
+   ╰────
+"""
+    assert_parse_error(
+        template=template, django_message=django_message, rusty_message=rusty_message
+    )
+
+
 def test_extra_argument(assert_parse_error):
     template = "{% autoescape on off %}{{ html }}"
     django_message = "'autoescape' tag requires exactly one argument."
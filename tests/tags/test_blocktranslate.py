@@ -0,0 +1,124 @@
+def test_blocktranslate(assert_render):
+    template = "{% load i18n %}{% blocktranslate %}Hello {{ name }}{% endblocktranslate %}"
+    assert_render(template=template, context={"name": "Lily"}, expected="Hello Lily")
+
+
+def test_blocktrans(assert_render):
+    template = "{% load i18n %}{% blocktrans %}Hello {{ name }}{% endblocktrans %}"
+    assert_render(template=template, context={"name": "Lily"}, expected="Hello Lily")
+
+
+def test_blocktranslate_escapes_variables(assert_render):
+    template = "{% load i18n %}{% blocktranslate %}<b>{{ name }}</b>{% endblocktranslate %}"
+    context = {"name": "<Lily>"}
+    assert_render(template=template, context=context, expected="<b>&lt;Lily&gt;</b>")
+
+
+def test_blocktranslate_percent(assert_render):
+    template = "{% load i18n %}{% blocktranslate %}{{ n }}% done{% endblocktranslate %}"
+    assert_render(template=template, context={"n": 50}, expected="50% done")
+
+
+def test_blocktranslate_missing_variable(assert_render):
+    template = "{% load i18n %}{% blocktranslate %}Hello {{ name }}{% endblocktranslate %}"
+    assert_render(template=template, context={}, expected="Hello ")
+
+
+def test_blocktranslate_trimmed(assert_render):
+    template = """{% load i18n %}{% blocktranslate trimmed %}
+  Hello
+  {{ name }}
+{% endblocktranslate %}"""
+    assert_render(template=template, context={"name": "Lily"}, expected="Hello Lily")
+
+
+def test_blocktranslate_asvar(assert_render):
+    template = (
+        "{% load i18n %}"
+        "{% blocktranslate asvar greeting %}Hello {{ name }}{% endblocktranslate %}"
+        "[{{ greeting }}]"
+    )
+    context = {"name": "<Lily>"}
+    expected = "[Hello &lt;Lily&gt;]"
+    assert_render(template=template, context=context, expected=expected)
+
+
+def test_blocktranslate_asvar_is_assigned_before_use(assert_render):
+    template = (
+        "{% load i18n %}"
+        "{% blocktranslate asvar greeting %}Hello {{ name }}{% endblocktranslate %}"
+        "{% for name in names %}{{ greeting }} {{ name }}. {% endfor %}"
+    )
+    context = {"name": "Lily", "names": ["Bryony", "Ash"]}
+    expected = "Hello Lily Bryony. Hello Lily Ash. "
+    assert_render(template=template, context=context, expected=expected)
+
+
+def test_blocktranslate_asvar_missing_name(assert_parse_error):
+    template = "{% load i18n %}{% blocktranslate asvar %}Hello{% endblocktranslate %}"
+    django_message = (
+        "No argument provided to the 'blocktranslate' tag for the asvar option."
+    )
+    rusty_message = """\
+  × No argument provided to the 'blocktranslate' tag for the asvar option.
+   ╭────
+ 1 │ {% load i18n %}{% blocktranslate asvar %}Hello{% endblocktranslate %}
+   ·                                  ──┬──
+   ·                                    ╰── here
+   ╰────
+"""
+    assert_parse_error(
+        template=template, django_message=django_message, rusty_message=rusty_message
+    )
+
+
+def test_blocktranslate_unknown_argument(assert_parse_error):
+    template = "{% load i18n %}{% blocktranslate foo %}Hello{% endblocktranslate %}"
+    django_message = "Unknown argument for 'blocktranslate' tag: 'foo'."
+    rusty_message = """\
+  × Unknown argument for 'blocktranslate' tag: 'foo'.
+   ╭────
+ 1 │ {% load i18n %}{% blocktranslate foo %}Hello{% endblocktranslate %}
+   ·                                  ─┬─
+   ·                                   ╰── here
+   ╰────
+"""
+    assert_parse_error(
+        template=template, django_message=django_message, rusty_message=rusty_message
+    )
+
+
+def test_blocktranslate_repeated_option(assert_parse_error):
+    template = (
+        "{% load i18n %}{% blocktranslate trimmed trimmed %}Hello{% endblocktranslate %}"
+    )
+    django_message = "The 'trimmed' option was specified more than once."
+    rusty_message = """\
+  × The 'trimmed' option was specified more than once.
+   ╭────
+ 1 │ {% load i18n %}{% blocktranslate trimmed trimmed %}Hello{% endblocktranslate %}
+   ·                                  ───┬─── ───┬───
+   ·                                     │       ╰── second
+   ·                                     ╰── first
+   ╰────
+"""
+    assert_parse_error(
+        template=template, django_message=django_message, rusty_message=rusty_message
+    )
+
+
+def test_blocktranslate_inner_tag(assert_parse_error):
+    template = "{% load i18n %}{% blocktranslate %}{% if a %}{% endif %}{% endblocktranslate %}"
+    django_message = "'blocktranslate' doesn't allow other block tags (seen 'if a') inside it"
+    rusty_message = """\
+  × 'blocktranslate' doesn't allow other block tags (seen 'if a') inside it
+   ╭────
+ 1 │ {% load i18n %}{% blocktranslate %}{% if a %}{% endif %}{% endblocktranslate %}
+   ·                ──────────┬──────────────┬────
+   ·                          │              ╰── unexpected tag
+   ·                          ╰── start tag
+   ╰────
+"""
+    assert_parse_error(
+        template=template, django_message=django_message, rusty_message=rusty_message
+    )
@@ -0,0 +1,100 @@
+import pytest
+from django.core.cache import caches
+from django.core.cache.utils import make_template_fragment_key
+from django.template import TemplateSyntaxError
+from django.test import override_settings
+
+
+@pytest.fixture
+def stub_cache():
+    backend = {"BACKEND": "tests.cache.StubCache", "LOCATION": "fragments"}
+    with override_settings(CACHES={"default": backend}):
+        cache = caches["default"]
+        cache.clear()
+        yield cache
+        cache.clear()
+
+
+def test_cache_hit(template_engine, stub_cache):
+    template = template_engine.from_string(
+        "{% load cache %}{% cache 500 greeting %}Hello {{ name }}{% endcache %}!"
+    )
+    assert template.render({"name": "Lily"}) == "Hello Lily!"
+    assert template.render({"name": "Bryony"}) == "Hello Lily!"
+
+    key = make_template_fragment_key("greeting")
+    assert stub_cache.sets == [(key, 500)]
+    assert stub_cache.gets == [(key, None), (key, "Hello Lily")]
+
+
+def test_cache_vary_on(template_engine, stub_cache):
+    template = template_engine.from_string(
+        "{% load cache %}{% cache 500 greeting name %}Hello {{ name }}{% endcache %}"
+    )
+    assert template.render({"name": "Lily"}) == "Hello Lily"
+    assert template.render({"name": "Bryony"}) == "Hello Bryony"
+    assert template.render({"name": "Lily"}) == "Hello Lily"
+
+    keys = [key for key, _ in stub_cache.sets]
+    assert keys == [
+        make_template_fragment_key("greeting", ["Lily"]),
+        make_template_fragment_key("greeting", ["Bryony"]),
+    ]
+
+
+def test_cache_timeout_variable(template_engine, stub_cache):
+    template = template_engine.from_string(
+        "{% load cache %}{% cache timeout greeting %}Hello{% endcache %}"
+    )
+    assert template.render({"timeout": "60"}) == "Hello"
+    assert stub_cache.sets == [(make_template_fragment_key("greeting"), 60)]
+
+
+def test_cache_timeout_none(template_engine, stub_cache):
+    template = template_engine.from_string(
+        "{% load cache %}{% cache None greeting %}Hello{% endcache %}"
+    )
+    assert template.render({}) == "Hello"
+    assert stub_cache.sets == [(make_template_fragment_key("greeting"), None)]
+
+
+def test_cache_using(template_engine, stub_cache):
+    template = template_engine.from_string(
+        "{% load cache %}{% cache 500 greeting using='default' %}Hello{% endcache %}"
+    )
+    assert template.render({}) == "Hello"
+    assert stub_cache.sets == [(make_template_fragment_key("greeting"), 500)]
+
+
+def test_cache_invalid_timeout(template_engine, stub_cache):
+    template = template_engine.from_string(
+        "{% load cache %}{% cache 'soon' greeting %}Hello{% endcache %}"
+    )
+    with pytest.raises(TemplateSyntaxError) as exc_info:
+        template.render({})
+    assert str(exc_info.value) == "\"cache\" tag got a non-integer timeout value: 'soon'"
+
+
+def test_cache_invalid_cache_name(template_engine, stub_cache):
+    template = template_engine.from_string(
+        "{% load cache %}{% cache 500 greeting using='missing' %}Hello{% endcache %}"
+    )
+    with pytest.raises(TemplateSyntaxError) as exc_info:
+        template.render({})
+    assert str(exc_info.value) == "Invalid cache name specified for cache tag: 'missing'"
+
+
+def test_cache_missing_arguments(assert_parse_error):
+    template = "{% load cache %}{% cache 500 %}{% endcache %}"
+    django_message = "''cache'' tag requires at least 2 arguments."
+    rusty_message = """\
+  × 'cache' tag requires at least 2 arguments.
+   ╭────
+ 1 │ {% load cache %}{% cache 500 %}{% endcache %}
+   ·                 ───────┬───────
+   ·                        ╰── here
+   ╰────
+"""
+    assert_parse_error(
+        template=template, django_message=django_message, rusty_message=rusty_message
+    )
@@ -0,0 +1,54 @@
+"""
+Adapted from
+https://github.com/django/django/blob/5.1/tests/template_tests/syntax_tests/test_comment.py
+"""
+
+import pytest
+
+
+@pytest.mark.parametrize(
+    "template",
+    [
+        "{% comment %}{% endcomment %}",
+        "{% comment %}hello{% endcomment %}",
+        '{% comment "note" %}hello{% endcomment %}',
+        "{% comment %}\n{{ x }}\n{% endcomment %}",
+    ],
+)
+def test_comment(assert_render, template):
+    assert_render(template=template, context={"x": 1}, expected="")
+
+
+def test_comment_surrounding_text(assert_render):
+    template = "hello{% comment %} world{% endcomment %}!"
+    assert_render(template=template, context={}, expected="hello!")
+
+
+@pytest.mark.parametrize(
+    "content",
+    ["{{ }}", "{% bogus %}", "{{ a|}}", "{% if %}", "{% endif %}", "{% comment %}"],
+)
+def test_comment_invalid_syntax(assert_render, content):
+    template = f"a{{% comment %}}{content}{{% endcomment %}}b"
+    assert_render(template=template, context={}, expected="ab")
+
+
+def test_comment_in_block(assert_render):
+    template = "{% if x %}{% comment %}{% endif %}{% endcomment %}yes{% endif %}"
+    assert_render(template=template, context={"x": True}, expected="yes")
+
+
+def test_comment_unclosed(assert_parse_error):
+    template = "{% comment %}{{ }}"
+    django_message = "Unclosed tag on line 1: 'comment'. Looking for one of: endcomment."
+    rusty_message = """\
+  × Unclosed 'comment' tag. Looking for one of: endcomment
+   ╭────
+ 1 │ {% comment %}{{ }}
+   · ──────┬──────
+   ·       ╰── started here
+   ╰────
+"""
+    assert_parse_error(
+        template=template, django_message=django_message, rusty_message=rusty_message
+    )
@@ -50,6 +50,41 @@ def test_simple_tag_positional_and_kwargs(assert_render):
     assert_render(template=template, context={}, expected="24")
 
 
+def test_simple_tag_positional_and_keyword_only(assert_render):
+    template = "{% load scaled_sum from custom_tags %}{% scaled_sum 1 2 c=3 %}"
+    assert_render(template=template, context={}, expected="9")
+
+
+def test_simple_tag_positional_and_keyword_only_default(assert_render):
+    template = "{% load scaled_sum from custom_tags %}{% scaled_sum 1 2 %}"
+    assert_render(template=template, context={}, expected="3")
+
+
+def test_simple_tag_positional_and_keyword_only_variables(assert_render):
+    template = (
+        "{% load scaled_sum from custom_tags %}"
+        "{% scaled_sum a b c=c as total %}{{ total }}"
+    )
+    context = {"a": 2, "b": 3, "c": 4}
+    assert_render(template=template, context=context, expected="20")
+
+
+def test_simple_tag_keyword_only_as_positional(assert_parse_error):
+    template = "{% load scaled_sum from custom_tags %}{% scaled_sum 1 2 3 %}"
+    django_message = "'scaled_sum' received too many positional arguments"
+    rusty_message = """\
+  × Unexpected positional argument
+   ╭────
+ 1 │ {% load scaled_sum from custom_tags %}{% scaled_sum 1 2 3 %}
+   ·                                                         ┬
+   ·                                                         ╰── here
+   ╰────
+"""
+    assert_parse_error(
+        template=template, django_message=django_message, rusty_message=rusty_message
+    )
+
+
 def test_simple_tag_double_as_variable(assert_render):
     template = (
         "{% load double from custom_tags %}{% double 3 as foo %}{{ foo }}{{ foo }}"
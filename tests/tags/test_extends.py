@@ -0,0 +1,278 @@
+from types import SimpleNamespace
+
+import pytest
+from django.template import TemplateDoesNotExist, TemplateSyntaxError, engines
+from django.template.backends.django import DjangoTemplates
+from django_rusty_templates import RustyTemplates
+
+LOCMEM_TEMPLATES = {
+    "base.html": (
+        "<{% block title %}Base{% endblock %}|{% block body %}base{% endblock %}>"
+    ),
+    "parent.html": '{% extends "base.html" %}{% block title %}Parent{% endblock %}',
+    "child.html": (
+        '{% extends "parent.html" %}'
+        "{% block body %}{{ block.super }} {{ user }}{% endblock %}"
+    ),
+    "by_variable.html": "{% extends parent %}{% block body %}variable{% endblock %}",
+}
+
+
+def locmem_engine(backend):
+    return backend(
+        {
+            "OPTIONS": {
+                "loaders": [("django.template.loaders.locmem.Loader", LOCMEM_TEMPLATES)]
+            },
+            "NAME": "locmem",
+            "DIRS": [],
+            "APP_DIRS": False,
+        }
+    )
+
+
+def test_extends_template_object(template_engine):
+    base = template_engine.from_string(
+        "<{% block title %}Base{% endblock %}|{% block body %}{% endblock body %}>"
+    )
+    template = template_engine.from_string(
+        "{% extends base %}ignored{% block body %}{{ user }}{% endblock %}"
+    )
+    context = {"base": base, "user": "Lily"}
+    assert template.render(context) == "<Base|Lily>"
+
+
+@pytest.mark.parametrize("backend", [DjangoTemplates, RustyTemplates])
+def test_extends_by_name(backend):
+    template = locmem_engine(backend).get_template("child.html")
+    assert template.render({"user": "Lily"}) == "<Parent|base Lily>"
+
+
+@pytest.mark.parametrize("backend", [DjangoTemplates, RustyTemplates])
+def test_extends_by_name_from_variable(backend):
+    template = locmem_engine(backend).get_template("by_variable.html")
+    assert template.render({"parent": "parent.html"}) == "<Parent|variable>"
+
+
+def test_extends_missing_template_name():
+    template = locmem_engine(RustyTemplates).from_string('{% extends "missing.html" %}')
+    with pytest.raises(TemplateDoesNotExist) as exc_info:
+        template.render({})
+    assert str(exc_info.value) == "missing.html"
+
+
+def test_extends_wrapped_template(template_engine):
+    base = template_engine.from_string("<{% block a %}base{% endblock %}>")
+    template = template_engine.from_string(
+        "{% extends base %}{% block a %}child{% endblock %}"
+    )
+    # Like a template backend's `Template`, which wraps the compiled template.
+    assert template.render({"base": SimpleNamespace(template=base)}) == "<child>"
+
+
+def test_extends_three_levels(template_engine):
+    base = template_engine.from_string(
+        "{% block a %}base a{% endblock %}|{% block b %}base b{% endblock %}"
+    )
+    parent = template_engine.from_string(
+        "{% extends base %}{% block a %}parent a{% endblock %}"
+        "{% block b %}parent b{% endblock %}"
+    )
+    template = template_engine.from_string(
+        "{% extends parent %}{% block b %}child b{% endblock %}"
+    )
+    context = {"base": base, "parent": parent}
+    assert template.render(context) == "parent a|child b"
+
+
+def test_extends_block_super(template_engine):
+    base = template_engine.from_string("{% block a %}<base>{% endblock %}")
+    parent = template_engine.from_string(
+        "{% extends base %}{% block a %}{{ block.super }}<parent>{% endblock %}"
+    )
+    template = template_engine.from_string(
+        "{% extends parent %}{% block a %}{{ block.super }}<child>{% endblock %}"
+    )
+    context = {"base": base, "parent": parent}
+    assert template.render(context) == "<base><parent><child>"
+
+
+def test_extends_block_super_in_base(template_engine):
+    template = template_engine.from_string(
+        "{% block a %}[{{ block.super }}]{% endblock %}"
+    )
+    with pytest.raises(TemplateSyntaxError) as exc_info:
+        template.render({})
+
+    assert "Did you use {{ block.super }} in a base template?" in str(
+        exc_info.value
+    )
+
+
+def test_extends_block_super_in_last_version(template_engine):
+    base = template_engine.from_string(
+        "{% block a %}[{{ block.super }}]{% endblock %}"
+    )
+    template = template_engine.from_string("{% extends base %}")
+    assert template.render({"base": base}) == "[]"
+
+
+def test_extends_nested_blocks(template_engine):
+    base = template_engine.from_string(
+        "{% block outer %}<{% block inner %}base{% endblock %}>{% endblock %}"
+    )
+    template = template_engine.from_string(
+        "{% extends base %}{% block inner %}child{% endblock %}"
+    )
+    assert template.render({"base": base}) == "<child>"
+
+
+def test_extends_block_in_loop(template_engine):
+    base = template_engine.from_string(
+        "{% for x in xs %}{% block item %}{{ x }}{% endblock %}{% endfor %}"
+    )
+    template = template_engine.from_string(
+        "{% extends base %}{% block item %}({{ x }}){% endblock %}"
+    )
+    assert template.render({"base": base, "xs": [1, 2]}) == "(1)(2)"
+
+
+def test_extends_include_does_not_see_blocks(template_engine):
+    included = template_engine.from_string("{% block a %}included{% endblock %}")
+    base = template_engine.from_string(
+        "{% block a %}base{% endblock %}|{% include tmpl %}"
+    )
+    template = template_engine.from_string(
+        "{% extends base %}{% block a %}child{% endblock %}"
+    )
+    context = {"base": base, "tmpl": included}
+    assert template.render(context) == "child|included"
+
+
+@pytest.mark.parametrize("engine", ["rusty", "django"])
+def test_extends_missing_variable(engine):
+    template = engines[engine].from_string("{% extends missing %}")
+    with pytest.raises(TemplateSyntaxError) as exc_info:
+        template.render({})
+    assert (
+        str(exc_info.value)
+        == "Invalid template name in 'extends' tag: ''. Got this from the 'missing' variable."
+    )
+
+
+def test_extends_no_arguments(assert_parse_error):
+    template = "{% extends %}"
+    django_message = "'extends' takes one argument"
+    rusty_message = """\
+  × 'extends' takes one argument
+   ╭────
+ 1 │ {% extends %}
+   · ──────┬──────
+   ·       ╰── here
+   ╰────
+"""
+    assert_parse_error(
+        template=template, django_message=django_message, rusty_message=rusty_message
+    )
+
+
+def test_extends_not_first(assert_parse_error):
+    template = "{{ x }}{% extends base %}"
+    django_message = (
+        "<ExtendsNode: extends base> must be the first tag in the template."
+    )
+    rusty_message = """\
+  × 'extends' must be the first tag in the template
+   ╭────
+ 1 │ {{ x }}{% extends base %}
+   ·        ─────────┬────────
+   ·                 ╰── here
+   ╰────
+"""
+    assert_parse_error(
+        template=template, django_message=django_message, rusty_message=rusty_message
+    )
+
+
+def test_extends_after_comment_tag(assert_parse_error):
+    template = "{% comment %}{% endcomment %}{% extends base %}"
+    django_message = (
+        "<ExtendsNode: extends base> must be the first tag in the template."
+    )
+    rusty_message = """\
+  × 'extends' must be the first tag in the template
+   ╭────
+ 1 │ {% comment %}{% endcomment %}{% extends base %}
+   ·                              ─────────┬────────
+   ·                                       ╰── here
+   ╰────
+"""
+    assert_parse_error(
+        template=template, django_message=django_message, rusty_message=rusty_message
+    )
+
+
+def test_extends_repeated(assert_parse_error):
+    template = "{% extends base %}{% extends base %}"
+    django_message = "'extends' cannot appear more than once in the same template"
+    rusty_message = """\
+  × 'extends' cannot appear more than once in the same template
+   ╭────
+ 1 │ {% extends base %}{% extends base %}
+   ·                   ─────────┬────────
+   ·                            ╰── here
+   ╰────
+"""
+    assert_parse_error(
+        template=template, django_message=django_message, rusty_message=rusty_message
+    )
+
+
+def test_block_no_name(assert_parse_error):
+    template = "{% block %}{% endblock %}"
+    django_message = "'block' tag takes only one argument"
+    rusty_message = """\
+  × 'block' tag takes only one argument
+   ╭────
+ 1 │ {% block %}{% endblock %}
+   · ─────┬─────
+   ·      ╰── here
+   ╰────
+"""
+    assert_parse_error(
+        template=template, django_message=django_message, rusty_message=rusty_message
+    )
+
+
+def test_block_duplicate_name(assert_parse_error):
+    template = "{% block a %}{% endblock %}{% block a %}{% endblock %}"
+    django_message = "'block' tag with name 'a' appears more than once"
+    rusty_message = """\
+  × 'block' tag with name 'a' appears more than once
+   ╭────
+ 1 │ {% block a %}{% endblock %}{% block a %}{% endblock %}
+   · ──────┬──────              ──────┬──────
+   ·       │                          ╰── second
+   ·       ╰── first
+   ╰────
+"""
+    assert_parse_error(
+        template=template, django_message=django_message, rusty_message=rusty_message
+    )
+
+
+def test_block_wrong_end_name(assert_parse_error):
+    template = "{% block a %}{% endblock b %}"
+    django_message = "Invalid block tag on line 1: 'endblock', expected 'endblock' or 'endblock a'. Did you forget to register or load this tag?"
+    rusty_message = """\
+  × Unexpected tag endblock b, expected endblock, endblock a
+   ╭────
+ 1 │ {% block a %}{% endblock b %}
+   · ──────┬──────────────┬───────
+   ·       │              ╰── unexpected tag
+   ·       ╰── start tag
+   ╰────
+"""
+    assert_parse_error(
+        template=template, django_message=django_message, rusty_message=rusty_message
+    )
@@ -70,6 +70,25 @@ def test_render_for_loop_numeric():
     assert str(exc_info.value) == expected
 
 
+def test_render_for_loop_filter_not_iterable(assert_render_error):
+    django_message = "'int' object is not iterable"
+    rusty_message = """\
+  × 'int' object is not iterable
+   ╭────
+ 1 │ {% for x in y|add:1 %}{{ x }}{% endfor %}
+   ·             ───┬───
+   ·                ╰── here
+   ╰────
+"""
+    assert_render_error(
+        template="{% for x in y|add:1 %}{{ x }}{% endfor %}",
+        context={"y": 1},
+        exception=TypeError,
+        django_message=django_message,
+        rusty_message=rusty_message,
+    )
+
+
 def test_render_for_loop_filter(assert_render):
     template = "{% for x in y|upper %}{{ x }}{% endfor %}"
     y = "foo"
@@ -105,6 +124,20 @@ def test_render_for_loop_unpack_dict_items(assert_render):
     assert_render(template=template, context={"d": d}, expected=expected)
 
 
+def test_render_for_loop_dict_keys(assert_render):
+    template = "{% for key in d %}{{ key }}\n{% endfor %}"
+    d = {"foo": 1, "bar": 2}
+    expected = "foo\nbar\n"
+    assert_render(template=template, context={"d": d}, expected=expected)
+
+
+def test_render_for_loop_unpack_dict_items_nested(assert_render):
+    template = "{% for key, value in d.items %}{{ key }}: {{ value.name }}\n{% endfor %}"
+    d = {"a": {"name": "Lily"}, "b": {"name": "Bryony"}}
+    expected = "a: Lily\nb: Bryony\n"
+    assert_render(template=template, context={"d": d}, expected=expected)
+
+
 def test_render_for_loop_counter(assert_render):
     template = "{% for x in y %}{{ x }}: {{ forloop.counter }}\n{% endfor %}"
     y = ["foo", "bar", "spam"]
@@ -170,6 +203,25 @@ def test_render_for_loop_forloop_variable_nested(assert_render):
     assert_render(template=template, context={"y": y}, expected=expected)
 
 
+def test_render_for_loop_forloop_variable_string(assert_render):
+    template = "{% autoescape off %}{% for x in 'ab' %}{{ forloop }};{% endfor %}{% endautoescape off %}"
+    expected = (
+        "{'parentloop': {}, 'counter0': 0, 'counter': 1, 'revcounter': 2, 'revcounter0': 1, 'first': True, 'last': False};"
+        "{'parentloop': {}, 'counter0': 1, 'counter': 2, 'revcounter': 1, 'revcounter0': 0, 'first': False, 'last': True};"
+    )
+    assert_render(template=template, context={}, expected=expected)
+
+
+def test_render_for_loop_forloop_variable_nested_lengths(assert_render):
+    template = "{% autoescape off %}{% for x in 'a' %}{% for y in 'bc' %}{{ forloop }};{% endfor %}{% endfor %}{% endautoescape off %}"
+    outer = "{'parentloop': {}, 'counter0': 0, 'counter': 1, 'revcounter': 1, 'revcounter0': 0, 'first': True, 'last': True}"
+    expected = (
+        f"{{'parentloop': {outer}, 'counter0': 0, 'counter': 1, 'revcounter': 2, 'revcounter0': 1, 'first': True, 'last': False}};"
+        f"{{'parentloop': {outer}, 'counter0': 1, 'counter': 2, 'revcounter': 1, 'revcounter0': 0, 'first': False, 'last': True}};"
+    )
+    assert_render(template=template, context={}, expected=expected)
+
+
 def test_render_for_loop_parentloop_variable(assert_render):
     template = "{% autoescape off %}{% for x in y %}{% for x2 in y %}{{ forloop.parentloop }}{% endfor %}{% endfor %}{% endautoescape off %}"
     y = ["foo"]
@@ -272,6 +324,13 @@ def test_render_for_loop_shadowing_context(assert_render):
     assert_render(template=template, context=context, expected=expected)
 
 
+def test_render_for_loop_empty_inner_loop(assert_render):
+    template = "{% for x in y %}{% for z in e %}{{ z }}{% endfor %}{{ x }}{% endfor %}{{ x }}"
+    context = {"x": 1, "y": [2, 3], "e": []}
+    expected = "231"
+    assert_render(template=template, context=context, expected=expected)
+
+
 def test_render_for_loop_url_shadowing(assert_render):
     template = (
         "{{ x }}{% for x in y %}{{ x }}{% url 'home' as x %}{{ x }}{% endfor %}{{ x }}"
@@ -395,11 +454,27 @@ def test_unpack_1_tuple(assert_parse_error):
     template = "{% for x, in l %}{% endfor %}"
     django_message = "'for' tag received an invalid argument: for x, in l"
     rusty_message = """\
-  × Expected another variable when unpacking in for loop:
+  × Empty variable name in for loop:
    ╭────
  1 │ {% for x, in l %}{% endfor %}
-   ·        ┬
-   ·        ╰── after this variable
+   ·         ┬
+   ·         ╰── expected a variable name next to this comma
+   ╰────
+"""
+    assert_parse_error(
+        template=template, django_message=django_message, rusty_message=rusty_message
+    )
+
+
+def test_unpack_empty_variable(assert_parse_error):
+    template = "{% for a,,b in items %}{% endfor %}"
+    django_message = "'for' tag received an invalid argument: for a,,b in items"
+    rusty_message = """\
+  × Empty variable name in for loop:
+   ╭────
+ 1 │ {% for a,,b in items %}{% endfor %}
+   ·         ┬
+   ·         ╰── expected a variable name next to this comma
    ╰────
 """
     assert_parse_error(
@@ -510,6 +585,26 @@ def test_render_for_loop_unpack_tuple_mismatch(assert_render_error):
     )
 
 
+def test_render_for_loop_unpack_dict_items_mismatch(assert_render_error):
+    django_message = "Need 3 values to unpack in for loop; got 2. "
+    rusty_message = """\
+  × Need 3 values to unpack; got 2.
+   ╭────
+ 1 │ {% for k, v, x in d.items %}{{ k }}{% endfor %}
+   ·        ───┬───    ───┬───
+   ·           │          ╰── from here
+   ·           ╰── unpacked here
+   ╰────
+"""
+    assert_render_error(
+        template="{% for k, v, x in d.items %}{{ k }}{% endfor %}",
+        context={"d": {"a": 1}},
+        exception=ValueError,
+        django_message=django_message,
+        rusty_message=rusty_message,
+    )
+
+
 def test_render_for_loop_unpack_tuple_invalid(assert_render_error):
     django_message = "Need 3 values to unpack in for loop; got 1. "
     rusty_message = """\
@@ -766,3 +861,132 @@ def test_missing_argument_after_for_loop(assert_render_error):
         django_message=django_message,
         rusty_message=rusty_message,
     )
+
+
+def test_for_reserved_name(assert_render):
+    template = "{% for True in items %}{{ True }}{% endfor %}{{ True }}"
+    assert_render(template=template, context={"items": [1, 2]}, expected="12True")
+
+
+def test_filter_argument_forloop_counter(assert_render):
+    template = "{% for x in l %}{{ x|add:forloop.counter }} {% endfor %}"
+    assert_render(template=template, context={"l": [10, 20, 30]}, expected="11 22 33 ")
+
+
+def test_filter_argument_loop_variable(assert_render):
+    template = "{% for x in l %}{{ missing|default:x }}{% endfor %}"
+    assert_render(template=template, context={"l": ["a", "b"]}, expected="ab")
+
+
+def test_filter_argument_nested_loop_variables(assert_render):
+    template = (
+        "{% for x in outer %}{% for y in inner %}"
+        "{{ y|add:forloop.parentloop.counter0 }}"
+        "{{ missing|default:x }}"
+        "{% endfor %}{% endfor %}"
+    )
+    context = {"outer": ["a", "b"], "inner": [1, 2]}
+    assert_render(template=template, context=context, expected="1a2a2b3b")
+
+
+def test_render_for_loop_empty_list(assert_render):
+    template = "{% for x in xs %}{{ x }}{% empty %}none{% endfor %}"
+    assert_render(template=template, context={"xs": []}, expected="none")
+
+
+def test_render_for_loop_generator(assert_render):
+    def numbers():
+        yield from range(1, 4)
+
+    template = "{% for x in xs %}{{ x }}{% if forloop.last %}!{% endif %}{% endfor %}"
+    assert_render(template=template, context={"xs": numbers()}, expected="123!")
+
+
+def test_render_for_loop_generator_revcounter(assert_render):
+    def numbers():
+        yield from range(1, 4)
+
+    template = "{% for x in xs %}{{ x }}:{{ forloop.revcounter }} {% endfor %}"
+    assert_render(template=template, context={"xs": numbers()}, expected="1:3 2:2 3:1 ")
+
+
+def test_render_for_loop_generator_reversed(assert_render):
+    def numbers():
+        yield from range(1, 4)
+
+    template = "{% for x in xs reversed %}{{ x }}{% endfor %}"
+    assert_render(template=template, context={"xs": numbers()}, expected="321")
+
+
+def test_render_for_loop_string_reversed(assert_render):
+    template = '{% for c in "abc" reversed %}{{ c }}{% endfor %}'
+    assert_render(template=template, context={}, expected="cba")
+
+
+def test_render_for_loop_generator_reversed_counters(assert_render):
+    def numbers():
+        yield from range(1, 4)
+
+    template = (
+        "{% for x in xs reversed %}"
+        "{{ x }}:{{ forloop.counter }}:{{ forloop.revcounter }}"
+        "{% if forloop.first %}F{% endif %}{% if forloop.last %}L{% endif %} "
+        "{% endfor %}"
+    )
+    assert_render(
+        template=template, context={"xs": numbers()}, expected="3:1:3F 2:2:2 1:3:1L "
+    )
+
+
+class LazyResults:
+    """A single-pass iterable, like a generator, with a length and `str`."""
+
+    def __init__(self, values):
+        self.values = values
+
+    def __len__(self):
+        return len(self.values)
+
+    def __iter__(self):
+        while self.values:
+            yield self.values.pop(0)
+
+    def __str__(self):
+        return f"<LazyResults {self.values}>"
+
+
+def test_render_lazy_iterable_str(assert_render):
+    template = "{{ results }}"
+    expected = "&lt;LazyResults [1, 2]&gt;"
+    assert_render(template, {"results": LazyResults([1, 2])}, expected)
+
+
+def test_render_for_single_pass_iterable_length(assert_render):
+    # Like Django, iterating consumes the iterable, so later lookups see it
+    # exhausted. A `QuerySet` caches its results, so it can be iterated again.
+    template = (
+        "{{ results|length }}|{% for x in results %}{{ x }}{% endfor %}|"
+        "{{ results|length }}|{% for x in results %}{{ x }}{% empty %}empty{% endfor %}"
+    )
+    results = LazyResults(["a", "b", "c"])
+    assert_render(template, {"results": results}, "3|abc|0|empty")
+
+
+def test_render_for_generator_exhausted(assert_render):
+    template = (
+        "{% for x in items %}{{ x }}{% endfor %}|"
+        "{% for x in items %}{{ x }}{% empty %}empty{% endfor %}"
+    )
+    items = (x for x in "abc")
+    assert_render(template, {"items": items}, "abc|empty")
+
+
+def test_render_for_loop_outer_counter_after_inner_loop(assert_render):
+    template = (
+        "{% for a in outer %}{{ forloop.counter }}["
+        "{% for b in inner %}{{ forloop.counter }}{{ forloop.parentloop.counter }}{% endfor %}"
+        "]{{ forloop.counter }}{{ forloop.revcounter }}{{ forloop.last }} {% endfor %}"
+        "[{{ forloop.counter }}]"
+    )
+    context = {"outer": "ab", "inner": "xyz"}
+    assert_render(template, context, "1[112131]12False 2[122232]21True []")
@@ -1,6 +1,10 @@
+import warnings
+from datetime import date, datetime, timezone
+
 import pytest
 from django.template import engines
 from django.template.exceptions import TemplateSyntaxError
+from django.utils.safestring import mark_safe
 from django.utils.translation import override
 from hypothesis import given
 from hypothesis.strategies import (
@@ -79,6 +83,35 @@ def test_render_not(a, assert_render):
     assert_render(template=template, context={"a": a}, expected=expected)
 
 
+@pytest.mark.parametrize("a", [True, False, "x", 1, 0])
+@pytest.mark.parametrize("b", [True, False, "x", 1, 0])
+def test_render_not_equal_precedence(a, b, assert_render):
+    template = "{% if not a == b %}foo{% else %}bar{% endif %}"
+    expected = "foo" if not a == b else "bar"
+    assert_render(template=template, context={"a": a, "b": b}, expected=expected)
+
+
+@pytest.mark.parametrize("a", ["x", "", 1])
+@pytest.mark.parametrize("b", ["xyz", "", [1, True], [False]])
+def test_render_not_in_precedence(a, b, assert_render):
+    template = "{% if not a in b %}foo{% else %}bar{% endif %}"
+    try:
+        contained = a in b
+    except TypeError:
+        # Like the `compare` helper, Django's `in` is False when it raises.
+        contained = False
+    expected = "foo" if not contained else "bar"
+    assert_render(template=template, context={"a": a, "b": b}, expected=expected)
+
+
+@pytest.mark.parametrize("a", [True, False, "", 1])
+@pytest.mark.parametrize("b", [True, False, "", 1])
+def test_render_and_not_precedence(a, b, assert_render):
+    template = "{% if a and not b %}foo{% else %}bar{% endif %}"
+    expected = "foo" if a and not b else "bar"
+    assert_render(template=template, context={"a": a, "b": b}, expected=expected)
+
+
 def compare(op, left, right):
     try:
         match op:
@@ -151,6 +184,25 @@ def test_render_op_var_var(a, b, op, assert_render):
     assert_render(template=template, context={"a": a, "b": b}, expected=expected)
 
 
+DATES = [
+    datetime(2024, 1, 1, 12),
+    datetime(2024, 6, 1),
+    date(2024, 1, 1),
+    datetime(2024, 1, 1, 12, tzinfo=timezone.utc),
+]
+
+
+@pytest.mark.parametrize("a", DATES)
+@pytest.mark.parametrize("b", DATES)
+@pytest.mark.parametrize("op", ["==", "!=", "<", ">", "<=", ">="])
+def test_render_op_dates(a, b, op, assert_render):
+    """Ordering a date against a datetime, or a naive datetime against an
+    aware one, raises TypeError in Python, so the condition is false."""
+    template = f"{{% if a {op} b %}}truthy{{% else %}}falsey{{% endif %}}"
+    expected = "truthy" if compare(op, a, b) else "falsey"
+    assert_render(template=template, context={"a": a, "b": b}, expected=expected)
+
+
 @pytest.mark.parametrize("a", [True, False, "foo", 1, "", 0])
 @pytest.mark.parametrize("b", ["foo", "", 1, 0, 1.5, -3.7])
 @pytest.mark.parametrize("op", ["==", "!=", "<", ">", "<=", ">="])
@@ -244,6 +296,41 @@ def test_render_is_not(a, b, assert_render):
     assert_render(template=template, context={"a": a, "b": b}, expected=expected)
 
 
+def test_render_is_none_no_warning():
+    template = "{% if a is None %}foo{% else %}bar{% endif %}"
+    with warnings.catch_warnings():
+        warnings.simplefilter("error")
+        rusty_template = engines["rusty"].from_string(template)
+
+    assert rusty_template.render({"a": None}) == "foo"
+
+
+def test_render_is_literal_warning():
+    template = "{% if a is 5 %}foo{% else %}bar{% endif %}"
+    expected = """\
+  ⚠ "is" with a literal is unreliable
+   ╭────
+ 1 │ {% if a is 5 %}foo{% else %}bar{% endif %}
+   ·            ┬
+   ·            ╰── literal
+   ╰────
+  help: Did you mean "=="?
+"""
+    with pytest.warns(SyntaxWarning) as record:
+        rusty_template = engines["rusty"].from_string(template)
+
+    assert str(record[0].message) == expected
+    assert rusty_template.render({"a": 5}) == "foo"
+
+
+def test_render_is_literal_warning_as_error():
+    template = "{% if a is 5 %}foo{% else %}bar{% endif %}"
+    with warnings.catch_warnings():
+        warnings.simplefilter("error")
+        with pytest.raises(SyntaxWarning):
+            engines["rusty"].from_string(template)
+
+
 def test_invalid_and_position(assert_parse_error):
     template = "{% if and %}{{ foo }}{% endif %}"
     django_message = "Not expecting 'and' in this position in if tag."
@@ -868,6 +955,35 @@ def test_variable_is_missing(assert_render):
     assert_render(template=template, context={"foo": ""}, expected="falsey")
 
 
+def test_variable_is_same_variable(assert_render):
+    template = "{% if a is b %}truthy{% else %}falsey{% endif %}"
+    shared = []
+    assert_render(
+        template=template, context={"a": shared, "b": shared}, expected="truthy"
+    )
+
+
+def test_variable_is_different_variable(assert_render):
+    template = "{% if a is b %}truthy{% else %}falsey{% endif %}"
+    assert_render(template=template, context={"a": [], "b": []}, expected="falsey")
+
+
+def test_missing_is_none_variable(assert_render):
+    template = (
+        "{% if foo is bar %}truthy{% else %}falsey{% endif %}"
+        "{% if bar is foo %}truthy{% else %}falsey{% endif %}"
+    )
+    assert_render(template=template, context={"bar": None}, expected="truthytruthy")
+
+
+def test_variable_is_missing_either_order(assert_render):
+    template = (
+        "{% if foo is bar %}truthy{% else %}falsey{% endif %}"
+        "{% if bar is foo %}truthy{% else %}falsey{% endif %}"
+    )
+    assert_render(template=template, context={"bar": []}, expected="falseyfalsey")
+
+
 def test_string_is_string(assert_render):
     template = "{% if '' is '' %}truthy{% else %}falsey{% endif %}"
     assert_render(template=template, context={"foo": ""}, expected="falsey")
@@ -1189,3 +1305,351 @@ def test_else_invalid_content(assert_parse_error):
     assert_parse_error(
         template=template, django_message=django_message, rusty_message=rusty_message
     )
+
+
+@pytest.mark.parametrize(
+    "name,expected", [("Lily", "yes"), ("LILY", "yes"), ("Bryony", "no")]
+)
+def test_if_filtered_equal(assert_render, name, expected):
+    template = '{% if name|lower == "lily" %}yes{% else %}no{% endif %}'
+    assert_render(template=template, context={"name": name}, expected=expected)
+
+
+@pytest.mark.parametrize("items,expected", [([1], "yes"), ([], "no"), (None, "no")])
+def test_if_filtered_truthy(assert_render, items, expected):
+    template = "{% if items|length %}yes{% else %}no{% endif %}"
+    assert_render(template=template, context={"items": items}, expected=expected)
+
+
+@pytest.mark.parametrize(
+    "context,expected", [({"x": 7}, "yes"), ({"x": 5}, "no"), ({}, "no")]
+)
+def test_if_filtered_default_greater_than(assert_render, context, expected):
+    template = "{% if x|default:0 > 5 %}yes{% else %}no{% endif %}"
+    assert_render(template=template, context=context, expected=expected)
+
+
+def test_if_filtered_length_comparison(assert_render):
+    template = "{% if items|length > 3 %}many{% elif 1 < items|length %}some{% endif %}"
+    assert_render(template=template, context={"items": [1, 2]}, expected="some")
+
+
+@pytest.mark.parametrize("name,expected", [("LILY", "yes"), ("Bryony", "no")])
+def test_if_filtered_in(assert_render, name, expected):
+    template = "{% if name|lower in names %}yes{% else %}no{% endif %}"
+    context = {"name": name, "names": ["lily", "ash"]}
+    assert_render(template=template, context=context, expected=expected)
+
+
+@pytest.mark.parametrize(
+    "a,b",
+    [
+        ("z", "é"),
+        ("é", "z"),
+        ("￿", "\U0001f600"),
+        ("\U0001f600", "￿"),
+        ("｡", "\U00010000"),
+        ("a\U0001f600", "b"),
+    ],
+)
+@pytest.mark.parametrize("op", ["<", ">", "<=", ">="])
+def test_string_ordering_non_ascii(assert_render, a, b, op):
+    template = f"{{% if a|lower {op} b|lower %}}yes{{% else %}}no{{% endif %}}"
+    expected = "yes" if eval(f"a {op} b") else "no"
+    assert_render(template=template, context={"a": a, "b": b}, expected=expected)
+
+
+def test_string_literal_ordering_non_ascii(assert_render):
+    template = "{% if 'z' < 'é' %}yes{% else %}no{% endif %}"
+    assert_render(template=template, context={}, expected="yes")
+
+
+@pytest.mark.parametrize(
+    "container",
+    [range(1, 10), {1, 5}, {5: "five"}, [5, 6]],
+    ids=["range", "set", "dict", "list"],
+)
+@pytest.mark.parametrize("value", ["5", "5.0"])
+def test_number_in_container(assert_render, container, value):
+    template = f"{{% if {value} in container %}}yes{{% else %}}no{{% endif %}}"
+    assert_render(template=template, context={"container": container}, expected="yes")
+
+
+@pytest.mark.parametrize(
+    "container",
+    [range(1, 5), {1, 4}, {4: "four"}, [4, 6]],
+    ids=["range", "set", "dict", "list"],
+)
+@pytest.mark.parametrize("value", ["5", "5.0", "5.5"])
+def test_number_not_in_container(assert_render, container, value):
+    template = f"{{% if {value} not in container %}}yes{{% else %}}no{{% endif %}}"
+    assert_render(template=template, context={"container": container}, expected="yes")
+
+
+@pytest.mark.parametrize("left", ["5", "5.0", "'5'", "x"])
+@pytest.mark.parametrize("right", ["5", "5.0", "x|default:5"])
+def test_in_non_container(assert_render, left, right):
+    template = f"{{% if {left} in {right} %}}yes{{% else %}}no{{% endif %}}"
+    assert_render(template=template, context={"x": 5}, expected="no")
+
+
+BIG = 1234567890123456789012345678901234567890
+
+
+@pytest.mark.parametrize(
+    "op,value,expected",
+    [
+        ("==", BIG, "yes"),
+        ("==", BIG + 1, "no"),
+        ("<", BIG + 1, "yes"),
+        ("<", BIG, "no"),
+        (">", BIG - 1, "yes"),
+        (">", BIG, "no"),
+    ],
+)
+def test_big_int_comparison(assert_render, op, value, expected):
+    template = f"{{% if x {op} {BIG} %}}yes{{% else %}}no{{% endif %}}"
+    assert_render(template=template, context={"x": value}, expected=expected)
+
+
+@pytest.mark.parametrize(
+    "literal,op,expected",
+    [
+        ("9007199254740993", "==", "no"),
+        ("9007199254740993", ">", "yes"),
+        ("9007199254740992", "==", "yes"),
+    ],
+)
+def test_float_compared_exactly_with_int(assert_render, literal, op, expected):
+    template = f"{{% if {literal} {op} x %}}yes{{% else %}}no{{% endif %}}"
+    assert_render(
+        template=template, context={"x": 9007199254740992.0}, expected=expected
+    )
+
+
+class User:
+    is_active = True
+
+    def __init__(self, authenticated):
+        self._authenticated = authenticated
+
+    @property
+    def is_authenticated(self):
+        return self._authenticated
+
+    def is_staff(self):
+        return False
+
+
+def test_if_property_returning_true(assert_render):
+    template = "{% if user.is_authenticated %}yes{% else %}no{% endif %}"
+    assert_render(template, {"user": User(True)}, "yes")
+
+
+def test_if_property_returning_false(assert_render):
+    template = "{% if user.is_authenticated %}yes{% else %}no{% endif %}"
+    assert_render(template, {"user": User(False)}, "no")
+
+
+def test_if_method_returning_false(assert_render):
+    template = "{% if user.is_staff %}yes{% else %}no{% endif %}"
+    assert_render(template, {"user": User(True)}, "no")
+
+
+def test_if_not_method_returning_false(assert_render):
+    template = "{% if not user.is_staff %}yes{% else %}no{% endif %}"
+    assert_render(template, {"user": User(True)}, "yes")
+
+
+def test_if_bool_attribute(assert_render):
+    template = "{% if user.is_active %}yes{% else %}no{% endif %}"
+    assert_render(template, {"user": User(False)}, "yes")
+
+
+@pytest.mark.parametrize(
+    "context,expected",
+    [
+        pytest.param({}, "yes", id="missing"),
+        pytest.param({"x": None}, "yes", id="none"),
+        pytest.param({"x": 0}, "no", id="zero"),
+        pytest.param({"x": ""}, "no", id="empty_string"),
+        pytest.param({"x": "None"}, "no", id="string"),
+    ],
+)
+def test_if_equals_none(assert_render, context, expected):
+    template = "{% if x == None %}yes{% else %}no{% endif %}"
+    assert_render(template, context, expected)
+
+
+@pytest.mark.parametrize(
+    "context,expected",
+    [
+        pytest.param({}, "no", id="missing"),
+        pytest.param({"x": None}, "no", id="none"),
+        pytest.param({"x": 0}, "yes", id="zero"),
+    ],
+)
+def test_if_not_equals_none(assert_render, context, expected):
+    template = "{% if None != x %}yes{% else %}no{% endif %}"
+    assert_render(template, context, expected)
+
+
+class EmptySequence:
+    def __len__(self):
+        return 0
+
+
+class BrokenBool:
+    def __bool__(self):
+        raise RuntimeError("broken")
+
+
+@pytest.mark.parametrize("value", [[], {}, 0, 0.0, "", EmptySequence(), None])
+def test_render_falsey_values(value, assert_render):
+    template = "{% if x %}truthy{% else %}falsey{% endif %}"
+    assert_render(template=template, context={"x": value}, expected="falsey")
+
+
+def test_render_bool_error_propagates(template_engine):
+    template = template_engine.from_string("{% if x %}truthy{% endif %}")
+    with pytest.raises(RuntimeError, match="broken"):
+        template.render({"x": BrokenBool()})
+
+
+def test_render_bool_error_in_operator(assert_render):
+    template = "{% if not x %}a{% endif %}{% if x or 1 %}b{% endif %}"
+    assert_render(template=template, context={"x": BrokenBool()}, expected="")
+
+
+@pytest.mark.parametrize(
+    "template",
+    [
+        "{% if safe == plain %}equal{% endif %}",
+        "{% if plain == safe %}equal{% endif %}",
+        "{% if plain|safe == plain %}equal{% endif %}",
+        "{% if safe == 'a&b' %}equal{% endif %}",
+        "{% if 'a&b'|safe == plain %}equal{% endif %}",
+    ],
+)
+def test_render_equal_ignores_safety(template, assert_render):
+    context = {"safe": mark_safe("a&b"), "plain": "a&b"}
+    assert_render(template=template, context=context, expected="equal")
+
+
+@pytest.mark.parametrize(
+    "condition", ['"a" < 5', '5 > "a"', '"a" > 5', '5 < "a"', "a <= b", "b >= a"]
+)
+def test_string_number_ordering_is_false(assert_render, condition):
+    template = "{%% if %s %%}truthy{%% else %%}falsey{%% endif %%}" % condition
+    assert_render(template=template, context={"a": "a", "b": 5}, expected="falsey")
+
+
+@pytest.mark.parametrize(
+    "template,expected",
+    [
+        ("{% if 'b' in data.values %}truthy{% else %}falsey{% endif %}", "truthy"),
+        ("{% if 'a' in data.values %}truthy{% else %}falsey{% endif %}", "falsey"),
+        ("{% if 'a' in data.keys %}truthy{% else %}falsey{% endif %}", "truthy"),
+        ("{% if 2 not in data.keys %}truthy{% else %}falsey{% endif %}", "truthy"),
+        ("{% if pair in data.items %}truthy{% else %}falsey{% endif %}", "truthy"),
+        ("{% if missing in data.values %}truthy{% else %}falsey{% endif %}", "falsey"),
+    ],
+)
+def test_in_dict_views(assert_render, template, expected):
+    context = {"data": {"a": "b", 1: 2}, "pair": ("a", "b")}
+    assert_render(template=template, context=context, expected=expected)
+
+
+def test_in_dict_values_key_shadows_method(assert_render):
+    template = "{% if 'x' in data.values %}truthy{% else %}falsey{% endif %}"
+    context = {"data": {"values": "xyz"}}
+    assert_render(template=template, context=context, expected="truthy")
+
+
+@pytest.mark.parametrize(
+    "condition,context,expected",
+    [
+        # (a == b) in c
+        ("a == b in c", {"a": "x", "b": "x", "c": [True]}, "truthy"),
+        # a in (b == c)
+        ("a in b == c", {"a": "x", "b": "x", "c": True}, "falsey"),
+        # (a in b) and c
+        ("a in b and c", {"a": "x", "b": "x", "c": "y"}, "truthy"),
+        # a or (b in c)
+        ("a or b in c", {"a": "", "b": "x", "c": "xyz"}, "truthy"),
+        # (a in b) in c
+        ("a in b in c", {"a": "x", "b": "xyz", "c": [True]}, "truthy"),
+    ],
+)
+def test_in_comparison_grouping(assert_render, condition, context, expected):
+    template = f"{{% if {condition} %}}truthy{{% else %}}falsey{{% endif %}}"
+    assert_render(template=template, context=context, expected=expected)
+
+
+@pytest.mark.parametrize(
+    "condition,expected",
+    [
+        ("count == True", "truthy"),
+        ("True == count", "truthy"),
+        ("flag == 1", "truthy"),
+        ("1 == flag", "truthy"),
+        ("flag == 1.0", "truthy"),
+        ("zero == False", "truthy"),
+        ("False != 0", "falsey"),
+        ("two == True", "falsey"),
+        ("flag != count", "falsey"),
+        ("flag < two", "truthy"),
+    ],
+)
+def test_bool_int_equality(assert_render, condition, expected):
+    template = f"{{% if {condition} %}}truthy{{% else %}}falsey{{% endif %}}"
+    context = {"count": 1, "flag": True, "zero": 0, "two": 2}
+    assert_render(template=template, context=context, expected=expected)
+
+
+def test_forloop_bool_int_equality(assert_render):
+    template = (
+        "{% for x in 'xy' %}{% if forloop.first == 1 %}first{% endif %}"
+        "{% if forloop.last == zero %}-not-last{% endif %}{% endfor %}"
+    )
+    assert_render(template=template, context={"zero": 0}, expected="first-not-last")
+
+
+@pytest.mark.parametrize(
+    "condition,expected",
+    [
+        ("1", "truthy"),
+        ("0", "falsey"),
+        ("0.0", "falsey"),
+        ("-1", "truthy"),
+        ("1.5", "truthy"),
+        ("not 0", "truthy"),
+        ("0 or 0.0", "falsey"),
+    ],
+)
+def test_numeric_literal(assert_render, condition, expected):
+    template = f"{{% if {condition} %}}truthy{{% else %}}falsey{{% endif %}}"
+    assert_render(template=template, context={}, expected=expected)
+
+
+def test_not_in_generator(assert_render):
+    template = (
+        "{% if 5 not in gen %}missing{% endif %}|"
+        "{% for x in gen %}{{ x }}{% endfor %}"
+    )
+    context = {"gen": (x for x in [1, 2, 3])}
+    assert_render(template=template, context=context, expected="missing|")
+
+
+def test_in_generator_consumes_items(assert_render):
+    template = (
+        "{% if 2 not in gen %}missing{% else %}found{% endif %}|"
+        "{% for x in gen %}{{ x }}{% endfor %}"
+    )
+    context = {"gen": (x for x in [1, 2, 3])}
+    assert_render(template=template, context=context, expected="found|3")
+
+
+def test_not_in_without_contains_or_iter(assert_render):
+    template = "{% if 1 not in value %}truthy{% else %}falsey{% endif %}"
+    assert_render(template=template, context={"value": object()}, expected="falsey")
@@ -0,0 +1,112 @@
+"""
+The `ifequal` and `ifnotequal` tags were removed in Django 3.0, so these tests
+only run against the rusty engine with the `legacy_tags` option enabled.
+"""
+
+import pytest
+from django.template import engines
+from django.template.exceptions import TemplateSyntaxError
+
+from django_rusty_templates import RustyTemplates
+
+
+def legacy_engine():
+    return RustyTemplates(
+        {
+            "OPTIONS": {"legacy_tags": True},
+            "NAME": "legacy",
+            "DIRS": [],
+            "APP_DIRS": False,
+        }
+    )
+
+
+def render(template, context):
+    return legacy_engine().from_string(template).render(context)
+
+
+@pytest.mark.parametrize(
+    "context,expected",
+    [
+        ({"a": 1, "b": 1}, "yes"),
+        ({"a": 1, "b": 2}, ""),
+        ({"a": "x", "b": "x"}, "yes"),
+        ({}, "yes"),
+    ],
+)
+def test_ifequal(context, expected):
+    template = "{% ifequal a b %}yes{% endifequal %}"
+    assert render(template, context) == expected
+
+
+def test_ifequal_else():
+    template = "{% ifequal a b %}yes{% else %}no{% endifequal %}"
+    assert render(template, {"a": 1, "b": 2}) == "no"
+    assert render(template, {"a": 2, "b": 2}) == "yes"
+
+
+def test_ifequal_literal():
+    template = "{% ifequal name 'Lily' %}Hi Lily{% else %}Hi {{ name }}{% endifequal %}"
+    assert render(template, {"name": "Lily"}) == "Hi Lily"
+    assert render(template, {"name": "Bryony"}) == "Hi Bryony"
+
+
+def test_ifnotequal():
+    template = "{% ifnotequal a b %}yes{% else %}no{% endifnotequal %}"
+    assert render(template, {"a": 1, "b": 2}) == "yes"
+    assert render(template, {"a": 2, "b": 2}) == "no"
+
+
+def test_ifequal_matches_if():
+    context = {"a": [1, 2], "b": [1, 2]}
+    legacy = render("{% ifequal a b %}yes{% else %}no{% endifequal %}", context)
+    django = engines["django"].from_string(
+        "{% if a == b %}yes{% else %}no{% endif %}"
+    )
+    assert legacy == django.render(context)
+
+
+def test_ifequal_wrong_arguments():
+    template = "{% ifequal a %}{% endifequal %}"
+    with pytest.raises(TemplateSyntaxError) as exc_info:
+        legacy_engine().from_string(template)
+
+    assert str(exc_info.value) == """\
+  × 'ifequal' takes two arguments
+   ╭────
+ 1 │ {% ifequal a %}{% endifequal %}
+   · ───────┬───────
+   ·        ╰── here
+   ╰────
+"""
+
+
+def test_ifnotequal_missing_end_tag():
+    template = "{% ifnotequal a b %}"
+    with pytest.raises(TemplateSyntaxError) as exc_info:
+        legacy_engine().from_string(template)
+
+    assert str(exc_info.value) == """\
+  × Unclosed 'ifnotequal' tag. Looking for one of: else, endifnotequal
+   ╭────
+ 1 │ {% ifnotequal a b %}
+   · ──────────┬─────────
+   ·           ╰── started here
+   ╰────
+"""
+
+
+def test_ifequal_disabled():
+    template = "{% ifequal a b %}{% endifequal %}"
+    with pytest.raises(TemplateSyntaxError) as exc_info:
+        engines["rusty"].from_string(template)
+
+    assert str(exc_info.value) == """\
+  × 'ifequal' is a legacy tag and is not enabled
+   ╭────
+ 1 │ {% ifequal a b %}{% endifequal %}
+   · ────────┬────────
+   ·         ╰── here
+   ╰────
+  help: Set the 'legacy_tags' engine option to use it
+"""
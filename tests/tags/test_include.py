@@ -0,0 +1,182 @@
+from concurrent.futures import ThreadPoolExecutor
+from pathlib import Path
+
+import pytest
+from django.conf import settings
+from django.template import TemplateDoesNotExist, engines
+
+
+def test_include_template_object(template_engine):
+    included = template_engine.from_string("{{ greeting }} {{ user }}")
+    template = template_engine.from_string("{% include tmpl %}!")
+    context = {"tmpl": included, "greeting": "Hello", "user": "Lily"}
+    assert template.render(context) == "Hello Lily!"
+
+
+def test_include_template_object_with(template_engine):
+    included = template_engine.from_string("{{ greeting }} {{ user }}")
+    template = template_engine.from_string(
+        "{% include tmpl with greeting='Hi' %}|{{ greeting }}"
+    )
+    context = {"tmpl": included, "greeting": "Hello", "user": "Lily"}
+    assert template.render(context) == "Hi Lily|Hello"
+
+
+def test_include_template_object_only(template_engine):
+    included = template_engine.from_string("[{{ greeting }}] {{ user }}")
+    template = template_engine.from_string(
+        "{% include tmpl with user='Bryony' only %}"
+    )
+    context = {"tmpl": included, "greeting": "Hello", "user": "Lily"}
+    assert template.render(context) == "[] Bryony"
+
+
+def test_include_template_object_in_loop(template_engine):
+    included = template_engine.from_string("{{ forloop.counter }}{{ x }}")
+    template = template_engine.from_string(
+        "{% for x in xs %}{% include tmpl %}{% endfor %}"
+    )
+    context = {"tmpl": included, "xs": ["a", "b"]}
+    assert template.render(context) == "1a2b"
+
+
+def test_include_template_object_autoescape(template_engine):
+    included = template_engine.from_string("{{ html }}")
+    template = template_engine.from_string(
+        "{% include tmpl %}{% autoescape off %}{% include tmpl %}{% endautoescape %}"
+    )
+    context = {"tmpl": included, "html": "<p>"}
+    assert template.render(context) == "&lt;p&gt;<p>"
+
+
+@pytest.mark.parametrize("engine", ["rusty", "django"])
+def test_include_missing_variable(engine):
+    template = engines[engine].from_string("{% include missing %}")
+    with pytest.raises(TemplateDoesNotExist) as exc_info:
+        template.render({})
+    assert str(exc_info.value) == "No template names provided"
+
+
+def test_include_no_arguments(assert_parse_error):
+    template = "{% include %}"
+    django_message = "'include' tag takes at least one argument: the name of the template to be included."
+    rusty_message = """\
+  × 'include' tag takes at least one argument: the name of the template to be
+  │ included.
+   ╭────
+ 1 │ {% include %}
+   · ──────┬──────
+   ·       ╰── here
+   ╰────
+"""
+    assert_parse_error(
+        template=template, django_message=django_message, rusty_message=rusty_message
+    )
+
+
+def test_include_repeated_option(assert_parse_error):
+    template = "{% include tmpl only only %}"
+    django_message = "The 'only' option was specified more than once."
+    rusty_message = """\
+  × The 'only' option was specified more than once.
+   ╭────
+ 1 │ {% include tmpl only only %}
+   ·                 ──┬─ ──┬─
+   ·                   │    ╰── second
+   ·                   ╰── first
+   ╰────
+"""
+    assert_parse_error(
+        template=template, django_message=django_message, rusty_message=rusty_message
+    )
+
+
+def test_include_with_no_keywords(assert_parse_error):
+    template = "{% include tmpl with %}"
+    django_message = "\"with\" in 'include' tag needs at least one keyword argument."
+    rusty_message = """\
+  × "with" in 'include' tag needs at least one keyword argument.
+   ╭────
+ 1 │ {% include tmpl with %}
+   ·                 ──┬─
+   ·                   ╰── here
+   ╰────
+"""
+    assert_parse_error(
+        template=template, django_message=django_message, rusty_message=rusty_message
+    )
+
+
+def test_include_unknown_argument(assert_parse_error):
+    template = "{% include tmpl bar %}"
+    django_message = "Unknown argument for 'include' tag: 'bar'."
+    rusty_message = """\
+  × Unknown argument for 'include' tag: 'bar'.
+   ╭────
+ 1 │ {% include tmpl bar %}
+   ·                 ─┬─
+   ·                  ╰── here
+   ╰────
+"""
+    assert_parse_error(
+        template=template, django_message=django_message, rusty_message=rusty_message
+    )
+
+
+def test_include_template_name(template_engine):
+    template = template_engine.from_string("{% include 'basic.txt' %}|{% include name %}")
+    context = {"name": "basic.txt", "user": "Lily"}
+    assert template.render(context) == "Hello Lily!\n|Hello Lily!\n"
+
+
+@pytest.mark.parametrize("names", [["missing.html", "basic.txt"], ("basic.txt",)])
+def test_include_template_name_list(template_engine, names):
+    template = template_engine.from_string("{% include names %}")
+    context = {"names": names, "user": "Lily"}
+    assert template.render(context) == "Hello Lily!\n"
+
+
+def test_include_template_name_list_none_exist(template_engine):
+    template = template_engine.from_string("{% include names %}")
+    with pytest.raises(TemplateDoesNotExist) as exc_info:
+        template.render({"names": ["missing.html", "other.html", "missing.html"]})
+    assert str(exc_info.value) == "missing.html, other.html"
+
+
+def test_include_template_name_list_tried():
+    template = engines["rusty"].from_string("{% include names %}")
+    with pytest.raises(TemplateDoesNotExist) as exc_info:
+        template.render({"names": ["missing.html", "other.html"]})
+
+    templates = Path(settings.BASE_DIR) / "templates"
+    assert exc_info.value.tried == [
+        (str(templates / "missing.html"), "Source does not exist"),
+        (str(templates / "other.html"), "Source does not exist"),
+    ]
+
+
+@pytest.mark.parametrize("engine", ["rusty", "django"])
+def test_include_empty_template_name_list(engine):
+    template = engines[engine].from_string("{% include names %}")
+    with pytest.raises(TemplateDoesNotExist) as exc_info:
+        template.render({"names": []})
+    assert str(exc_info.value) == "No template names provided"
+
+
+def test_include_template_name_from_threads(template_engine):
+    template = template_engine.from_string(
+        "{% for x in items %}{{ forloop.counter }}{{ x }}{% endfor %}|{% include name %}"
+    )
+
+    def render(n):
+        context = {"items": [n] * (n % 3 + 1), "name": "basic.txt", "user": n}
+        return template.render(context)
+
+    with ThreadPoolExecutor(max_workers=8) as executor:
+        rendered = list(executor.map(render, range(64)))
+
+    expected = [
+        "".join(f"{i}{n}" for i in range(1, n % 3 + 2)) + f"|Hello {n}!\n"
+        for n in range(64)
+    ]
+    assert rendered == expected
@@ -19,6 +19,7 @@ l10n
 more_filters
 no_filters
 no_tags
+override_tags
 static
 tz"""
     rusty_message = """\
@@ -38,6 +39,7 @@ tz"""
         more_filters
         no_filters
         no_tags
+        override_tags
         static
         tz
 """
@@ -81,6 +83,33 @@ def test_unknown_filter(assert_parse_error):
     )
 
 
+def test_filter_before_load(assert_parse_error):
+    template = "{{ foo|double }}{% load custom_filters %}"
+    django_message = "Invalid filter: 'double'"
+    rusty_message = """\
+  × Invalid filter: 'double'
+   ╭────
+ 1 │ {{ foo|double }}{% load custom_filters %}
+   ·        ───┬──
+   ·           ╰── here
+   ╰────
+  help: Load it with {% load custom_filters %}
+"""
+    assert_parse_error(
+        template=template, django_message=django_message, rusty_message=rusty_message
+    )
+
+
+def test_filter_after_load(assert_render):
+    template = "{{ foo }}{% load custom_filters %}{{ foo|double }}"
+    assert_render(template=template, context={"foo": 2}, expected="24")
+
+
+def test_load_multiple_times(assert_render):
+    template = "{% load double from custom_filters %}{{ foo|double }}{% load square from more_filters %}{{ foo|square }}"
+    assert_render(template=template, context={"foo": 3}, expected="69")
+
+
 def test_load_no_filters(template_engine):
     template = "{% load no_filters %}"
 
@@ -93,3 +122,18 @@ def test_load_no_tags(template_engine):
 
     with pytest.raises(AttributeError):
         template_engine.from_string(template)
+
+
+def test_load_overrides_builtin_tag(assert_render):
+    template = "{% load override_tags %}{% url 'home' %}"
+    assert_render(template=template, context={}, expected="custom:home")
+
+
+def test_load_overrides_builtin_tag_only_after_load(assert_render):
+    template = "{% url 'home' %}{% load override_tags %}{% url 'home' %}"
+    assert_render(template=template, context={}, expected="/custom:home")
+
+
+def test_load_from_overrides_builtin_tag(assert_render):
+    template = "{% load url from override_tags %}{% url 'home' %}"
+    assert_render(template=template, context={}, expected="custom:home")
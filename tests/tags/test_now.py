@@ -0,0 +1,48 @@
+from datetime import datetime
+
+from django.test import override_settings
+from django.utils import timezone
+
+
+@override_settings(USE_TZ=True)
+def test_now_active_timezone(assert_render):
+    with timezone.override("Asia/Kolkata"):
+        assert_render('{% now "e" %}', {}, "IST")
+        assert_render('{% now "O" %}', {}, "+0530")
+
+
+@override_settings(USE_TZ=True, TIME_ZONE="America/Chicago")
+def test_now_default_timezone(assert_render):
+    assert_render('{% now "e" %}', {}, timezone.localtime().tzname())
+
+
+@override_settings(USE_TZ=False)
+def test_now_naive(assert_render):
+    assert_render('{% now "e" %}', {}, "")
+
+
+def test_now_year(assert_render):
+    assert_render('{% now "Y" %}', {}, str(datetime.now().year))
+
+
+@override_settings(USE_TZ=True)
+def test_now_as_variable(assert_render):
+    template = '{% now "e" as zone %}Zone: {{ zone }}'
+    with timezone.override("Asia/Kolkata"):
+        assert_render(template, {}, "Zone: IST")
+
+
+def test_now_missing_format(assert_parse_error):
+    template = "{% now %}"
+    django_message = "'now' statement takes one argument"
+    rusty_message = """\
+  × 'now' statement takes one argument
+   ╭────
+ 1 │ {% now %}
+   · ────┬────
+   ·     ╰── here
+   ╰────
+"""
+    assert_parse_error(
+        template=template, django_message=django_message, rusty_message=rusty_message
+    )
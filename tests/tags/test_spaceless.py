@@ -0,0 +1,51 @@
+"""
+Adapted from
+https://github.com/django/django/blob/5.1/tests/template_tests/syntax_tests/test_spaceless.py
+"""
+
+from django.utils.safestring import mark_safe
+
+
+def test_spaceless01(assert_render):
+    template = "{% spaceless %} <b>    <i> text </i>    </b> {% endspaceless %}"
+    assert_render(template, {}, "<b><i> text </i></b>")
+
+
+def test_spaceless02(assert_render):
+    template = "{% spaceless %} <b> \n <i> text </i> \n </b> {% endspaceless %}"
+    assert_render(template, {}, "<b><i> text </i></b>")
+
+
+def test_spaceless03(assert_render):
+    template = "{% spaceless %}<b><i>text</i></b>{% endspaceless %}"
+    assert_render(template, {}, "<b><i>text</i></b>")
+
+
+def test_spaceless04(assert_render):
+    template = "{% spaceless %}<b>   <i>{{ text }}</i>  </b>{% endspaceless %}"
+    assert_render(template, {"text": "This & that"}, "<b><i>This &amp; that</i></b>")
+
+
+def test_spaceless05(assert_render):
+    template = (
+        "{% autoescape off %}{% spaceless %}"
+        "<b>   <i>{{ text }}</i>  </b>"
+        "{% endspaceless %}{% endautoescape %}"
+    )
+    assert_render(template, {"text": "This & that"}, "<b><i>This & that</i></b>")
+
+
+def test_spaceless06(assert_render):
+    template = "{% spaceless %}<b>   <i>{{ text|safe }}</i>  </b>{% endspaceless %}"
+    assert_render(template, {"text": "This & that"}, "<b><i>This & that</i></b>")
+
+
+def test_spaceless_variable_whitespace(assert_render):
+    template = "{% spaceless %}<div>{{ html }}</div>{% endspaceless %}"
+    html = mark_safe("  <p>  <span>x</span>\n  </p>  ")
+    assert_render(template, {"html": html}, "<div><p><span>x</span></p></div>")
+
+
+def test_spaceless_keeps_text_whitespace(assert_render):
+    template = "{% spaceless %}<p>{{ text }}</p> {{ text }} {% endspaceless %}"
+    assert_render(template, {"text": "a  b"}, "<p>a  b</p> a  b")
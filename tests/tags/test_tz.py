@@ -0,0 +1,116 @@
+import zoneinfo
+
+import pytest
+from django.template import engines
+from django.test import override_settings
+from django.utils import timezone
+
+
+@override_settings(USE_TZ=True)
+def test_timezone_now(assert_render):
+    template = '{% load tz %}{% timezone "Asia/Kolkata" %}{% now "e O" %}{% endtimezone %}'
+    assert_render(template, {}, "IST +0530")
+
+
+@override_settings(USE_TZ=True)
+def test_timezone_variable(assert_render):
+    template = '{% load tz %}{% timezone tz %}{% now "e" %}{% endtimezone %}'
+    assert_render(template, {"tz": "Asia/Kolkata"}, "IST")
+
+
+@override_settings(USE_TZ=True)
+def test_timezone_restored(assert_render):
+    template = (
+        '{% load tz %}{% now "e" %}|'
+        '{% timezone "Asia/Kolkata" %}{% now "e" %}{% endtimezone %}|'
+        '{% now "e" %}'
+    )
+    with timezone.override("America/New_York"):
+        expected = timezone.localtime().tzname()
+        assert_render(template, {}, f"{expected}|IST|{expected}")
+        assert timezone.get_current_timezone_name() == "America/New_York"
+
+
+@override_settings(USE_TZ=True)
+def test_timezone_nested(assert_render):
+    template = (
+        '{% load tz %}{% timezone "Asia/Kolkata" %}'
+        '{% timezone "UTC" %}{% now "e" %}{% endtimezone %}|{% now "e" %}'
+        "{% endtimezone %}"
+    )
+    assert_render(template, {}, "UTC|IST")
+
+
+@override_settings(USE_TZ=True)
+def test_get_current_timezone(assert_render):
+    template = "{% load tz %}{% get_current_timezone as TZ %}{{ TZ }}"
+    with timezone.override("America/New_York"):
+        assert_render(template, {}, "America/New_York")
+
+
+@override_settings(USE_TZ=True)
+def test_get_current_timezone_in_timezone(assert_render):
+    template = (
+        '{% load tz %}{% timezone "Asia/Kolkata" %}'
+        "{% get_current_timezone as TZ %}{% endtimezone %}{{ TZ }}"
+    )
+    assert_render(template, {}, "Asia/Kolkata")
+
+
+def test_timezone_no_argument(assert_parse_error):
+    template = "{% load tz %}{% timezone %}{% endtimezone %}"
+    django_message = "'timezone' takes one argument (timezone)"
+    rusty_message = """\
+  × 'timezone' takes one argument (timezone)
+   ╭────
+ 1 │ {% load tz %}{% timezone %}{% endtimezone %}
+   ·              ───────┬──────
+   ·                     ╰── here
+   ╰────
+"""
+    assert_parse_error(
+        template=template, django_message=django_message, rusty_message=rusty_message
+    )
+
+
+def test_timezone_missing_end_tag(assert_parse_error):
+    template = '{% load tz %}{% timezone "UTC" %}'
+    django_message = "Unclosed tag on line 1: 'timezone'. Looking for one of: endtimezone."
+    rusty_message = """\
+  × Unclosed 'timezone' tag. Looking for one of: endtimezone
+   ╭────
+ 1 │ {% load tz %}{% timezone "UTC" %}
+   ·              ──────────┬─────────
+   ·                        ╰── started here
+   ╰────
+"""
+    assert_parse_error(
+        template=template, django_message=django_message, rusty_message=rusty_message
+    )
+
+
+def test_get_current_timezone_missing_as(assert_parse_error):
+    template = "{% load tz %}{% get_current_timezone TZ %}"
+    django_message = "'get_current_timezone' requires 'as variable' (got ['get_current_timezone', 'TZ'])"
+    rusty_message = """\
+  × 'get_current_timezone' requires 'as variable' (got
+  │ ['get_current_timezone', 'TZ'])
+   ╭────
+ 1 │ {% load tz %}{% get_current_timezone TZ %}
+   ·              ──────────────┬──────────────
+   ·                            ╰── here
+   ╰────
+"""
+    assert_parse_error(
+        template=template, django_message=django_message, rusty_message=rusty_message
+    )
+
+
+@pytest.mark.parametrize("engine", ["rusty", "django"])
+@override_settings(USE_TZ=True)
+def test_timezone_invalid(engine):
+    template = engines[engine].from_string(
+        "{% load tz %}{% timezone tz %}{% endtimezone %}"
+    )
+    with pytest.raises(zoneinfo.ZoneInfoNotFoundError):
+        template.render({"tz": "Not/AZone"})
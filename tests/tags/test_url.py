@@ -47,6 +47,65 @@ def test_render_url_kwarg(assert_render):
     assert_render(template, {}, expected)
 
 
+def test_render_url_missing_arg():
+    template = "{% url 'bio' user %}"
+
+    django_template = engines["django"].from_string(template)
+    rust_template = engines["rusty"].from_string(template)
+
+    with pytest.raises(NoReverseMatch) as django_error:
+        django_template.render({})
+
+    msg = "Reverse for 'bio' with arguments '('',)' not found. 1 pattern(s) tried: ['bio/(?P<username>[^/]+)/\\\\Z']"
+    assert django_error.value.args[0] == msg
+
+    with pytest.raises(NoReverseMatch) as rust_error:
+        rust_template.render({})
+
+    expected = """\
+  × Reverse for 'bio' with arguments '('',)' not found. 1 pattern(s) tried:
+  │ ['bio/(?P<username>[^/]+)/\\\\Z']
+   ╭────
+ 1 │ {% url 'bio' user %}
+   ·              ──┬─
+   ·                ╰── this variable is missing
+   ╰────
+"""
+    assert str(rust_error.value) == expected
+
+
+def test_render_url_missing_kwarg():
+    template = "{% url 'bio' username=user %}"
+
+    django_template = engines["django"].from_string(template)
+    rust_template = engines["rusty"].from_string(template)
+
+    with pytest.raises(NoReverseMatch) as django_error:
+        django_template.render({})
+
+    msg = "Reverse for 'bio' with keyword arguments '{'username': ''}' not found. 1 pattern(s) tried: ['bio/(?P<username>[^/]+)/\\\\Z']"
+    assert django_error.value.args[0] == msg
+
+    with pytest.raises(NoReverseMatch) as rust_error:
+        rust_template.render({})
+
+    expected = """\
+  × Reverse for 'bio' with keyword arguments '{'username': ''}' not found. 1
+  │ pattern(s) tried: ['bio/(?P<username>[^/]+)/\\\\Z']
+   ╭────
+ 1 │ {% url 'bio' username=user %}
+   ·                       ──┬─
+   ·                         ╰── this variable is missing
+   ╰────
+"""
+    assert str(rust_error.value) == expected
+
+
+def test_render_url_missing_arg_as_variable(assert_render):
+    template = "{% url 'bio' user as bio %}[{{ bio }}]"
+    assert_render(template, {}, "[]")
+
+
 def test_render_url_arg_as_variable(assert_render):
     template = "{% url 'bio' 'lily' as bio %}https://example.com{{ bio }}"
     expected = "https://example.com/bio/lily/"
@@ -0,0 +1,81 @@
+"""
+Adapted from
+https://github.com/django/django/blob/5.1/tests/template_tests/syntax_tests/test_verbatim.py
+"""
+
+
+def test_verbatim_variable(assert_render):
+    template = "{% verbatim %}{{bare   }}{% endverbatim %}"
+    assert_render(template=template, context={}, expected="{{bare   }}")
+
+
+def test_verbatim_tag(assert_render):
+    template = "{% verbatim %}{% endif %}{% endverbatim %}"
+    assert_render(template=template, context={}, expected="{% endif %}")
+
+
+def test_verbatim_verbatim_tag(assert_render):
+    template = "{% verbatim %}It's the {% verbatim %} tag{% endverbatim %}"
+    assert_render(template=template, context={}, expected="It's the {% verbatim %} tag")
+
+
+def test_verbatim_adjacent(assert_render):
+    template = "{% verbatim %}{% endverbatim %}{% verbatim %}{% endverbatim %}"
+    assert_render(template=template, context={}, expected="")
+
+
+def test_verbatim_named(assert_render):
+    template = (
+        "{% verbatim special %}Don't {% endverbatim %} just yet{% endverbatim special %}"
+    )
+    assert_render(template=template, context={}, expected="Don't {% endverbatim %} just yet")
+
+
+def test_verbatim_named_containing_unnamed(assert_render):
+    template = "{% verbatim outer %}{% verbatim %}{{ x }}{% endverbatim %}{% endverbatim outer %}"
+    expected = "{% verbatim %}{{ x }}{% endverbatim %}"
+    assert_render(template=template, context={"x": 1}, expected=expected)
+
+
+def test_verbatim_named_wrong_end(assert_render):
+    template = "{% verbatim a %}{% endverbatim b %}{% endverbatim a %}"
+    assert_render(template=template, context={}, expected="{% endverbatim b %}")
+
+
+def test_verbatim_invalid_syntax(assert_render):
+    template = "{% verbatim %}{{ }}{% bogus %}{{ a|}}{% endverbatim %}"
+    expected = "{{ }}{% bogus %}{{ a|}}"
+    assert_render(template=template, context={}, expected=expected)
+
+
+def test_verbatim_unnamed_nesting(assert_parse_error):
+    # Like Django, the first `endverbatim` closes the outer unnamed block.
+    template = "{% verbatim %}{% verbatim %}{% endverbatim %}{% endverbatim %}"
+    django_message = "Invalid block tag on line 1: 'endverbatim'. Did you forget to register or load this tag?"
+    rusty_message = """\
+  × Unexpected tag endverbatim
+   ╭────
+ 1 │ {% verbatim %}{% verbatim %}{% endverbatim %}{% endverbatim %}
+   ·                                              ────────┬────────
+   ·                                                      ╰── unexpected tag
+   ╰────
+"""
+    assert_parse_error(
+        template=template, django_message=django_message, rusty_message=rusty_message
+    )
+
+
+def test_verbatim_unclosed(assert_parse_error):
+    template = "{% verbatim %}foo"
+    django_message = "Unclosed tag on line 1: 'verbatim'. Looking for one of: endverbatim."
+    rusty_message = """\
+  × Unclosed 'verbatim' tag. Looking for one of: endverbatim
+   ╭────
+ 1 │ {% verbatim %}foo
+   · ───────┬──────
+   ·        ╰── started here
+   ╰────
+"""
+    assert_parse_error(
+        template=template, django_message=django_message, rusty_message=rusty_message
+    )
@@ -0,0 +1,89 @@
+"""
+Adapted from
+https://github.com/django/django/blob/5.1/tests/template_tests/syntax_tests/test_width_ratio.py
+"""
+
+import pytest
+from django.template import TemplateSyntaxError
+
+
+@pytest.mark.parametrize(
+    "template,context,expected",
+    [
+        ("{% widthratio a b 0 %}", {"a": 50, "b": 100}, "0"),
+        ("{% widthratio a b 100 %}", {"a": 0, "b": 0}, "0"),
+        ("{% widthratio a b 100 %}", {"a": 0, "b": 100}, "0"),
+        ("{% widthratio a b 100 %}", {"a": 50, "b": 100}, "50"),
+        ("{% widthratio a b 100 %}", {"a": 100, "b": 100}, "100"),
+        ("{% widthratio a b 100 %}", {"a": 50, "b": 80}, "62"),
+        ("{% widthratio a b 100 %}", {"a": 50, "b": 70}, "71"),
+        ("{% widthratio a b 100.0 %}", {"a": 50, "b": 100}, "50"),
+        ("{% widthratio a b c %}", {"a": 50, "b": 100, "c": 100}, "50"),
+        ("{% widthratio a b c %}", {"a": "a", "b": 100, "c": 100}, ""),
+        ("{% widthratio a b c %}", {"a": None, "b": 100, "c": 100}, ""),
+        ("{% widthratio a b c %}", {"a": 0, "b": "b", "c": 100}, ""),
+        ("{% widthratio a b c %}", {"a": 0, "b": None, "c": 100}, ""),
+        ("{% widthratio a b 100 %}", {"a": float("inf"), "b": float("inf")}, ""),
+        ("{% widthratio a b 100 %}", {"a": float("inf"), "b": 2}, ""),
+        ("{% widthratio a b 100 %}", {"a": 10**400, "b": 1}, ""),
+        ("{% widthratio a b 100 %}", {"a": 1, "b": 10**400}, ""),
+        ("{% widthratio a b c %}", {"a": 1, "b": 2, "c": 10**400}, ""),
+        ("{% widthratio 175 200 100 %}", {}, "88"),
+        # Like Python's round, ties round to the nearest even number.
+        ("{% widthratio 1 2 1 %}", {}, "0"),
+        ("{% widthratio 3 2 1 %}", {}, "2"),
+    ],
+)
+def test_widthratio(assert_render, template, context, expected):
+    assert_render(template, context, expected)
+
+
+def test_widthratio_as(assert_render):
+    template = "{% widthratio a b 100 as variable %}-{{ variable }}-"
+    assert_render(template, {"a": 50, "b": 100}, "-50-")
+    assert_render(template, {"a": 100, "b": 100}, "-100-")
+
+
+def test_widthratio_as_tie(assert_render):
+    template = "{% widthratio 1 2 3 as variable %}-{{ variable }}-"
+    assert_render(template, {}, "-2-")
+
+
+@pytest.mark.parametrize("max_width", ["c", None])
+def test_widthratio_invalid_max_width(template_engine, max_width):
+    template = template_engine.from_string("{% widthratio a b c %}")
+    with pytest.raises(TemplateSyntaxError) as exc_info:
+        template.render({"a": 50, "b": 100, "c": max_width})
+    assert str(exc_info.value) == "widthratio final argument must be a number"
+
+
+def test_widthratio_no_arguments(assert_parse_error):
+    template = "{% widthratio %}"
+    django_message = "widthratio takes at least three arguments"
+    rusty_message = """\
+  × widthratio takes at least three arguments
+   ╭────
+ 1 │ {% widthratio %}
+   · ────────┬───────
+   ·         ╰── here
+   ╰────
+"""
+    assert_parse_error(
+        template=template, django_message=django_message, rusty_message=rusty_message
+    )
+
+
+def test_widthratio_missing_as(assert_parse_error):
+    template = "{% widthratio a b 100 not_as variable %}"
+    django_message = "Invalid syntax in widthratio tag. Expecting 'as' keyword"
+    rusty_message = """\
+  × Invalid syntax in widthratio tag. Expecting 'as' keyword
+   ╭────
+ 1 │ {% widthratio a b 100 not_as variable %}
+   ·                       ───┬──
+   ·                          ╰── here
+   ╰────
+"""
+    assert_parse_error(
+        template=template, django_message=django_message, rusty_message=rusty_message
+    )
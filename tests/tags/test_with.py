@@ -0,0 +1,101 @@
+def test_with(assert_render):
+    template = "{% with greeting='Hello' %}{{ greeting }} {{ name }}{% endwith %}"
+    assert_render(template=template, context={"name": "Lily"}, expected="Hello Lily")
+
+
+def test_with_restores_shadowed_variable(assert_render):
+    template = "{% with x=20 %}{{ x }}{% endwith %}{{ x }}"
+    assert_render(template=template, context={"x": 10}, expected="2010")
+
+
+def test_with_nested_shadowing(assert_render):
+    template = (
+        "{% with x=20 %}{% with x=30 %}{{ x }}{% endwith %}{{ x }}{% endwith %}{{ x }}"
+    )
+    assert_render(template=template, context={"x": 10}, expected="302010")
+
+
+def test_with_removes_new_variable(assert_render):
+    template = "{% with y=1 %}{{ y }}{% endwith %}{{ y }}"
+    assert_render(template=template, context={}, expected="1")
+
+
+def test_with_multiple(assert_render):
+    template = "{% with a=1 b=x|upper %}{{ a }}{{ b }}{% endwith %}"
+    assert_render(template=template, context={"x": "x"}, expected="1X")
+
+
+def test_with_uses_outer_values(assert_render):
+    template = "{% with x=y y=x %}{{ x }}{{ y }}{% endwith %}"
+    assert_render(template=template, context={"x": 1, "y": 2}, expected="21")
+
+
+def test_with_legacy_format(assert_render):
+    template = "{% with x as y and 2 as z %}{{ y }}{{ z }}{% endwith %}"
+    assert_render(template=template, context={"x": 1}, expected="12")
+
+
+def test_with_inside_for_loop(assert_render):
+    template = (
+        "{% for x in xs %}{% with y=x %}{{ y }}{% endwith %}{{ y }}{% endfor %}{{ x }}"
+    )
+    context = {"xs": [1, 2], "x": "outer", "y": "-"}
+    assert_render(template=template, context=context, expected="1-2-outer")
+
+
+def test_with_missing_variable(assert_render):
+    template = "{% with y=missing %}[{{ y }}]{% endwith %}"
+    assert_render(template=template, context={}, expected="[]")
+
+
+def test_with_no_assignment(assert_parse_error):
+    template = "{% with %}{% endwith %}"
+    django_message = "'with' expected at least one variable assignment"
+    rusty_message = """\
+  × 'with' expected at least one variable assignment
+   ╭────
+ 1 │ {% with %}{% endwith %}
+   · ─────┬────
+   ·      ╰── here
+   ╰────
+"""
+    assert_parse_error(
+        template=template, django_message=django_message, rusty_message=rusty_message
+    )
+
+
+def test_with_invalid_token(assert_parse_error):
+    template = "{% with x=1 y %}{% endwith %}"
+    django_message = "'with' received an invalid token: 'y'"
+    rusty_message = """\
+  × 'with' received an invalid token: 'y'
+   ╭────
+ 1 │ {% with x=1 y %}{% endwith %}
+   ·             ┬
+   ·             ╰── here
+   ╰────
+"""
+    assert_parse_error(
+        template=template, django_message=django_message, rusty_message=rusty_message
+    )
+
+
+def test_with_missing_end_tag(assert_parse_error):
+    template = "{% with x=1 %}"
+    django_message = "Unclosed tag on line 1: 'with'. Looking for one of: endwith."
+    rusty_message = """\
+  × Unclosed 'with' tag. Looking for one of: endwith
+   ╭────
+ 1 │ {% with x=1 %}
+   · ───────┬──────
+   ·        ╰── started here
+   ╰────
+"""
+    assert_parse_error(
+        template=template, django_message=django_message, rusty_message=rusty_message
+    )
+
+
+def test_with_reserved_name(assert_render):
+    template = "{% with None=1 %}{{ None }}{% endwith %}{{ None }}"
+    assert_render(template=template, context={}, expected="1None")
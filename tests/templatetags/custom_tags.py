@@ -14,6 +14,11 @@ def multiply(a, b, c):
     return a * b * c
 
 
+@register.simple_tag
+def scaled_sum(a, b, *, c=1):
+    return (a + b) * c
+
+
 @register.simple_tag
 def invert(value=2):
     return 1 / value
@@ -0,0 +1,19 @@
+from django import template
+
+
+register = template.Library()
+
+
+@register.simple_tag
+def url(name):
+    return f"custom:{name}"
+
+
+@register.filter
+def json(value):
+    return f"custom:{value}"
+
+
+@register.filter
+def unicode_slugify(value):
+    return f"custom:{value}"
@@ -1,3 +1,4 @@
+from django.utils.html import html_safe
 from django.utils.safestring import mark_safe
 
 
@@ -78,3 +79,27 @@ def test_safe_lower(assert_render):
     template = "{{ html|safe|lower }}"
     expected = "<p>hello world!</p>"
     assert_render(template=template, context={"html": html}, expected=expected)
+
+
+def test_html_safe_object(assert_render):
+    @html_safe
+    class Widget:
+        def __str__(self):
+            return '<input name="a&b">'
+
+    template = "{{ widget }}|{{ widget|escape }}"
+    expected = '<input name="a&b">|<input name="a&b">'
+    assert_render(template=template, context={"widget": Widget()}, expected=expected)
+
+
+def test_html_method_escape_filter(assert_render):
+    class Widget:
+        def __html__(self):
+            return "<input>"
+
+        def __str__(self):
+            return "<widget>"
+
+    template = "{{ widget }}|{{ widget|escape }}"
+    expected = "&lt;widget&gt;|<input>"
+    assert_render(template=template, context={"widget": Widget()}, expected=expected)
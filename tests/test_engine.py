@@ -1,3 +1,4 @@
+import io
 from pathlib import Path
 
 import pytest
@@ -5,8 +6,9 @@ from django.conf import settings
 from django.core.exceptions import ImproperlyConfigured
 from django.template import engines, Context
 from django.template.engine import Engine
+from django.template.base import VariableDoesNotExist
 from django.template.library import InvalidTemplateLibrary
-from django.template.exceptions import TemplateDoesNotExist
+from django.template.exceptions import TemplateDoesNotExist, TemplateSyntaxError
 from django_rusty_templates import RustyTemplates
 
 
@@ -74,6 +76,62 @@ def test_pathlib_dirs():
     assert template.render(context) == expected
 
 
+def test_template_source_from_string():
+    template = engines["rusty"].from_string("Hello {{ user }}!")
+    assert template.source == "Hello {{ user }}!"
+    assert template.origin == "<unknown source>"
+
+
+def test_render_with_timings():
+    template = engines["rusty"].from_string("{% for x in xs %}{{ x }}{% endfor %}!{{ a }}")
+    rendered, timings = template.render_with_timings({"xs": [1, 2], "a": "b"})
+    assert rendered == "12!b"
+    assert list(timings) == [0, 1, 2]
+    assert all(seconds >= 0 for seconds in timings.values())
+
+
+def test_stream():
+    template = engines["rusty"].from_string("{% for x in xs %}{{ x }}{% endfor %}!{{ a }}")
+    context = {"xs": [1, 2], "a": "b"}
+    out = io.StringIO()
+    assert template.stream(context, out) is None
+    assert out.getvalue() == template.render(context) == "12!b"
+
+
+def test_stream_writes_each_node():
+    template = engines["rusty"].from_string("a{{ b }}{% if c %}{% endif %}d")
+    chunks = []
+
+    class Out:
+        def write(self, chunk):
+            chunks.append(chunk)
+
+    template.stream({"b": "B"}, Out())
+    assert chunks == ["a", "B", "d"]
+
+
+def test_stream_write_error():
+    template = engines["rusty"].from_string("{{ a }}")
+
+    class Out:
+        def write(self, chunk):
+            raise OSError("disk full")
+
+    with pytest.raises(OSError, match="disk full"):
+        template.stream({"a": "b"}, Out())
+
+
+def test_template_debug_source():
+    template = engines["rusty"].from_string("{{ foo|upper }}Hello{%if x%}y{% endif %}")
+    assert template.debug_source() == "{{ foo|upper }}Hello{% if x %}y{% endif %}"
+
+
+def test_template_source_get_template():
+    template = engines["rusty"].get_template("basic.txt")
+    assert template.source == "Hello {{ user }}!\n"
+    assert template.origin == str(Path(settings.BASE_DIR) / "templates" / "basic.txt")
+
+
 def test_select_template_first_exists():
     template = engines["rusty"].engine.select_template(
         ["basic.txt", "full_example.html"]
@@ -339,3 +397,205 @@ def test_invalid_loader_configurations(loaders, error_message):
         )
 
     assert error_message == str(exc_info.value)
+
+
+def rusty_engine(**options):
+    return RustyTemplates(
+        {"OPTIONS": options, "NAME": "rust", "DIRS": [], "APP_DIRS": False}
+    )
+
+
+def test_autoescape_default():
+    template = rusty_engine().from_string("{{ html }}")
+    assert template.render({"html": "<p>"}) == "&lt;p&gt;"
+
+
+def test_autoescape_engine_option():
+    template = rusty_engine(autoescape=False).from_string("{{ html }}")
+    assert template.render({"html": "<p>"}) == "<p>"
+
+
+@pytest.mark.parametrize("engine_autoescape", [True, False])
+@pytest.mark.parametrize(
+    "template_autoescape,expected", [(True, "&lt;p&gt;"), (False, "<p>")]
+)
+def test_autoescape_template_override(
+    engine_autoescape, template_autoescape, expected
+):
+    engine = rusty_engine(autoescape=engine_autoescape)
+    template = engine.from_string("{{ html }}", autoescape=template_autoescape)
+    assert template.render({"html": "<p>"}) == expected
+
+
+@pytest.mark.parametrize(
+    "template", ["Hello {{ name", "{% if x", "{# comment", "{{ a\n}}"]
+)
+def test_unclosed_tag_is_text_by_default(assert_render, template):
+    assert_render(template=template, context={}, expected=template)
+
+
+def test_unclosed_variable_error():
+    engine = rusty_engine(unclosed_tag_errors=True)
+    with pytest.raises(TemplateSyntaxError) as exc_info:
+        engine.from_string("Hello {{ name")
+
+    assert str(exc_info.value) == """\
+  × Unclosed variable, expected '}}'
+   ╭────
+ 1 │ Hello {{ name
+   ·       ─┬
+   ·        ╰── started here
+   ╰────
+"""
+
+
+def test_unclosed_tag_error():
+    engine = rusty_engine(unclosed_tag_errors=True)
+    with pytest.raises(TemplateSyntaxError) as exc_info:
+        engine.from_string("{% if x %}{% if y")
+
+    assert str(exc_info.value) == """\
+  × Unclosed tag, expected '%}'
+   ╭────
+ 1 │ {% if x %}{% if y
+   ·           ─┬
+   ·            ╰── started here
+   ╰────
+"""
+
+
+def test_unclosed_comment_error():
+    engine = rusty_engine(unclosed_tag_errors=True)
+    with pytest.raises(TemplateSyntaxError) as exc_info:
+        engine.from_string("{# comment")
+
+    assert str(exc_info.value) == """\
+  × Unclosed comment, expected '#}'
+   ╭────
+ 1 │ {# comment
+   · ─┬
+   ·  ╰── started here
+   ╰────
+"""
+
+
+def test_max_parse_depth():
+    engine = rusty_engine(max_parse_depth=2)
+    template = "{% if a %}{% if b %}x{% endif %}{% endif %}"
+    assert engine.from_string(template).render({"a": 1, "b": 1}) == "x"
+
+    with pytest.raises(TemplateSyntaxError) as exc_info:
+        engine.from_string(
+            "{% if a %}{% if b %}{% if c %}{% endif %}{% endif %}{% endif %}"
+        )
+
+    assert str(exc_info.value) == """\
+  × Block tags are nested more than 2 levels deep
+   ╭────
+ 1 │ {% if a %}{% if b %}{% if c %}{% endif %}{% endif %}{% endif %}
+   ·                     ─────┬────
+   ·                          ╰── here
+   ╰────
+"""
+
+
+def test_default_max_parse_depth():
+    depth = 10_000
+    template = "{% if a %}" * depth + "{% endif %}" * depth
+    with pytest.raises(TemplateSyntaxError) as exc_info:
+        rusty_engine().from_string(template)
+
+    assert "Block tags are nested more than 128 levels deep" in str(exc_info.value)
+
+
+def test_undefined_variable_lenient_by_default():
+    template = rusty_engine().from_string("Hello {{ missing }}{{ user.name }}!")
+    assert template.render({"user": {}}) == "Hello !"
+
+
+def test_strict_variables():
+    engine = rusty_engine(strict_variables=True)
+    template = engine.from_string("Hello {{ missing }}!")
+    with pytest.raises(VariableDoesNotExist) as exc_info:
+        template.render({})
+
+    assert str(exc_info.value) == """\
+  × Failed lookup for key [missing] in {"False": False, "None": None, "True":
+  │ True}
+   ╭────
+ 1 │ Hello {{ missing }}!
+   ·          ───┬───
+   ·             ╰── key
+   ╰────
+"""
+
+
+def test_strict_variables_attribute():
+    engine = rusty_engine(strict_variables=True)
+    template = engine.from_string("{{ user.name }}")
+    assert template.render({"user": {"name": "Lily"}}) == "Lily"
+    with pytest.raises(VariableDoesNotExist):
+        template.render({"user": {}})
+
+
+def test_strict_variables_if():
+    engine = rusty_engine(strict_variables=True)
+    template = engine.from_string("{% if missing %}yes{% else %}no{% endif %}")
+    assert template.render({"missing": False}) == "no"
+    with pytest.raises(VariableDoesNotExist):
+        template.render({})
+
+
+@pytest.mark.parametrize(
+    "condition",
+    ["not missing", "x or missing", "missing == 1", "x in missing", "missing is None"],
+)
+def test_strict_variables_if_operators(condition):
+    engine = rusty_engine(strict_variables=True)
+    template = engine.from_string(f"{{% if {condition} %}}yes{{% endif %}}")
+    with pytest.raises(VariableDoesNotExist):
+        template.render({"x": 0})
+
+
+def locmem_engine(*loaders):
+    return RustyTemplates(
+        {
+            "OPTIONS": {
+                "loaders": [
+                    ("django.template.loaders.locmem.Loader", templates)
+                    for templates in loaders
+                ]
+            },
+            "NAME": "rust",
+            "DIRS": [],
+            "APP_DIRS": False,
+        }
+    )
+
+
+def test_locmem_loader_missing_template():
+    engine = locmem_engine({"index.html": "index"}, {"other.html": "other"})
+
+    with pytest.raises(TemplateDoesNotExist) as exc_info:
+        engine.get_template("missing.html")
+
+    assert str(exc_info.value) == "missing.html"
+    assert exc_info.value.tried == [
+        ("missing.html", "Source does not exist"),
+        ("missing.html", "Source does not exist"),
+    ]
+
+
+def test_locmem_loader_case_sensitive():
+    engine = locmem_engine({"index.html": "index"})
+
+    with pytest.raises(TemplateDoesNotExist):
+        engine.get_template("Index.html")
+
+    assert engine.get_template("index.html").render({}) == "index"
+
+
+def test_locmem_loader_second_loader():
+    engine = locmem_engine({"index.html": "first"}, {"other.html": "Hi {{ user }}"})
+
+    assert engine.get_template("other.html").render({"user": "Lily"}) == "Hi Lily"
@@ -0,0 +1,65 @@
+import re
+
+from django.test import RequestFactory
+
+factory = RequestFactory()
+
+
+def test_request_path(assert_render):
+    request = factory.get("/blog/")
+    assert_render(
+        template="{{ request.path }}", context={}, request=request, expected="/blog/"
+    )
+
+
+def test_request_get(assert_render):
+    request = factory.get("/blog/?page=2")
+    assert_render(
+        template="{{ request.GET.page }}", context={}, request=request, expected="2"
+    )
+
+
+def test_request_meta(assert_render):
+    request = factory.get("/", HTTP_HOST="example.com")
+    assert_render(
+        template="{{ request.META.HTTP_HOST }}",
+        context={},
+        request=request,
+        expected="example.com",
+    )
+
+
+def test_request_without_request(assert_render):
+    assert_render(template="[{{ request }}]", context={}, expected="[]")
+
+
+def test_request_context_takes_precedence(assert_render):
+    request = factory.get("/blog/")
+    assert_render(
+        template="{{ request }}",
+        context={"request": "mine"},
+        request=request,
+        expected="mine",
+    )
+
+
+def test_csrf_token(template_engine):
+    request = factory.get("/")
+    template = template_engine.from_string("{% csrf_token %}")
+    rendered = template.render({}, request)
+    assert re.fullmatch(
+        r'<input type="hidden" name="csrfmiddlewaretoken" value="[a-zA-Z0-9]{64}">',
+        rendered,
+    )
+
+
+def test_csrf_token_variable(template_engine):
+    request = factory.get("/")
+    template = template_engine.from_string("{% csrf_token %}|{{ csrf_token }}")
+    rendered = template.render({}, request)
+    tag, token = rendered.split("|")
+    assert tag.endswith(f'value="{token}">')
+
+
+def test_csrf_token_without_request(assert_render):
+    assert_render(template="{% csrf_token %}", context={}, expected="")
@@ -1,3 +1,10 @@
+from collections import defaultdict
+from collections.abc import Mapping
+
+import pytest
+from django.template import engines
+
+
 def test_render_variable(assert_render):
     template = "{{ foo }}"
     assert_render(template=template, context={"foo": 3}, expected="3")
@@ -134,6 +141,47 @@ def test_render_callable_do_not_call_in_templates_attribute(assert_render):
     assert_render(template=template, context=context, expected="attribute")
 
 
+class Manager:
+    do_not_call_in_templates = True
+
+    def __call__(self):
+        raise AssertionError("Managers should not be called in templates")
+
+    def count(self):
+        return 3
+
+    def __str__(self):
+        return "manager"
+
+
+def build():
+    return "built"
+
+
+build.do_not_call_in_templates = True
+
+
+class MyModel:
+    objects = Manager()
+    build = staticmethod(build)
+
+
+def test_render_do_not_call_in_templates_manager(assert_render):
+    template = "{{ MyModel.objects }}"
+    assert_render(template=template, context={"MyModel": MyModel}, expected="manager")
+
+
+def test_render_do_not_call_in_templates_manager_method(assert_render):
+    template = "{{ MyModel.objects.count }}"
+    assert_render(template=template, context={"MyModel": MyModel}, expected="3")
+
+
+def test_render_do_not_call_in_templates_method(assert_render):
+    template = "{% if model.build == build %}same{% endif %}"
+    context = {"model": MyModel(), "build": build}
+    assert_render(template=template, context=context, expected="same")
+
+
 def test_render_callable_attribute_alters_data(assert_render):
     template = "{{ foo.increment }}"
     mutable = AltersData()
@@ -156,3 +204,102 @@ def test_do_not_call_and_alters_data(assert_render):
     context = {"foo": both}
     assert_render(template=template, context=context, expected="0")
     assert both.data == 0
+
+
+class Settings(Mapping):
+    def __init__(self, **values):
+        self.values = values
+        self.lookups = []
+
+    def __getitem__(self, key):
+        self.lookups.append(key)
+        return self.values[key]
+
+    def __iter__(self):
+        return iter(self.values)
+
+    def __len__(self):
+        return len(self.values)
+
+
+def test_render_mapping_subclass(assert_render):
+    template = "{{ settings.theme }}"
+    settings = Settings(theme="dark")
+    assert_render(template=template, context={"settings": settings}, expected="dark")
+    assert settings.lookups == ["theme"]
+
+
+def test_render_mapping_subclass_falls_back_to_attribute(assert_render):
+    template = "{{ settings.values.theme }}"
+    settings = Settings(theme="dark")
+    assert_render(template=template, context={"settings": settings}, expected="dark")
+    assert settings.lookups == ["values"]
+
+
+def test_render_defaultdict(assert_render):
+    template = "{{ counts.missing }}"
+    counts = defaultdict(lambda: "default")
+    assert_render(template=template, context={"counts": counts}, expected="default")
+    assert "missing" in counts
+
+
+class Broken:
+    def __getitem__(self, key):
+        raise RuntimeError("broken")
+
+
+def test_render_getitem_error_propagates(template_engine):
+    template = template_engine.from_string("{{ broken.key }}")
+    with pytest.raises(RuntimeError) as exc_info:
+        template.render({"broken": Broken()})
+
+    assert str(exc_info.value) == "broken"
+
+
+class Dynamic:
+    def __getattr__(self, name):
+        if name.startswith("missing"):
+            raise AttributeError(name)
+        if name.startswith("method"):
+            return lambda: f"called {name}"
+        return f"computed {name}"
+
+
+def test_render_dynamic_attribute(assert_render):
+    template = "{{ obj.anything }}|{{ obj.missing }}|{{ obj.method }}|{{ obj.anything|upper }}"
+    assert_render(
+        template=template,
+        context={"obj": Dynamic()},
+        expected="computed anything||called method|COMPUTED ANYTHING",
+    )
+
+
+def test_render_dynamic_attribute_in_if(assert_render):
+    template = "{% if obj.missing %}yes{% elif obj.other %}other{% endif %}"
+    assert_render(template=template, context={"obj": Dynamic()}, expected="other")
+
+
+def test_render_lone_surrogate():
+    # Django returns the surrogate unchanged, but it can't be represented in
+    # a Rust string, so it is replaced with U+FFFD instead.
+    template = engines["rusty"].from_string("{{ value }}")
+    value = "a" + chr(0xD800) + "b"
+    assert template.render({"value": value}) == "a\ufffdb"
+
+
+def test_render_bool(assert_render):
+    template = "{{ yes }} {{ no }} {{ True }} {{ False }}"
+    assert_render(
+        template=template,
+        context={"yes": True, "no": False},
+        expected="True False True False",
+    )
+
+
+def test_render_bool_filtered(assert_render):
+    template = "{{ yes|default:'x' }} {{ no|default_if_none:'x' }} {{ yes|lower }}"
+    assert_render(
+        template=template,
+        context={"yes": True, "no": False},
+        expected="True False true",
+    )